@@ -2,12 +2,21 @@ use anyhow::{anyhow, Result};
 use clap::{Parser, Subcommand};
 use crossterm::event::KeyCode;
 use crossterm::style::Color;
+use rand::{rngs::StdRng, SeedableRng};
 use shape_engine_core::{
-    load_config, render::TerminalRenderer, time::Clock, Context, EngineSettings, InputState, Scene,
-    SceneManager,
+    load_config, render::TerminalRenderer, time::Clock, BlendMode, Context, EffectChain,
+    EngineSettings, Flip, Image, InputState, Rgba, Scene, SceneConfig, SceneManager, Sprite,
 };
+use std::collections::HashMap;
 use std::time::Duration;
 
+mod scenes;
+
+use scenes::{
+    BoidsScene, Donut3dScene, EasingsScene, FlowScene, FluidScene, FractalScene, LSystemScene,
+    LifeScene, NoiseScene, ParticlesScene, PlasmaScene, ReactionScene, WalkersScene,
+};
+
 #[derive(Parser, Debug)]
 #[command(name = "shape")]
 #[command(author, version, about, long_about = None)]
@@ -41,6 +50,8 @@ struct ListScenesArgs {
     config: String,
 }
 
+const STARFIELD_LAYER: &str = "starfield";
+
 struct MyTestScene {
     frame_count: u64,
     dt: f32,
@@ -51,6 +62,9 @@ struct MyTestScene {
     time_elapsed: f32,
     player_x: f32,
     player_y: f32,
+    facing_left: bool,
+    sprite: Option<Sprite>,
+    background: Option<Image>,
 }
 
 impl MyTestScene {
@@ -65,13 +79,45 @@ impl MyTestScene {
             time_elapsed: 0.0,
             player_x: 5.0,
             player_y: 5.0,
+            facing_left: false,
+            sprite: None,
+            background: None,
+        }
+    }
+
+    fn from_config(scene_def: &SceneConfig, config_path: &str) -> Result<Self> {
+        let mut scene = Self::new();
+        if let Some(path) = scene_def.path_setting("sprite", config_path) {
+            scene.sprite = Some(Sprite::load(path)?);
+        }
+        if let Some(path) = scene_def.path_setting("image", config_path) {
+            scene.background = Some(Image::load(path)?);
         }
+        Ok(scene)
     }
 }
 
 impl Scene for MyTestScene {
-    fn on_start(&mut self, _ctx: &mut Context<'_>) {
-        // You can keep start-up logic here if needed.
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        // A wide, persistent starfield beneath the scene, scrolled for parallax
+        let (width, height) = (ctx.canvas.width * 2, ctx.canvas.height);
+        let Some(layers) = ctx.layers() else {
+            return;
+        };
+        let starfield = layers.add_sized(STARFIELD_LAYER, -1, width, height);
+        starfield.set_persistent(true);
+        starfield.set_wrap(true);
+        starfield.set_opacity(0.7);
+        let mut canvas = starfield.canvas();
+        canvas.set_foreground_color(Color::DarkGrey);
+        for i in 0..(width as u32 * height as u32 / 24) {
+            let hash = i.wrapping_mul(2_654_435_761);
+            canvas.set_symbol(if hash % 7 == 0 { '*' } else { '.' });
+            canvas.draw_point(
+                (hash % width as u32) as u16,
+                (hash / 7 % height as u32) as u16,
+            );
+        }
     }
 
     fn on_update(&mut self, ctx: &mut Context<'_>) {
@@ -89,6 +135,13 @@ impl Scene for MyTestScene {
             self.y_dir *= -1.0;
         }
 
+        if let Some(starfield) = ctx
+            .layers()
+            .and_then(|layers| layers.get_mut(STARFIELD_LAYER))
+        {
+            starfield.set_offset(-(self.time_elapsed * 4.0) as i32, 0);
+        }
+
         // Player-controlled movement
         if let Some(input) = ctx.input() {
             let speed = 20.0;
@@ -100,9 +153,11 @@ impl Scene for MyTestScene {
             }
             if input.is_key_pressed(KeyCode::Left) {
                 self.player_x -= speed * ctx.delta_time;
+                self.facing_left = true;
             }
             if input.is_key_pressed(KeyCode::Right) {
                 self.player_x += speed * ctx.delta_time;
+                self.facing_left = false;
             }
         }
     }
@@ -110,17 +165,53 @@ impl Scene for MyTestScene {
     fn on_draw(&mut self, ctx: &mut Context<'_>) {
         // The overlay now handles debug text, so we just draw scene elements here.
 
-        // Draw automated moving character
+        // Draw the configured background image centred, in the current pixel mode
+        if let Some(image) = &self.background {
+            let (w, h) = image.fit(ctx.canvas.width, ctx.canvas.height);
+            let x = (ctx.canvas.width - w) / 2;
+            let y = (ctx.canvas.height - h) / 2;
+            ctx.canvas.draw_image(image, x as i32, y as i32, w, h);
+        }
+
+        // Draw automated moving character with an additive glow behind it
+        ctx.canvas.set_blend_mode(BlendMode::Add);
+        ctx.canvas.set_background_rgba(Rgba::new(120, 20, 0, 160));
+        ctx.canvas.set_symbol(' ');
+        ctx.canvas.draw_rect(
+            (self.x_pos as u16).saturating_sub(1),
+            self.y_pos as u16,
+            3,
+            1,
+            true,
+        );
+        ctx.canvas.set_blend_mode(BlendMode::Normal);
+        ctx.canvas.set_background_color(Color::Reset);
         ctx.canvas.set_foreground_color(Color::Red);
         ctx.canvas.set_symbol('@');
         ctx.canvas.draw_point(self.x_pos as u16, self.y_pos as u16);
         ctx.canvas.set_foreground_color(Color::Reset);
 
-        // Draw player-controlled rectangle
+        // Draw the player: the configured sprite if there is one, otherwise a rectangle
         ctx.canvas.set_foreground_color(Color::Blue);
-        ctx.canvas.set_symbol('■');
-        ctx.canvas
-            .draw_rect(self.player_x as u16, self.player_y as u16, 2, 1, true);
+        if let Some(sprite) = &self.sprite {
+            let flip = if self.facing_left {
+                Flip::Horizontal
+            } else {
+                Flip::None
+            };
+            let frame = sprite.frame_at(ctx.total_time);
+            ctx.canvas.draw_sprite(
+                sprite,
+                self.player_x as i32,
+                self.player_y as i32,
+                frame,
+                flip,
+            );
+        } else {
+            ctx.canvas.set_symbol('■');
+            ctx.canvas
+                .draw_rect(self.player_x as u16, self.player_y as u16, 2, 1, true);
+        }
         ctx.canvas.set_foreground_color(Color::Reset);
 
         // Draw some other static and animated primitives from before
@@ -140,21 +231,29 @@ impl Scene for MyTestScene {
         ctx.canvas.set_foreground_color(Color::Reset);
 
         let circle_radius = (5.0 * (self.time_elapsed.sin() + 1.0) + 2.0) as i32;
-        let circle_x =
-            (ctx.canvas.width / 2) as i32 + (10.0 * (self.time_elapsed * 0.5).cos()) as i32;
-        let circle_y =
-            (ctx.canvas.height / 2) as i32 + (5.0 * (self.time_elapsed * 0.8).sin()) as i32;
 
+        ctx.canvas.push();
+        ctx.canvas.translate(
+            (ctx.canvas.width / 2) as f32,
+            (ctx.canvas.height / 2) as f32,
+        );
+        ctx.canvas.translate(
+            10.0 * (self.time_elapsed * 0.5).cos(),
+            5.0 * (self.time_elapsed * 0.8).sin(),
+        );
         ctx.canvas.set_foreground_color(Color::Magenta);
         ctx.canvas.set_symbol('*');
-        ctx.canvas
-            .draw_circle(circle_x, circle_y, circle_radius, false);
+        ctx.canvas.draw_circle(0, 0, circle_radius, false);
         ctx.canvas.set_foreground_color(Color::Reset);
+        ctx.canvas.pop();
 
         ctx.canvas.set_symbol(' ');
     }
 
     fn on_exit(&mut self, ctx: &mut Context<'_>) {
+        if let Some(layers) = ctx.layers() {
+            layers.remove(STARFIELD_LAYER);
+        }
         ctx.canvas.draw_text(0, 15, "MyTestScene exiting. Goodbye!");
         ctx.canvas.set_foreground_color(Color::Reset);
         ctx.canvas.set_background_color(Color::Reset);
@@ -162,8 +261,33 @@ impl Scene for MyTestScene {
     }
 }
 
-fn instantiate_scene(_name: &str, _config_path: &str) -> Option<Box<dyn Scene>> {
-    Some(Box::new(MyTestScene::new()))
+fn instantiate_scene(scene_def: &SceneConfig, config_path: &str) -> Result<Box<dyn Scene>> {
+    let scene: Box<dyn Scene> = match scene_def.scene_type() {
+        "boids" => Box::new(BoidsScene::from_config(scene_def)?),
+        "demo" => Box::new(MyTestScene::from_config(scene_def, config_path)?),
+        "donut3d" => Box::new(Donut3dScene::from_config(scene_def, config_path)?),
+        "easings" => Box::new(EasingsScene::from_config(scene_def)?),
+        "flow" => Box::new(FlowScene::from_config(scene_def)?),
+        "fluid" => Box::new(FluidScene::from_config(scene_def)?),
+        "fractal" => Box::new(FractalScene::from_config(scene_def)?),
+        "life" => Box::new(LifeScene::from_config(scene_def, config_path)?),
+        "lsystem" => Box::new(LSystemScene::from_config(scene_def)?),
+        "noise" => Box::new(NoiseScene::from_config(scene_def)?),
+        "particles" => Box::new(ParticlesScene::from_config(scene_def)?),
+        "plasma" => Box::new(PlasmaScene::from_config(scene_def)?),
+        "reaction" => Box::new(ReactionScene::from_config(scene_def)?),
+        "walkers" => Box::new(WalkersScene::from_config(scene_def)?),
+        other => {
+            // Configs written before scene types existed name their scenes
+            // freely and always ran the demo scene, so keep loading them.
+            eprintln!(
+                "Warning: scene '{}' has unknown type '{other}', running the demo scene",
+                scene_def.name
+            );
+            Box::new(MyTestScene::from_config(scene_def, config_path)?)
+        }
+    };
+    Ok(scene)
 }
 
 fn run_scene(args: &RunArgs) -> Result<()> {
@@ -175,14 +299,15 @@ fn run_scene(args: &RunArgs) -> Result<()> {
         ));
     }
 
-    let mut renderer = TerminalRenderer::new(config.engine.width, config.engine.height)?;
-    renderer.init()?;
-
     let mut engine_settings: EngineSettings = config.engine.clone();
     if args.framerate > 0 {
         engine_settings.framerate = args.framerate;
     }
 
+    let seed = engine_settings.seed.unwrap_or_else(rand::random);
+    engine_settings.seed = Some(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+
     let mut clock = Clock::new(engine_settings.framerate as f32);
 
     let mut scene_manager = SceneManager::new();
@@ -192,18 +317,31 @@ fn run_scene(args: &RunArgs) -> Result<()> {
         .iter()
         .map(|scene| scene.name.clone())
         .collect();
+    let mut effect_chains: HashMap<String, EffectChain> = HashMap::new();
+    for (index, scene_def) in config.scenes.iter().enumerate() {
+        // Offset per scene so each chain draws its own random sequence.
+        effect_chains
+            .entry(scene_def.name.clone())
+            .or_insert_with(|| {
+                EffectChain::new(scene_def.effects.clone())
+                    .with_seed(seed.wrapping_add(index as u64 + 1))
+            });
+    }
+    let mut effects_scene: Option<String> = None;
 
     for scene_def in &config.scenes {
         if scene_manager.has_scene(&scene_def.name) {
             continue;
         }
-        if let Some(scene) = instantiate_scene(&scene_def.name, &args.config) {
-            scene_manager.add_boxed_scene(scene_def.name.clone(), scene);
-        } else {
-            eprintln!("Warning: scene '{}' is not available", scene_def.name);
-        }
+        let scene = instantiate_scene(scene_def, &args.config)?;
+        scene_manager.add_boxed_scene(scene_def.name.clone(), scene);
     }
 
+    // Set up the terminal only once the scenes have loaded, so config errors
+    // and warnings print normally.
+    let mut renderer = TerminalRenderer::new(config.engine.width, config.engine.height)?;
+    renderer.init()?;
+
     let initial_scene = config.scenes.first().unwrap();
     let mut active_scene_index = scene_names
         .iter()
@@ -218,8 +356,11 @@ fn run_scene(args: &RunArgs) -> Result<()> {
 
     renderer.clear_screen();
     {
-        let mut context = Context::new(renderer.canvas(), engine_settings.clone());
+        let (canvas, layers) = renderer.canvas_with_layers();
+        let mut context = Context::new(canvas, engine_settings.clone());
+        context.set_layers(Some(layers));
         context.set_input(Some(&input_state));
+        context.set_rng(Some(&mut rng));
         context.set_timing(0.0, 0.0, 0);
         context.canvas.current_pixel_mode = engine_settings.mode;
         scene_manager.activate(&initial_scene.name, &mut context)?;
@@ -237,13 +378,11 @@ fn run_scene(args: &RunArgs) -> Result<()> {
                 KeyCode::Char(c) => match c.to_ascii_lowercase() {
                     'q' => exit_requested = true,
                     'p' => paused = !paused,
-                    'n' => {
-                        if !scene_names.is_empty() {
-                            active_scene_index = (active_scene_index + 1) % scene_names.len();
-                            if let Some(next_scene) = scene_names.get(active_scene_index) {
-                                if scene_manager.has_scene(next_scene) {
-                                    scene_manager.queue_transition(next_scene.clone());
-                                }
+                    'n' if !scene_names.is_empty() => {
+                        active_scene_index = (active_scene_index + 1) % scene_names.len();
+                        if let Some(next_scene) = scene_names.get(active_scene_index) {
+                            if scene_manager.has_scene(next_scene) {
+                                scene_manager.queue_transition(next_scene.clone());
                             }
                         }
                     }
@@ -275,15 +414,32 @@ fn run_scene(args: &RunArgs) -> Result<()> {
 
         renderer.clear_screen();
         {
-            let mut context = Context::new(renderer.canvas(), engine_settings.clone());
+            let (canvas, layers) = renderer.canvas_with_layers();
+            let mut context = Context::new(canvas, engine_settings.clone());
+            context.set_layers(Some(layers));
             context.set_input(Some(&input_state));
+            context.set_rng(Some(&mut rng));
             context.set_timing(delta_time, elapsed_time, frame);
             context.canvas.current_pixel_mode = engine_settings.mode;
             scene_manager.update(&mut context)?;
             scene_manager.draw(&mut context);
         }
+        if let Some(scene_name) = scene_manager.current_scene() {
+            if let Some(chain) = effect_chains.get_mut(scene_name) {
+                if effects_scene.as_deref() != Some(scene_name) {
+                    chain.reset();
+                    effects_scene = Some(scene_name.to_string());
+                }
+                renderer.apply_effects(chain, elapsed_time, delta_time);
+            }
+        }
         {
             let mut overlay = renderer.overlay_canvas();
+            // Translucent panel behind the HUD; text keeps it with a transparent background
+            overlay.set_background_rgba(Rgba::new(0, 0, 0, 170));
+            overlay.set_symbol(' ');
+            overlay.draw_rect(0, 0, overlay.width, 3, true);
+            overlay.set_background_rgba(Rgba::new(0, 0, 0, 0));
             overlay.set_foreground_color(Color::Yellow);
             let scene_label = scene_manager.current_scene().unwrap_or("<none>");
             let pause_suffix = if paused { " [Paused]" } else { "" };
@@ -305,8 +461,11 @@ fn run_scene(args: &RunArgs) -> Result<()> {
 
     renderer.clear_screen();
     {
-        let mut context = Context::new(renderer.canvas(), engine_settings.clone());
+        let (canvas, layers) = renderer.canvas_with_layers();
+        let mut context = Context::new(canvas, engine_settings.clone());
+        context.set_layers(Some(layers));
         context.set_input(Some(&input_state));
+        context.set_rng(Some(&mut rng));
         context.set_timing(0.0, elapsed_time, frame);
         context.canvas.current_pixel_mode = engine_settings.mode;
         scene_manager.shutdown(&mut context);
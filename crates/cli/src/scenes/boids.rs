@@ -0,0 +1,87 @@
+use anyhow::{bail, Result};
+use rand::{rngs::StdRng, SeedableRng};
+use shape_engine_core::{parse_color, Context, Flock, Param, Rgba, Scene, SceneConfig};
+
+/// A flock of boids wrapping around the canvas.
+///
+/// Settings: `count`, `perception` (how far boids see), `separation`,
+/// `alignment` and `cohesion` (steering weights), `max_speed` and `color`.
+/// All but `count` and `color` may be keyframed.
+pub struct BoidsScene {
+    count: usize,
+    perception: Option<Param>,
+    weights: [Option<Param>; 3],
+    max_speed: Option<Param>,
+    color: Option<Rgba>,
+    flock: Option<Flock>,
+}
+
+impl BoidsScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let color = match scene_def.settings.get("color").and_then(|v| v.as_str()) {
+            Some(text) => match parse_color(text).and_then(Rgba::from_color) {
+                Some(color) => Some(color),
+                None => bail!("Scene '{}': unknown colour '{text}'", scene_def.name),
+            },
+            None => None,
+        };
+        Ok(Self {
+            count: scene_def.float_setting("count")?.unwrap_or(150.0) as usize,
+            perception: scene_def.param("perception")?,
+            weights: [
+                scene_def.param("separation")?,
+                scene_def.param("alignment")?,
+                scene_def.param("cohesion")?,
+            ],
+            max_speed: scene_def.param("max_speed")?,
+            color,
+            flock: None,
+        })
+    }
+
+    /// Applies the configured settings at `time`, leaving the flock's own
+    /// defaults for those not set.
+    fn tune(&self, flock: &mut Flock, time: f32) {
+        let apply = |field: &mut f32, param: &Option<Param>| {
+            if let Some(param) = param {
+                *field = param.value(time);
+            }
+        };
+        let [separation, alignment, cohesion] = &self.weights;
+        apply(&mut flock.perception, &self.perception);
+        apply(&mut flock.separation, separation);
+        apply(&mut flock.alignment, alignment);
+        apply(&mut flock.cohesion, cohesion);
+        apply(&mut flock.max_speed, &self.max_speed);
+    }
+}
+
+impl Scene for BoidsScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        let (width, height) = (ctx.canvas.width as f32, ctx.canvas.height as f32);
+        let seed = ctx.seed();
+        let mut flock = match ctx.rng() {
+            Some(rng) => Flock::new(self.count, width, height, rng),
+            None => Flock::new(self.count, width, height, &mut StdRng::seed_from_u64(seed)),
+        };
+        self.tune(&mut flock, 0.0);
+        flock.color = self.color;
+        self.flock = Some(flock);
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        if let Some(mut flock) = self.flock.take() {
+            self.tune(&mut flock, ctx.scene_time);
+            flock.update(ctx.delta_time);
+            self.flock = Some(flock);
+        }
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        if let Some(flock) = &self.flock {
+            flock.draw(&mut ctx.canvas);
+        }
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
@@ -0,0 +1,80 @@
+use anyhow::{bail, Result};
+use shape_engine_core::{
+    parse_color, Context, Mat4, Material, Mesh, MeshStyle, Param, Rgba, Scene, SceneConfig,
+    Shading, Viewport3d,
+};
+
+/// The classic spinning donut, or any OBJ model given as `model`.
+///
+/// Settings: `model` (path), `style` (`filled`, `wireframe` or
+/// `filled_wireframe`), `shading` (`flat` or `smooth`), `color` and `spin`
+/// (radians per second, may be keyframed).
+pub struct Donut3dScene {
+    viewport: Viewport3d,
+    mesh: Mesh,
+    material: Material,
+    spin: Param,
+    angle: f32,
+}
+
+impl Donut3dScene {
+    pub fn from_config(scene_def: &SceneConfig, config_path: &str) -> Result<Self> {
+        let mesh = match scene_def.path_setting("model", config_path) {
+            Some(path) => Mesh::load_obj(path)?.fitted(2.5),
+            None => Mesh::torus(1.0, 0.45, 48, 24),
+        };
+        let setting = |key: &str| scene_def.settings.get(key).and_then(|v| v.as_str());
+        let style = match setting("style").unwrap_or("filled") {
+            "filled" => MeshStyle::Filled,
+            "wireframe" => MeshStyle::Wireframe,
+            "filled_wireframe" => MeshStyle::FilledWireframe,
+            other => bail!("Scene '{}': unknown style '{other}'", scene_def.name),
+        };
+        let shading = match setting("shading").unwrap_or("smooth") {
+            "flat" => Shading::Flat,
+            "smooth" => Shading::Smooth,
+            other => bail!("Scene '{}': unknown shading '{other}'", scene_def.name),
+        };
+        let color = match setting("color") {
+            Some(text) => match parse_color(text).and_then(Rgba::from_color) {
+                Some(color) => Some(color),
+                None => bail!("Scene '{}': unknown colour '{text}'", scene_def.name),
+            },
+            None => None,
+        };
+        Ok(Self {
+            viewport: Viewport3d::new(0, 0, Default::default()),
+            mesh,
+            material: Material {
+                color,
+                style,
+                shading,
+                ..Material::default()
+            },
+            spin: super::param(scene_def, "spin", 1.0)?,
+            angle: 0.0,
+        })
+    }
+}
+
+impl Scene for Donut3dScene {
+    fn on_start(&mut self, _ctx: &mut Context<'_>) {
+        self.angle = 0.0;
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        self.angle += self.spin.value(ctx.scene_time) * ctx.delta_time;
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        let (width, height) = (ctx.canvas.width, ctx.canvas.height);
+        self.viewport
+            .resize(width, height, ctx.canvas.current_pixel_mode);
+        self.viewport.clear();
+        let model = Mat4::rotation_y(self.angle * 0.7) * Mat4::rotation_x(self.angle + 0.6);
+        self.viewport.draw_mesh(&self.mesh, &model, &self.material);
+        self.viewport.draw(&mut ctx.canvas, 0, 0);
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
@@ -0,0 +1,100 @@
+use anyhow::{anyhow, Result};
+use crossterm::style::Color;
+use shape_engine_core::{
+    Animation, Context, Delay, Easing, Parallel, Repeat, Rgba, Scene, SceneConfig, Timeline, Tween,
+    Yoyo,
+};
+
+/// Easings shown when the config does not pick any.
+const DEFAULT_EASINGS: [&str; 8] = [
+    "linear",
+    "sine_in_out",
+    "quad_out",
+    "cubic_in_out",
+    "back_out",
+    "elastic_out",
+    "bounce_out",
+    "bounce_in_out",
+];
+
+/// Width of the name column, in cells.
+const LABEL_WIDTH: u16 = 15;
+
+type Track = Timeline<Repeat<Yoyo<Delay<Parallel<Tween<f32>, Tween<Rgba>>>>>>;
+
+/// One row per easing: a dot travels across the canvas and back, shifting
+/// colour as it goes.
+///
+/// Settings: `easings` (array of names such as `cubic_in_out`), `duration`
+/// (seconds per crossing), `pause` (seconds at each end) and `colors`
+/// (start and end colour).
+pub struct EasingsScene {
+    rows: Vec<(String, Track)>,
+}
+
+impl EasingsScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let names: Vec<String> = match scene_def.settings.get("easings") {
+            Some(value) => value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|name| name.as_str().map(str::to_string))
+                .collect(),
+            None => DEFAULT_EASINGS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        };
+        let duration = scene_def.float_setting("duration")?.unwrap_or(2.0).max(0.1);
+        let pause = scene_def.float_setting("pause")?.unwrap_or(0.5);
+        let colors = super::color_list(scene_def, "colors")?;
+        let from = colors.first().copied().unwrap_or(Rgba::rgb(80, 160, 255));
+        let to = colors.last().copied().unwrap_or(Rgba::rgb(255, 120, 60));
+        let rows = names
+            .into_iter()
+            .map(|name| {
+                let easing = Easing::parse(&name)
+                    .map_err(|_| anyhow!("Scene '{}': unknown easing '{name}'", scene_def.name))?;
+                let motion = Tween::new(0.0, 1.0, duration).with_easing(easing);
+                let color = Tween::new(from, to, duration).with_easing(easing);
+                let track = motion.join(color).delay(pause).yoyo().repeat_forever();
+                Ok((name, Timeline::new(track)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rows })
+    }
+}
+
+impl Scene for EasingsScene {
+    fn on_start(&mut self, _ctx: &mut Context<'_>) {
+        for (_, timeline) in &mut self.rows {
+            timeline.restart();
+        }
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        for (_, timeline) in &mut self.rows {
+            timeline.update(ctx.delta_time);
+        }
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        let canvas = &mut ctx.canvas;
+        let spacing = (canvas.height / self.rows.len().max(1) as u16).clamp(1, 3);
+        let track_start = LABEL_WIDTH + 1;
+        let track_width = canvas.width.saturating_sub(track_start + 2) as f32;
+        for (index, (name, timeline)) in self.rows.iter().enumerate() {
+            let y = index as u16 * spacing + spacing / 2;
+            let (position, color) = timeline.value();
+            canvas.draw_text(0, y, name);
+            // Overshooting easings may leave the track for a moment.
+            let x = track_start as f32 + 1.0 + position * track_width;
+            canvas.set_foreground_color(color.to_color());
+            canvas.draw_text(x.round().max(0.0) as u16, y, "●");
+            canvas.set_foreground_color(Color::Reset);
+        }
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
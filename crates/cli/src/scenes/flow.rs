@@ -0,0 +1,127 @@
+use anyhow::{bail, Result};
+use rand::{rngs::StdRng, SeedableRng};
+use shape_engine_core::{
+    Context, CurlField, NoiseField, OpenSimplex, Param, Point, Rgba, Scene, SceneConfig, Tracers,
+    VectorField,
+};
+
+/// The flow field, kept concrete so its scale and strength can follow
+/// keyframes.
+enum Field {
+    Noise(NoiseField<OpenSimplex>),
+    Curl(CurlField<OpenSimplex>),
+}
+
+impl Field {
+    fn tune(&mut self, scale: f32, strength: f32) {
+        match self {
+            Field::Noise(field) => (field.scale, field.strength) = (scale, strength),
+            Field::Curl(field) => (field.scale, field.strength) = (scale, strength),
+        }
+    }
+
+    fn as_field(&self) -> &dyn VectorField {
+        match self {
+            Field::Noise(field) => field,
+            Field::Curl(field) => field,
+        }
+    }
+}
+
+/// Tracers drifting through a noise flow field, leaving fading trails.
+///
+/// Settings: `field` (`noise` or `curl`), `count`, `trail` (positions per
+/// trail), `scale` (feature size), `strength` (speed), `evolution` (how fast
+/// the field changes) and `colors` (head to tail). `scale`, `strength` and
+/// `evolution` may be keyframed.
+pub struct FlowScene {
+    curl: bool,
+    count: usize,
+    trail: usize,
+    scale: Param,
+    strength: Param,
+    evolution: Param,
+    colors: Vec<Rgba>,
+    /// How far the field has evolved, integrated from `evolution`.
+    phase: f32,
+    field: Option<Field>,
+    tracers: Option<Tracers>,
+}
+
+impl FlowScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let curl = match scene_def
+            .settings
+            .get("field")
+            .and_then(|v| v.as_str())
+            .unwrap_or("curl")
+        {
+            "noise" => false,
+            "curl" => true,
+            other => bail!("Scene '{}': unknown field '{other}'", scene_def.name),
+        };
+        Ok(Self {
+            curl,
+            count: scene_def.float_setting("count")?.unwrap_or(400.0) as usize,
+            trail: scene_def.float_setting("trail")?.unwrap_or(12.0) as usize,
+            scale: super::param(scene_def, "scale", 20.0)?,
+            strength: super::param(scene_def, "strength", 8.0)?,
+            evolution: super::param(scene_def, "evolution", 0.1)?,
+            colors: super::color_list(scene_def, "colors")?,
+            phase: 0.0,
+            field: None,
+            tracers: None,
+        })
+    }
+}
+
+impl Scene for FlowScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        let seed = ctx.seed();
+        let noise = OpenSimplex::new(seed);
+        // The field is sampled at the integrated phase, so it evolves at one
+        // unit per unit of phase.
+        self.field = Some(if self.curl {
+            Field::Curl(CurlField::new(noise).with_evolution(1.0))
+        } else {
+            Field::Noise(NoiseField::new(noise).with_evolution(1.0))
+        });
+        self.phase = 0.0;
+        let (width, height) = (ctx.canvas.width as f32, ctx.canvas.height as f32);
+        let tracers = match ctx.rng() {
+            Some(rng) => Tracers::new(self.count, width, height, rng),
+            None => Tracers::new(self.count, width, height, &mut StdRng::seed_from_u64(seed)),
+        };
+        self.tracers = Some(
+            tracers
+                .with_trail_length(self.trail)
+                .with_colors(self.colors.clone())
+                .with_glyphs("*+:."),
+        );
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        let (time, delta_time) = (ctx.scene_time, ctx.delta_time);
+        // Integrated rather than `time * evolution`, so keyframed evolution
+        // changes the pace without jumping.
+        let (start, evolution) = (self.phase, self.evolution.value(time));
+        self.phase += evolution * delta_time;
+        let (Some(field), Some(tracers), Some(rng)) =
+            (&mut self.field, &mut self.tracers, ctx.rng())
+        else {
+            return;
+        };
+        field.tune(self.scale.value(time).max(0.1), self.strength.value(time));
+        let field = field.as_field();
+        let flow = |position: Point, t: f32| field.velocity(position, start + t * evolution);
+        tracers.advect(&flow, 0.0, delta_time, rng);
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        if let Some(tracers) = &self.tracers {
+            tracers.draw(&mut ctx.canvas);
+        }
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use shape_engine_core::{Context, Fluid, Param, Rgba, Scene, SceneConfig};
+
+use super::Brush;
+
+/// How quickly the jet and the brush push the fluid, as a share of their
+/// own velocity added per second.
+const PUSH_RATE: f32 = 20.0;
+
+/// A stable-fluids box on a grid matching the canvas' logical resolution,
+/// stirred by a swaying jet of dye rising from the bottom. Arrow keys move
+/// a brush and holding space pours dye, pushed along the brush's motion.
+///
+/// Settings: `viscosity`, `diffusion` (dye spread), `dissipation` (share of
+/// dye lost per second), `iterations` (solver sweeps), `jet` (jet speed in
+/// cell widths per second, 0 to turn it off), `radius` (dye radius in
+/// logical pixels) and `colors` (ramp from no dye to full). `viscosity`,
+/// `diffusion`, `dissipation`, `jet` and `radius` may be keyframed.
+pub struct FluidScene {
+    viscosity: Param,
+    diffusion: Param,
+    dissipation: Param,
+    iterations: usize,
+    jet: Param,
+    radius: Param,
+    colors: Vec<Rgba>,
+    fluid: Option<Fluid>,
+    brush: Option<Brush>,
+}
+
+impl FluidScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        Ok(Self {
+            viscosity: super::param(scene_def, "viscosity", 0.0)?,
+            diffusion: super::param(scene_def, "diffusion", 0.0)?,
+            dissipation: super::param(scene_def, "dissipation", 0.3)?,
+            iterations: scene_def
+                .float_setting("iterations")?
+                .unwrap_or(12.0)
+                .max(1.0) as usize,
+            jet: super::param(scene_def, "jet", 30.0)?,
+            radius: super::param(scene_def, "radius", 3.0)?,
+            colors: super::color_list(scene_def, "colors")?,
+            fluid: None,
+            brush: None,
+        })
+    }
+}
+
+impl Scene for FluidScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        let (width, height) = ctx.canvas.logical_size();
+        let mut fluid = Fluid::new(width, height).with_colors(self.colors.clone());
+        fluid.iterations = self.iterations;
+        self.fluid = Some(fluid);
+        self.brush = Some(Brush::new(ctx.canvas.width, ctx.canvas.height));
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        let Some(fluid) = &mut self.fluid else {
+            return;
+        };
+        let (width, height) = ctx.canvas.logical_size();
+        fluid.resize(width, height);
+        let (sx, sy) = ctx.canvas.current_pixel_mode.cell_resolution();
+        let (sx, sy) = (sx as f32, sy as f32);
+        let (dt, time) = (ctx.delta_time, ctx.scene_time);
+        fluid.viscosity = self.viscosity.value(time).max(0.0);
+        fluid.diffusion = self.diffusion.value(time).max(0.0);
+        fluid.dissipation = self.dissipation.value(time);
+        let radius = self.radius.value(time).max(0.5);
+
+        let jet = self.jet.value(time);
+        if jet > 0.0 && dt > 0.0 {
+            let sway = (ctx.total_time * 0.7).sin() * 0.6;
+            // Cells are twice as tall as wide, so a vertical cell width is
+            // half a cell.
+            let push = jet * PUSH_RATE * dt;
+            let velocity = (sway.sin() * push * sx, -sway.cos() * push * 0.5 * sy);
+            let (x, y) = (width as f32 / 2.0, height as f32 - radius - 1.0);
+            fluid.add_density(x, y, radius, 4.0 * dt);
+            fluid.add_velocity(x, y, radius, velocity);
+        }
+        let canvas_size = (ctx.canvas.width, ctx.canvas.height);
+        if let (Some(brush), Some(input)) = (&mut self.brush, ctx.input()) {
+            if let Some(motion) = brush.steer(input, dt, canvas_size) {
+                let at = brush.pixel_position(ctx.canvas.current_pixel_mode);
+                fluid.add_density(at.x, at.y, radius, 4.0 * dt);
+                let push = PUSH_RATE * dt;
+                let velocity = (motion.x * push * sx, motion.y * push * sy);
+                fluid.add_velocity(at.x, at.y, radius, velocity);
+            }
+        }
+        fluid.step(dt);
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        if let Some(fluid) = &self.fluid {
+            fluid.draw(&mut ctx.canvas);
+        }
+        if let Some(brush) = &self.brush {
+            brush.draw(&mut ctx.canvas);
+        }
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
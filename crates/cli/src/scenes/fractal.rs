@@ -0,0 +1,161 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::KeyCode;
+use shape_engine_core::{Context, FractalSet, FractalView, InputState, Param, Scene, SceneConfig};
+
+/// Extra iterations per doubling of the zoom, keeping the boundary
+/// detailed as the view deepens.
+const ITERATIONS_PER_OCTAVE: f64 = 24.0;
+
+/// An explorable Mandelbrot or Julia set.
+///
+/// Arrow keys pan, `+`/`-` zoom, `[`/`]` change the iteration budget, `j`
+/// swaps between the Mandelbrot set and the Julia set of the point under
+/// the centre, and `r` resets the view.
+///
+/// Settings: `set` (`mandelbrot` or `julia`), `center` and `julia` (plane
+/// points as `[re, im]`), `zoom`, `iterations`, `colors` (cycled palette),
+/// `color_period` (iterations per palette cycle), `cycle_speed` (palette
+/// cycles per second) and `zoom_speed` (automatic zoom, doublings per
+/// second). `color_period`, `cycle_speed` and `zoom_speed` may be keyframed.
+pub struct FractalScene {
+    initial: FractalView,
+    view: FractalView,
+    iterations: u32,
+    color_period: Param,
+    cycle_speed: Param,
+    zoom_speed: Param,
+    /// Keys held last frame, so toggles fire once per press.
+    toggles_held: [bool; 2],
+}
+
+impl FractalScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let name = &scene_def.name;
+        let set = match scene_def.settings.get("set").and_then(|v| v.as_str()) {
+            None | Some("mandelbrot") => FractalSet::Mandelbrot,
+            Some("julia") => {
+                let (re, im) = point_setting(scene_def, "julia")?.unwrap_or((-0.8, 0.156));
+                FractalSet::Julia { re, im }
+            }
+            Some(other) => bail!("Scene '{name}': unknown fractal set '{other}'"),
+        };
+        let iterations = scene_def
+            .float_setting("iterations")?
+            .unwrap_or(128.0)
+            .max(1.0) as u32;
+        let color_period = super::param(scene_def, "color_period", 32.0)?;
+        let mut view = FractalView::new(set)
+            .with_zoom(scene_def.float_setting("zoom")?.unwrap_or(1.0).max(1e-6) as f64)
+            .with_iterations(iterations)
+            .with_colors(super::color_list(scene_def, "colors")?)
+            .with_color_period(color_period.value(0.0).max(f32::EPSILON));
+        if let Some((re, im)) = point_setting(scene_def, "center")? {
+            view = view.with_center(re, im);
+        }
+        Ok(Self {
+            initial: view.clone(),
+            view,
+            iterations,
+            color_period,
+            cycle_speed: super::param(scene_def, "cycle_speed", 0.0)?,
+            zoom_speed: super::param(scene_def, "zoom_speed", 0.0)?,
+            toggles_held: [false; 2],
+        })
+    }
+
+    fn handle_input(&mut self, input: &InputState, dt: f32) {
+        let dt = dt as f64;
+        let held = |codes: &[KeyCode]| codes.iter().any(|&code| input.is_key_pressed(code));
+        let pan = 0.5 * dt;
+        if held(&[KeyCode::Left]) {
+            self.view.pan(-pan, 0.0);
+        }
+        if held(&[KeyCode::Right]) {
+            self.view.pan(pan, 0.0);
+        }
+        if held(&[KeyCode::Up]) {
+            self.view.pan(0.0, -pan);
+        }
+        if held(&[KeyCode::Down]) {
+            self.view.pan(0.0, pan);
+        }
+        if held(&[KeyCode::Char('+'), KeyCode::Char('=')]) {
+            self.view.zoom_by(2f64.powf(dt));
+        }
+        if held(&[KeyCode::Char('-'), KeyCode::Char('_')]) {
+            self.view.zoom_by(0.5f64.powf(dt));
+        }
+        if held(&[KeyCode::Char(']')]) {
+            self.iterations = self.iterations.saturating_add((64.0 * dt).ceil() as u32);
+        }
+        if held(&[KeyCode::Char('[')]) {
+            self.iterations = self
+                .iterations
+                .saturating_sub((64.0 * dt).ceil() as u32)
+                .max(16);
+        }
+
+        let pressed = [
+            held(&[KeyCode::Char('j'), KeyCode::Char('J')]),
+            held(&[KeyCode::Char('r'), KeyCode::Char('R')]),
+        ];
+        let [julia, reset] = [0, 1].map(|i| pressed[i] && !self.toggles_held[i]);
+        self.toggles_held = pressed;
+        if julia {
+            self.view = match self.view.set {
+                FractalSet::Mandelbrot => {
+                    let (re, im) = self.view.center;
+                    FractalView::julia(re, im)
+                }
+                FractalSet::Julia { re, im } => FractalView::mandelbrot().with_center(re, im),
+            }
+            .with_colors(self.view.colors.clone())
+            .with_color_period(self.view.color_period);
+        }
+        if reset {
+            self.view = self.initial.clone();
+            self.iterations = self.initial.max_iterations;
+        }
+    }
+}
+
+impl Scene for FractalScene {
+    fn on_start(&mut self, _ctx: &mut Context<'_>) {}
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        if let Some(input) = ctx.input() {
+            self.handle_input(input, ctx.delta_time);
+        }
+        let (dt, time) = (ctx.delta_time, ctx.scene_time);
+        let (zoom_speed, cycle_speed) = (self.zoom_speed.value(time), self.cycle_speed.value(time));
+        self.view.zoom_by(2f64.powf((zoom_speed * dt) as f64));
+        self.view.color_offset = (self.view.color_offset + cycle_speed * dt).rem_euclid(1.0);
+        self.view.color_period = self.color_period.value(time).max(f32::EPSILON);
+        let octaves = self.view.zoom.log2().max(0.0);
+        self.view.max_iterations = self.iterations + (octaves * ITERATIONS_PER_OCTAVE) as u32;
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        self.view.draw(&mut ctx.canvas);
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
+
+/// Reads a plane point written as `[re, im]`, keeping full `f64` precision
+/// for deep-zoom centres.
+fn point_setting(scene_def: &SceneConfig, key: &str) -> Result<Option<(f64, f64)>> {
+    let Some(value) = scene_def.settings.get(key) else {
+        return Ok(None);
+    };
+    let invalid = || anyhow!("Scene '{}': {key} must be [re, im]", scene_def.name);
+    let parts = value.as_array().ok_or_else(invalid)?;
+    let numbers: Option<Vec<f64>> = parts
+        .iter()
+        .map(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
+        .collect();
+    match numbers.as_deref() {
+        Some(&[re, im]) => Ok(Some((re, im))),
+        _ => Err(invalid()),
+    }
+}
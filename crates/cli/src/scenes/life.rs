@@ -0,0 +1,113 @@
+use anyhow::{bail, Context as AnyhowContext, Result};
+use rand::{rngs::StdRng, SeedableRng};
+use shape_engine_core::{
+    time::FixedStep, Automaton, AutomatonRule, CellPattern, Context, Param, Scene, SceneConfig,
+    Topology,
+};
+
+/// Cellular automata filling the canvas, one cell per logical pixel of the
+/// current pixel mode, so braille mode packs eight cells into each glyph.
+///
+/// Settings: `rule` (`B3/S23`, `W30`, `brians_brain`... see
+/// `AutomatonRule::parse`; defaults to the pattern's rule, then Life),
+/// `pattern` (RLE file placed in the middle), `density` (share of live
+/// cells when seeding at random; 0 seeds a single cell), `topology`
+/// (`toroidal` or `bounded`), `speed` (generations per second) and `colors`
+/// (one per live state). `speed` may be keyframed. The grid reseeds when
+/// everything dies.
+pub struct LifeScene {
+    grid: Automaton,
+    pattern: Option<CellPattern>,
+    density: f32,
+    speed: Param,
+    stepper: FixedStep,
+}
+
+impl LifeScene {
+    pub fn from_config(scene_def: &SceneConfig, config_path: &str) -> Result<Self> {
+        let name = &scene_def.name;
+        let setting = |key: &str| scene_def.settings.get(key).and_then(|v| v.as_str());
+        let pattern = match scene_def.path_setting("pattern", config_path) {
+            Some(path) => Some(CellPattern::load_rle(path)?),
+            None => None,
+        };
+        let rule_text = setting("rule")
+            .or_else(|| pattern.as_ref().and_then(|p| p.rule.as_deref()))
+            .unwrap_or("life");
+        let rule = AutomatonRule::parse(rule_text).with_context(|| format!("Scene '{name}'"))?;
+        let topology = match setting("topology").unwrap_or("toroidal") {
+            "toroidal" => Topology::Toroidal,
+            "bounded" => Topology::Bounded,
+            other => bail!("Scene '{name}': unknown topology '{other}'"),
+        };
+        let colors = super::color_list(scene_def, "colors")?;
+        let default_density = match rule {
+            AutomatonRule::Elementary(_) => 0.0,
+            AutomatonRule::Totalistic { .. } => 0.25,
+        };
+        let speed = super::param(scene_def, "speed", 20.0)?;
+        Ok(Self {
+            grid: Automaton::new(0, 0, rule)
+                .with_topology(topology)
+                .with_colors(colors),
+            pattern,
+            density: scene_def
+                .float_setting("density")?
+                .unwrap_or(default_density),
+            stepper: FixedStep::new(speed.value(0.0).max(0.1)),
+            speed,
+        })
+    }
+
+    fn reseed(&mut self, ctx: &mut Context<'_>) {
+        let (width, height) = ctx.canvas.logical_size();
+        self.grid.resize(width, height);
+        self.grid.clear();
+        if let Some(pattern) = &self.pattern {
+            let x = width.saturating_sub(pattern.width) / 2;
+            let y = height.saturating_sub(pattern.height) / 2;
+            self.grid.place(pattern, x, y);
+        } else if self.density <= 0.0 {
+            // A single cell; elementary rules grow down from the top row.
+            let y = match self.grid.rule {
+                AutomatonRule::Elementary(_) => 0,
+                AutomatonRule::Totalistic { .. } => height / 2,
+            };
+            self.grid.set(width / 2, y, 1);
+        } else {
+            let seed = ctx.seed();
+            match ctx.rng() {
+                Some(rng) => self.grid.randomize(self.density, rng),
+                None => self
+                    .grid
+                    .randomize(self.density, &mut StdRng::seed_from_u64(seed)),
+            }
+        }
+    }
+}
+
+impl Scene for LifeScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        self.reseed(ctx);
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        // Follow pixel mode changes, keeping what still fits.
+        let (width, height) = ctx.canvas.logical_size();
+        self.grid.resize(width, height);
+        self.stepper
+            .set_rate(self.speed.value(ctx.scene_time).max(0.1));
+        for _ in 0..self.stepper.advance(ctx.delta_time) {
+            self.grid.step();
+        }
+        if self.grid.population() == 0 {
+            self.reseed(ctx);
+        }
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        self.grid.draw(&mut ctx.canvas, 0, 0);
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
@@ -0,0 +1,120 @@
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use crossterm::style::Color;
+use rand::{rngs::StdRng, SeedableRng};
+use shape_engine_core::{
+    lsystem::Module, parse_color, Context, LSystem, Param, Point, Scene, SceneConfig, Turtle,
+};
+
+/// An L-system grown from a grammar declared in the scene's settings and
+/// scaled to fit the canvas.
+///
+/// Settings: `axiom`, `rules` (an array like `["F -> FF", "X -> F[+X]F[-X]+X"]`,
+/// see `lsystem::Rule`), `angle` (degrees), `iterations`, `heading` (start
+/// direction, 90 is up), `growth` (seconds to trace the drawing, 0 draws it
+/// at once), `symbol` and `color`. `growth` may be keyframed.
+pub struct LSystemScene {
+    system: LSystem,
+    iterations: u32,
+    heading: f32,
+    growth: Param,
+    symbol: char,
+    color: Option<Color>,
+    word: Vec<Module>,
+    start: Point,
+    /// Share of the drawing traced so far, 0–1.
+    progress: f32,
+}
+
+impl LSystemScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let name = &scene_def.name;
+        let setting = |key: &str| scene_def.settings.get(key).and_then(|v| v.as_str());
+        let axiom = setting("axiom").ok_or_else(|| anyhow!("Scene '{name}': missing axiom"))?;
+        let angle = scene_def.float_setting("angle")?.unwrap_or(25.0);
+        let mut system = LSystem::new(axiom, angle).with_context(|| format!("Scene '{name}'"))?;
+        let rules = match scene_def.settings.get("rules") {
+            Some(rules) => rules
+                .as_array()
+                .ok_or_else(|| anyhow!("Scene '{name}': rules must be an array of strings"))?
+                .clone(),
+            None => Vec::new(),
+        };
+        for rule in &rules {
+            let Some(rule) = rule.as_str() else {
+                bail!("Scene '{name}': rules must be an array of strings");
+            };
+            system = system
+                .with_rule(rule)
+                .with_context(|| format!("Scene '{name}'"))?;
+        }
+        let color = match setting("color") {
+            Some(text) => match parse_color(text) {
+                Some(color) => Some(color),
+                None => bail!("Scene '{name}': unknown colour '{text}'"),
+            },
+            None => None,
+        };
+        Ok(Self {
+            system,
+            iterations: scene_def.float_setting("iterations")?.unwrap_or(4.0) as u32,
+            heading: scene_def.float_setting("heading")?.unwrap_or(90.0),
+            growth: super::param(scene_def, "growth", 4.0)?,
+            symbol: setting("symbol")
+                .and_then(|s| s.chars().next())
+                .unwrap_or('*'),
+            color,
+            word: Vec::new(),
+            start: Point::default(),
+            progress: 0.0,
+        })
+    }
+}
+
+impl Scene for LSystemScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        let seed = ctx.seed();
+        self.word = match ctx.rng() {
+            Some(rng) => self.system.generate(self.iterations, rng),
+            None => self
+                .system
+                .generate(self.iterations, &mut StdRng::seed_from_u64(seed)),
+        };
+        self.progress = 0.0;
+
+        // Fit the drawing's extent into the canvas, leaving a one-cell margin.
+        self.system.step = 1.0;
+        let (min, max) = self.system.extent(&self.word, self.heading);
+        let (width, height) = (ctx.canvas.width as f32, ctx.canvas.height as f32);
+        let (span_x, span_y) = ((max.x - min.x).max(1e-3), (max.y - min.y).max(1e-3));
+        let step = ((width - 2.0) / span_x).min((height - 2.0) / span_y);
+        self.system.step = step;
+        self.start = Point::new(
+            (width - span_x * step) / 2.0 - min.x * step,
+            (height - span_y * step) / 2.0 - min.y * step,
+        );
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        // Integrated rather than `time / growth`, so a keyframed growth
+        // changes the pace without jumping.
+        let growth = self.growth.value(ctx.scene_time);
+        self.progress = if growth > 0.0 {
+            (self.progress + ctx.delta_time / growth).min(1.0)
+        } else {
+            1.0
+        };
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        ctx.canvas.set_symbol(self.symbol);
+        if let Some(color) = self.color {
+            ctx.canvas.set_foreground_color(color);
+        }
+        let mut turtle = Turtle::new(&mut ctx.canvas);
+        turtle.jump_to(self.start.x, self.start.y);
+        turtle.set_heading(self.heading);
+        self.system.draw(&mut turtle, &self.word, self.progress);
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
@@ -0,0 +1,111 @@
+//! Stock scenes selected by name from the configuration file.
+
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::KeyCode;
+use shape_engine_core::{
+    parse_color, Canvas, InputState, Param, PixelMode, Point, Rgba, SceneConfig,
+};
+
+mod boids;
+mod donut3d;
+mod easings;
+mod flow;
+mod fluid;
+mod fractal;
+mod life;
+mod lsystem;
+mod noise;
+mod particles;
+mod plasma;
+mod reaction;
+mod walkers;
+
+pub use boids::BoidsScene;
+pub use donut3d::Donut3dScene;
+pub use easings::EasingsScene;
+pub use flow::FlowScene;
+pub use fluid::FluidScene;
+pub use fractal::FractalScene;
+pub use life::LifeScene;
+pub use lsystem::LSystemScene;
+pub use noise::NoiseScene;
+pub use particles::ParticlesScene;
+pub use plasma::PlasmaScene;
+pub use reaction::ReactionScene;
+pub use walkers::WalkersScene;
+
+/// Reads a setting that may be keyframed, falling back to `default`.
+fn param(scene_def: &SceneConfig, key: &str, default: f32) -> Result<Param> {
+    Ok(scene_def.param(key)?.unwrap_or(Param::Constant(default)))
+}
+
+/// Reads a setting holding an array of colour names or hex codes.
+fn color_list(scene_def: &SceneConfig, key: &str) -> Result<Vec<Rgba>> {
+    let name = &scene_def.name;
+    let Some(values) = scene_def.settings.get(key) else {
+        return Ok(Vec::new());
+    };
+    let values = values
+        .as_array()
+        .ok_or_else(|| anyhow!("Scene '{name}': {key} must be an array of colours"))?;
+    values
+        .iter()
+        .map(|value| {
+            let text = value.as_str().unwrap_or_default();
+            match parse_color(text).and_then(Rgba::from_color) {
+                Some(color) => Ok(color),
+                None => bail!("Scene '{name}': unknown colour '{text}'"),
+            }
+        })
+        .collect()
+}
+
+/// Brush speed in cell widths per second.
+const BRUSH_SPEED: f32 = 30.0;
+
+/// A cursor for painting into simulation grids: the arrow keys move it and
+/// holding space paints.
+struct Brush {
+    /// Position in canvas cells.
+    position: Point,
+}
+
+impl Brush {
+    fn new(width: u16, height: u16) -> Self {
+        Self {
+            position: Point::new(width as f32 / 2.0, height as f32 / 2.0),
+        }
+    }
+
+    /// Moves the brush with the held arrow keys, keeping it on the canvas.
+    /// Returns its velocity in cells per second while space is held, so
+    /// the caller can paint and push along the stroke.
+    fn steer(&mut self, input: &InputState, dt: f32, (width, height): (u16, u16)) -> Option<Point> {
+        let axis = |negative: KeyCode, positive: KeyCode| {
+            input.is_key_pressed(positive) as i32 as f32
+                - input.is_key_pressed(negative) as i32 as f32
+        };
+        // Cells are twice as tall as wide, so vertical moves cover half as
+        // many cells to look equally fast.
+        let velocity = Point::new(
+            axis(KeyCode::Left, KeyCode::Right) * BRUSH_SPEED,
+            axis(KeyCode::Up, KeyCode::Down) * BRUSH_SPEED * 0.5,
+        );
+        self.position.x = (self.position.x + velocity.x * dt).clamp(0.0, width as f32 - 1.0);
+        self.position.y = (self.position.y + velocity.y * dt).clamp(0.0, height as f32 - 1.0);
+        input.is_key_pressed(KeyCode::Char(' ')).then_some(velocity)
+    }
+
+    /// The brush position in logical pixels of `mode`.
+    fn pixel_position(&self, mode: PixelMode) -> Point {
+        let (sx, sy) = mode.cell_resolution();
+        Point::new(
+            (self.position.x + 0.5) * sx as f32,
+            (self.position.y + 0.5) * sy as f32,
+        )
+    }
+
+    fn draw(&self, canvas: &mut Canvas<'_>) {
+        canvas.draw_text(self.position.x as u16, self.position.y as u16, "+");
+    }
+}
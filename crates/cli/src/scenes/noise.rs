@@ -0,0 +1,107 @@
+use anyhow::{bail, Result};
+use shape_engine_core::{
+    Context, DomainWarp, Fractal, FractalKind, Noise3, OpenSimplex, Param, Perlin, Scene,
+    SceneConfig, Worley,
+};
+
+type Field = Box<dyn Noise3 + Send + Sync>;
+
+/// An animated noise field drawn with `Canvas::shade`, seeded from the
+/// engine seed so a run can be replayed.
+///
+/// Settings: `source` (`perlin`, `simplex` or `worley`), `fractal` (`none`,
+/// `fbm`, `turbulence` or `ridged`), `octaves`, `warp` (domain warp
+/// strength), `scale` (feature size in cells) and `speed`. `scale` and `speed`
+/// may be keyframed.
+pub struct NoiseScene {
+    source: String,
+    fractal: Option<FractalKind>,
+    octaves: u32,
+    warp: f32,
+    scale: Param,
+    speed: Param,
+    phase: f32,
+    field: Option<Field>,
+}
+
+impl NoiseScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let setting = |key: &str| scene_def.settings.get(key).and_then(|v| v.as_str());
+        let source = setting("source").unwrap_or("simplex").to_string();
+        if !matches!(source.as_str(), "perlin" | "simplex" | "worley") {
+            bail!(
+                "Scene '{}': unknown noise source '{source}'",
+                scene_def.name
+            );
+        }
+        let fractal = match setting("fractal").unwrap_or("fbm") {
+            "none" => None,
+            "fbm" => Some(FractalKind::Fbm),
+            "turbulence" => Some(FractalKind::Turbulence),
+            "ridged" => Some(FractalKind::Ridged),
+            other => bail!("Scene '{}': unknown fractal '{other}'", scene_def.name),
+        };
+        Ok(Self {
+            source,
+            fractal,
+            octaves: scene_def.float_setting("octaves")?.unwrap_or(4.0) as u32,
+            warp: scene_def.float_setting("warp")?.unwrap_or(0.0),
+            scale: super::param(scene_def, "scale", 12.0)?,
+            speed: super::param(scene_def, "speed", 0.3)?,
+            phase: 0.0,
+            field: None,
+        })
+    }
+
+    fn build(&self, seed: u64) -> Field {
+        let source: Field = match self.source.as_str() {
+            "perlin" => Box::new(Perlin::new(seed)),
+            "worley" => Box::new(Worley::new(seed)),
+            _ => Box::new(OpenSimplex::new(seed)),
+        };
+        let field = match self.fractal {
+            Some(kind) => Box::new(Fractal::new(source, kind).with_octaves(self.octaves)),
+            None => source,
+        };
+        if self.warp == 0.0 {
+            return field;
+        }
+        let warp = Fractal::fbm(OpenSimplex::new(seed.wrapping_add(1))).with_octaves(2);
+        Box::new(DomainWarp::new(field, warp, self.warp))
+    }
+}
+
+impl Scene for NoiseScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        self.field = Some(self.build(ctx.seed()));
+        self.phase = 0.0;
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        self.phase += self.speed.value(ctx.scene_time) * ctx.delta_time;
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        let Some(field) = &self.field else {
+            return;
+        };
+        // Logical pixels back to square units, cells being twice as tall as wide
+        let (sx, sy) = ctx.canvas.current_pixel_mode.cell_resolution();
+        let scale = self.scale.value(ctx.scene_time).max(0.1);
+        let (unit_x, unit_y) = (scale * sx as f32, scale * sy as f32 * 0.5);
+        let unsigned = matches!(
+            (self.source.as_str(), self.fractal),
+            ("worley", _) | (_, Some(FractalKind::Turbulence | FractalKind::Ridged))
+        );
+        ctx.canvas.shade(self.phase, |x, y, t| {
+            let value = field.noise3(x / unit_x, y / unit_y, t);
+            if unsigned {
+                value
+            } else {
+                value * 0.5 + 0.5
+            }
+        });
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
@@ -0,0 +1,151 @@
+use anyhow::{bail, Result};
+use rand::Rng;
+use shape_engine_core::{
+    time::FixedStep, Context, Emitter, EmitterShape, Force, ParticleSystem, Point, Rgba, Scene,
+    SceneConfig,
+};
+
+/// Seconds between firework bursts.
+const FIREWORK_INTERVAL: f32 = 0.9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Preset {
+    Fireworks,
+    Rain,
+    Smoke,
+}
+
+/// Particle effects driven by the run's RNG and a fixed-step update.
+///
+/// Settings: `preset` (`fireworks`, `rain` or `smoke`) and `steps` (fixed
+/// simulation steps per second, 60 by default).
+pub struct ParticlesScene {
+    preset: Preset,
+    stepper: FixedStep,
+    system: ParticleSystem,
+    next_burst: f32,
+}
+
+impl ParticlesScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let preset = match scene_def
+            .settings
+            .get("preset")
+            .and_then(|v| v.as_str())
+            .unwrap_or("fireworks")
+        {
+            "fireworks" => Preset::Fireworks,
+            "rain" => Preset::Rain,
+            "smoke" => Preset::Smoke,
+            other => bail!(
+                "Scene '{}': unknown particle preset '{other}'",
+                scene_def.name
+            ),
+        };
+        let steps = scene_def.float_setting("steps")?.unwrap_or(60.0).max(1.0);
+        Ok(Self {
+            preset,
+            stepper: FixedStep::new(steps),
+            system: ParticleSystem::new(),
+            next_burst: 0.0,
+        })
+    }
+
+    fn build(&self, width: f32, height: f32) -> ParticleSystem {
+        match self.preset {
+            Preset::Fireworks => ParticleSystem::new()
+                .with_emitter(
+                    Emitter::new(EmitterShape::Point(Point::new(width / 2.0, height / 3.0)))
+                        .with_rate(0.0)
+                        .with_direction(90.0, 360.0)
+                        .with_speed(6.0..16.0)
+                        .with_lifetime(0.8..1.8),
+                )
+                .with_force(Force::Gravity(Point::new(0.0, 5.0)))
+                .with_force(Force::Drag(1.2))
+                .with_colors(vec![
+                    Rgba::rgb(255, 255, 255),
+                    Rgba::rgb(255, 220, 80),
+                    Rgba::rgb(255, 110, 30),
+                    Rgba::rgb(90, 10, 10),
+                ])
+                .with_glyphs("*+:."),
+            Preset::Rain => ParticleSystem::new()
+                .with_emitter(
+                    Emitter::new(EmitterShape::Line {
+                        start: Point::new(-width * 0.2, -1.0),
+                        end: Point::new(width, -1.0),
+                    })
+                    .with_rate(width * 2.0)
+                    .with_direction(285.0, 4.0)
+                    .with_speed(40.0..60.0)
+                    .with_lifetime(2.0..2.0),
+                )
+                .with_force(Force::Gravity(Point::new(0.0, 10.0)))
+                .with_colors(vec![Rgba::rgb(170, 200, 255), Rgba::rgb(60, 90, 200)])
+                .with_glyphs("|"),
+            Preset::Smoke => ParticleSystem::new()
+                .with_emitter(
+                    Emitter::new(EmitterShape::Area {
+                        x: width / 2.0 - 3.0,
+                        y: height - 2.0,
+                        width: 6.0,
+                        height: 1.0,
+                    })
+                    .with_rate(40.0)
+                    .with_direction(90.0, 50.0)
+                    .with_speed(2.0..6.0)
+                    .with_lifetime(2.5..5.0),
+                )
+                // Buoyancy, plus a slow drift to the right.
+                .with_force(Force::Gravity(Point::new(1.5, -1.5)))
+                .with_force(Force::Drag(0.4))
+                .with_colors(vec![
+                    Rgba::rgb(230, 230, 230),
+                    Rgba::rgb(140, 140, 150),
+                    Rgba::rgb(50, 50, 60),
+                ])
+                .with_glyphs("@Oo°."),
+        }
+    }
+}
+
+impl Scene for ParticlesScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        let (width, height) = (ctx.canvas.width as f32, ctx.canvas.height as f32);
+        self.system = self.build(width, height);
+        self.next_burst = 0.0;
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        let (width, height) = (ctx.canvas.width as f32, ctx.canvas.height as f32);
+        let steps = self.stepper.advance(ctx.delta_time);
+        let step = self.stepper.step();
+        let Some(rng) = ctx.rng() else {
+            return;
+        };
+        for _ in 0..steps {
+            if self.preset == Preset::Fireworks {
+                self.next_burst -= step;
+                if self.next_burst <= 0.0 {
+                    self.next_burst = FIREWORK_INTERVAL;
+                    let emitter = &mut self.system.emitters[0];
+                    emitter.shape = EmitterShape::Point(Point::new(
+                        rng.gen_range(0.15..0.85) * width,
+                        rng.gen_range(0.15..0.5) * height,
+                    ));
+                    emitter.burst(150);
+                }
+            }
+            self.system.update(step, rng);
+        }
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        self.system.draw(&mut ctx.canvas);
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {
+        self.system.clear();
+    }
+}
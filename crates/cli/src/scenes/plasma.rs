@@ -0,0 +1,60 @@
+use anyhow::Result;
+use shape_engine_core::{Context, Param, Rgba, Scene, SceneConfig, Shade};
+
+/// A full-screen plasma drawn with `Canvas::shade`.
+///
+/// Settings: `scale` (pattern size in cells) and `speed`, both of which
+/// may be keyframed.
+pub struct PlasmaScene {
+    scale: Param,
+    speed: Param,
+    phase: f32,
+}
+
+impl PlasmaScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        Ok(Self {
+            scale: super::param(scene_def, "scale", 8.0)?,
+            speed: super::param(scene_def, "speed", 1.0)?,
+            phase: 0.0,
+        })
+    }
+}
+
+impl Scene for PlasmaScene {
+    fn on_start(&mut self, _ctx: &mut Context<'_>) {
+        self.phase = 0.0;
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        // Integrated rather than `time * speed`, so keyframed speeds change
+        // the pace without jumping.
+        self.phase += self.speed.value(ctx.scene_time) * ctx.delta_time;
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        // Scale logical pixels back to cell units (cells being twice as tall
+        // as wide) so the pattern looks the same in every pixel mode.
+        let (sx, sy) = ctx.canvas.current_pixel_mode.cell_resolution();
+        let scale = self.scale.value(ctx.scene_time).max(0.1);
+        let (unit_x, unit_y) = (scale * sx as f32, scale * sy as f32 * 0.5);
+        ctx.canvas.shade(self.phase, |x, y, t| {
+            let (u, v) = (x / unit_x, y / unit_y);
+            let value = (u + t).sin()
+                + (v * 0.8 - t * 0.7).sin()
+                + ((u + v) * 0.6 + t * 0.5).sin()
+                + ((u * u + v * v).sqrt() * 0.7 - t).sin();
+            let phase = value * 0.25;
+            let channel = |offset: f32| {
+                (((phase + offset) * std::f32::consts::TAU).sin() * 127.5 + 127.5) as u8
+            };
+            Shade::new(phase * 0.5 + 0.5).with_color(Rgba::rgb(
+                channel(0.0),
+                channel(0.33),
+                channel(0.67),
+            ))
+        });
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
@@ -0,0 +1,109 @@
+use anyhow::{bail, Result};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use shape_engine_core::{time::FixedStep, Context, GrayScott, Param, Rgba, Scene, SceneConfig};
+
+use super::Brush;
+
+/// Gray–Scott reaction-diffusion on a grid matching the canvas' logical
+/// resolution, started from a few random drops of `v`. Arrow keys move a
+/// brush and holding space drops in more.
+///
+/// Settings: `preset` (`coral`, `mitosis`, `maze`, `spots`, `worms` or
+/// `solitons`), `feed` and `kill` (override the preset's rates), `seeds`
+/// (starting drops), `radius` (drop radius in logical pixels), `speed`
+/// (simulation steps per second) and `colors` (ramp from empty to full).
+/// `feed` and `kill` may be keyframed, sweeping through the presets, and so
+/// may `radius`.
+pub struct ReactionScene {
+    feed: Param,
+    kill: Param,
+    seeds: usize,
+    radius: Param,
+    colors: Vec<Rgba>,
+    stepper: FixedStep,
+    reaction: Option<GrayScott>,
+    brush: Option<Brush>,
+}
+
+impl ReactionScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let preset = scene_def.settings.get("preset").and_then(|v| v.as_str());
+        let (feed, kill) = match preset.unwrap_or("coral") {
+            "coral" => (0.0545, 0.062),
+            "mitosis" => (0.0367, 0.0649),
+            "maze" => (0.029, 0.057),
+            "spots" => (0.035, 0.065),
+            "worms" => (0.078, 0.061),
+            "solitons" => (0.03, 0.062),
+            other => bail!("Scene '{}': unknown preset '{other}'", scene_def.name),
+        };
+        let speed = scene_def.float_setting("speed")?.unwrap_or(600.0).max(1.0);
+        Ok(Self {
+            feed: super::param(scene_def, "feed", feed)?,
+            kill: super::param(scene_def, "kill", kill)?,
+            seeds: scene_def.float_setting("seeds")?.unwrap_or(6.0) as usize,
+            radius: super::param(scene_def, "radius", 4.0)?,
+            colors: super::color_list(scene_def, "colors")?,
+            // Dropping steps on slow frames slows the pattern down rather
+            // than stalling the terminal.
+            stepper: FixedStep::new(speed).with_max_steps((speed / 10.0).ceil() as u32),
+            reaction: None,
+            brush: None,
+        })
+    }
+}
+
+impl Scene for ReactionScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        let (width, height) = ctx.canvas.logical_size();
+        let mut reaction = GrayScott::new(width, height)
+            .with_rates(self.feed.value(0.0), self.kill.value(0.0))
+            .with_colors(self.colors.clone());
+        let seed = ctx.seed();
+        let mut fallback = StdRng::seed_from_u64(seed);
+        let rng = match ctx.rng() {
+            Some(rng) => rng,
+            None => &mut fallback,
+        };
+        let radius = self.radius.value(0.0).max(0.5);
+        for _ in 0..self.seeds {
+            let x = rng.gen::<f32>() * width as f32;
+            let y = rng.gen::<f32>() * height as f32;
+            reaction.inject(x, y, radius);
+        }
+        self.reaction = Some(reaction);
+        self.brush = Some(Brush::new(ctx.canvas.width, ctx.canvas.height));
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        let Some(reaction) = &mut self.reaction else {
+            return;
+        };
+        let (width, height) = ctx.canvas.logical_size();
+        reaction.resize(width, height);
+        reaction.feed = self.feed.value(ctx.scene_time);
+        reaction.kill = self.kill.value(ctx.scene_time);
+        let canvas_size = (ctx.canvas.width, ctx.canvas.height);
+        if let (Some(brush), Some(input)) = (&mut self.brush, ctx.input()) {
+            if brush.steer(input, ctx.delta_time, canvas_size).is_some() {
+                let at = brush.pixel_position(ctx.canvas.current_pixel_mode);
+                let radius = self.radius.value(ctx.scene_time).max(0.5);
+                reaction.inject(at.x, at.y, radius);
+            }
+        }
+        for _ in 0..self.stepper.advance(ctx.delta_time) {
+            reaction.step();
+        }
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        if let Some(reaction) = &self.reaction {
+            reaction.draw(&mut ctx.canvas);
+        }
+        if let Some(brush) = &self.brush {
+            brush.draw(&mut ctx.canvas);
+        }
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
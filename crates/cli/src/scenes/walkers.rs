@@ -0,0 +1,133 @@
+use anyhow::{bail, Result};
+use rand::Rng;
+use shape_engine_core::{
+    time::FixedStep, Agent, Agents, Context, Edges, Param, Point, RandomWalk, Rgba, Scene,
+    SceneConfig,
+};
+
+/// Random walkers leaving fading trails, driven by the run's seeded RNG.
+///
+/// Settings: `count`, `step` (cells per step), `turn` (largest turn per step
+/// in degrees), `lattice` (grid directions only), `bias` (0–1 pull towards
+/// `bias_direction`, in degrees), `edges` (`wrap` or `bounce`), `start`
+/// (`center` or `random`), `trail`, `fade`, `speed` (steps per second) and
+/// `colors` (cycled through the walkers). `step`, `turn`, `bias`,
+/// `bias_direction`, `fade` and `speed` may be keyframed.
+pub struct WalkersScene {
+    count: usize,
+    step: Param,
+    turn: Param,
+    lattice: bool,
+    bias: Param,
+    bias_direction: Param,
+    edges: Edges,
+    random_start: bool,
+    trail: usize,
+    fade: Param,
+    colors: Vec<Rgba>,
+    speed: Param,
+    stepper: FixedStep,
+    agents: Option<Agents<RandomWalk>>,
+}
+
+impl WalkersScene {
+    pub fn from_config(scene_def: &SceneConfig) -> Result<Self> {
+        let name = &scene_def.name;
+        let setting = |key: &str| scene_def.settings.get(key).and_then(|v| v.as_str());
+        let edges = match setting("edges").unwrap_or("wrap") {
+            "wrap" => Edges::Wrap,
+            "bounce" => Edges::Bounce,
+            other => bail!("Scene '{name}': unknown edges '{other}'"),
+        };
+        let random_start = match setting("start").unwrap_or("center") {
+            "center" => false,
+            "random" => true,
+            other => bail!("Scene '{name}': unknown start '{other}'"),
+        };
+        let defaults = RandomWalk::default();
+        let speed = super::param(scene_def, "speed", 30.0)?;
+        Ok(Self {
+            count: scene_def.float_setting("count")?.unwrap_or(12.0) as usize,
+            step: super::param(scene_def, "step", defaults.step)?,
+            turn: super::param(scene_def, "turn", defaults.turn)?,
+            lattice: scene_def
+                .settings
+                .get("lattice")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.lattice),
+            bias: super::param(scene_def, "bias", defaults.bias)?,
+            bias_direction: super::param(scene_def, "bias_direction", defaults.bias_direction)?,
+            edges,
+            random_start,
+            trail: scene_def.float_setting("trail")?.unwrap_or(200.0) as usize,
+            fade: super::param(scene_def, "fade", 0.8)?,
+            colors: super::color_list(scene_def, "colors")?,
+            stepper: FixedStep::new(speed.value(0.0).max(0.1)),
+            speed,
+            agents: None,
+        })
+    }
+
+    /// The walk rule and trail fade at `time`.
+    fn tune(&self, agents: &mut Agents<RandomWalk>, time: f32) {
+        agents.rule = RandomWalk {
+            step: self.step.value(time),
+            turn: self.turn.value(time),
+            lattice: self.lattice,
+            bias: self.bias.value(time),
+            bias_direction: self.bias_direction.value(time),
+        };
+        agents.fade = self.fade.value(time);
+    }
+}
+
+impl Scene for WalkersScene {
+    fn on_start(&mut self, ctx: &mut Context<'_>) {
+        let (width, height) = (ctx.canvas.width as f32, ctx.canvas.height as f32);
+        let mut agents = Agents::new(RandomWalk::default(), width, height)
+            .with_edges(self.edges)
+            .with_trail_length(self.trail);
+        self.tune(&mut agents, 0.0);
+        agents.glyphs = "@*+:.".chars().collect();
+        let Some(rng) = ctx.rng() else {
+            return;
+        };
+        for index in 0..self.count {
+            let position = if self.random_start {
+                Point::new(rng.gen::<f32>() * width, rng.gen::<f32>() * height)
+            } else {
+                Point::new(width / 2.0, height / 2.0)
+            };
+            let mut agent = Agent::new(position, rng.gen_range(0.0..360.0));
+            if !self.colors.is_empty() {
+                agent = agent.with_color(self.colors[index % self.colors.len()]);
+            }
+            agents.push(agent);
+        }
+        self.agents = Some(agents);
+    }
+
+    fn on_update(&mut self, ctx: &mut Context<'_>) {
+        let time = ctx.scene_time;
+        self.stepper.set_rate(self.speed.value(time).max(0.1));
+        let steps = self.stepper.advance(ctx.delta_time);
+        let Some(mut agents) = self.agents.take() else {
+            return;
+        };
+        self.tune(&mut agents, time);
+        if let Some(rng) = ctx.rng() {
+            for _ in 0..steps {
+                agents.step(rng);
+            }
+        }
+        self.agents = Some(agents);
+    }
+
+    fn on_draw(&mut self, ctx: &mut Context<'_>) {
+        if let Some(agents) = &self.agents {
+            agents.draw(&mut ctx.canvas);
+        }
+    }
+
+    fn on_exit(&mut self, _ctx: &mut Context<'_>) {}
+}
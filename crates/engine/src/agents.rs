@@ -0,0 +1,249 @@
+use std::collections::VecDeque;
+
+use rand::{Rng, RngCore};
+
+use crate::color::Rgba;
+use crate::draw::{Canvas, PixelMode};
+use crate::fill::pick_stop;
+use crate::flow::trail_points;
+use crate::path::Point;
+use crate::plot::PlotPoint;
+
+/// An entity moving over the canvas, leaving a trail.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Agent {
+    /// Position in canvas cells.
+    pub position: Point,
+    /// Degrees counter-clockwise from east, so 90 points up the screen.
+    pub heading: f32,
+    /// Trail colour; `None` uses the canvas' foreground colour.
+    pub color: Option<Rgba>,
+    /// Past positions, newest first.
+    pub trail: VecDeque<Point>,
+}
+
+impl Agent {
+    pub fn new(position: Point, heading: f32) -> Self {
+        Self {
+            position,
+            heading,
+            color: None,
+            trail: VecDeque::from([position]),
+        }
+    }
+
+    pub fn with_color(self, color: Rgba) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+}
+
+/// Decides how an agent moves on each step: it may turn the agent or change
+/// its colour, and returns how far to move along the new heading, in cells.
+///
+/// Closures taking `(&mut Agent, &mut dyn RngCore)` are rules too.
+pub trait StepRule {
+    fn step(&mut self, agent: &mut Agent, rng: &mut dyn RngCore) -> f32;
+}
+
+impl<F: FnMut(&mut Agent, &mut dyn RngCore) -> f32> StepRule for F {
+    fn step(&mut self, agent: &mut Agent, rng: &mut dyn RngCore) -> f32 {
+        self(agent, rng)
+    }
+}
+
+/// A random walk: each step turns by a random angle up to `turn` degrees
+/// either way, optionally leaning towards `bias_direction`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomWalk {
+    /// Distance per step in cells.
+    pub step: f32,
+    /// Largest turn per step in degrees; 180 or more picks any direction.
+    pub turn: f32,
+    /// Restricts headings to the four grid directions, for a lattice walk.
+    pub lattice: bool,
+    /// How strongly (0–1) headings are pulled towards `bias_direction`.
+    pub bias: f32,
+    /// Direction of the drift, in degrees counter-clockwise from east.
+    pub bias_direction: f32,
+}
+
+impl Default for RandomWalk {
+    fn default() -> Self {
+        Self {
+            step: 1.0,
+            turn: 180.0,
+            lattice: false,
+            bias: 0.0,
+            bias_direction: 90.0,
+        }
+    }
+}
+
+impl StepRule for RandomWalk {
+    fn step(&mut self, agent: &mut Agent, rng: &mut dyn RngCore) -> f32 {
+        let turn = self.turn.abs().min(180.0);
+        let mut heading = agent.heading + rng.gen_range(-turn..=turn);
+        if self.bias > 0.0 {
+            // Blend the unit vectors rather than the angles, so the pull
+            // always takes the short way round.
+            let (h, b) = (heading.to_radians(), self.bias_direction.to_radians());
+            let bias = self.bias.clamp(0.0, 1.0);
+            let x = h.cos() * (1.0 - bias) + b.cos() * bias;
+            let y = h.sin() * (1.0 - bias) + b.sin() * bias;
+            if x.hypot(y) > f32::EPSILON {
+                heading = y.atan2(x).to_degrees();
+            }
+        }
+        if self.lattice {
+            heading = (heading / 90.0).round() * 90.0;
+        }
+        agent.heading = heading.rem_euclid(360.0);
+        self.step
+    }
+}
+
+/// What happens to agents reaching the edge of their area.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Edges {
+    /// Leave one side and come back on the opposite one.
+    #[default]
+    Wrap,
+    /// Reflect off the edge like a ball.
+    Bounce,
+}
+
+/// A set of agents moved by a shared `StepRule` inside a `width` x
+/// `height` cell area. Draw with `Agents::draw`.
+#[derive(Debug, Clone)]
+pub struct Agents<R> {
+    pub agents: Vec<Agent>,
+    pub rule: R,
+    pub edges: Edges,
+    /// Positions remembered per agent, current one included.
+    pub trail_length: usize,
+    /// How much trails darken towards their tail, 0–1.
+    pub fade: f32,
+    /// Scale applied to vertical movement; 0.5 for cells twice as tall as
+    /// they are wide.
+    pub aspect: f32,
+    /// ASCII glyph from head to tail; empty draws `.`.
+    pub glyphs: Vec<char>,
+    width: f32,
+    height: f32,
+}
+
+impl<R: StepRule> Agents<R> {
+    pub fn new(rule: R, width: f32, height: f32) -> Self {
+        Self {
+            agents: Vec::new(),
+            rule,
+            edges: Edges::default(),
+            trail_length: 100,
+            fade: 0.0,
+            aspect: 0.5,
+            glyphs: Vec::new(),
+            width,
+            height,
+        }
+    }
+
+    pub fn with_edges(self, edges: Edges) -> Self {
+        Self { edges, ..self }
+    }
+
+    pub fn with_trail_length(self, trail_length: usize) -> Self {
+        Self {
+            trail_length,
+            ..self
+        }
+    }
+
+    pub fn push(&mut self, agent: Agent) {
+        self.agents.push(agent);
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Applies the rule to every agent once, moves it and records its trail.
+    pub fn step(&mut self, rng: &mut dyn RngCore) {
+        let (width, height) = (self.width.max(f32::EPSILON), self.height.max(f32::EPSILON));
+        for agent in &mut self.agents {
+            let distance = self.rule.step(agent, rng);
+            let radians = agent.heading.to_radians();
+            let mut next = Point::new(
+                agent.position.x + radians.cos() * distance,
+                agent.position.y - radians.sin() * distance * self.aspect,
+            );
+            match self.edges {
+                Edges::Wrap => {
+                    let wrapped = Point::new(next.x.rem_euclid(width), next.y.rem_euclid(height));
+                    if wrapped != next {
+                        // Don't draw a trail across the whole area.
+                        agent.trail.clear();
+                        next = wrapped;
+                    }
+                }
+                Edges::Bounce => {
+                    if !(0.0..=width).contains(&next.x) {
+                        next.x = reflect(next.x, width);
+                        agent.heading = (180.0 - agent.heading).rem_euclid(360.0);
+                    }
+                    if !(0.0..=height).contains(&next.y) {
+                        next.y = reflect(next.y, height);
+                        agent.heading = (-agent.heading).rem_euclid(360.0);
+                    }
+                }
+            }
+            agent.position = next;
+            agent.trail.push_front(next);
+            agent.trail.truncate(self.trail_length.max(1));
+        }
+    }
+
+    /// Draws each agent's trail as a line of logical pixels in its colour
+    /// through `Canvas::plot_points`, darkening towards the tail by `fade`.
+    pub fn draw(&self, canvas: &mut Canvas<'_>) {
+        let mode = canvas.current_pixel_mode;
+        canvas.plot_points(agent_points(self, mode, canvas.foreground_rgba()));
+    }
+}
+
+/// Folds `value` back into `0..=limit` as if bouncing off both ends.
+fn reflect(value: f32, limit: f32) -> f32 {
+    let period = 2.0 * limit;
+    let folded = value.rem_euclid(period);
+    if folded > limit {
+        period - folded
+    } else {
+        folded
+    }
+}
+
+/// Logical pixels along every agent's trail, oldest first.
+fn agent_points<R>(agents: &Agents<R>, mode: PixelMode, default_color: Rgba) -> Vec<PlotPoint> {
+    let mut points = Vec::new();
+    let fade = agents.fade.clamp(0.0, 1.0);
+    for agent in &agents.agents {
+        let color = agent.color.unwrap_or(default_color);
+        let paint = |t: f32| {
+            let factor = 1.0 - fade * t;
+            let scale = |c: u8| (c as f32 * factor).round() as u8;
+            (
+                Rgba::rgb(scale(color.r), scale(color.g), scale(color.b)),
+                pick_stop(&agents.glyphs, t).unwrap_or('.'),
+            )
+        };
+        trail_points(agent.trail.iter().rev().copied(), paint, mode, &mut points);
+    }
+    points
+}
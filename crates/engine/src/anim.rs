@@ -0,0 +1,877 @@
+//! Easing, tweens and timelines.
+//!
+//! Animations are pure functions of time: `Animation::sample` gives the
+//! value at any moment, and combinators (`delay`, `repeat`, `yoyo`, `join`,
+//! `Sequence`) build new animations out of old ones. A `Timeline` owns the
+//! clock and is advanced by the scene:
+//!
+//! ```text
+//! let bounce = Tween::new(0.0, 20.0, 1.5).with_easing(Easing::BounceOut);
+//! self.timeline = Timeline::new(bounce.delay(0.5).yoyo().repeat_forever());
+//!
+//! // on_update; delta_time is zero while the engine is paused
+//! self.timeline.update(ctx.delta_time);
+//! let y = self.timeline.value();
+//! ```
+
+use std::f32::consts::PI;
+
+use anyhow::{anyhow, Result};
+use serde::de::{value, IntoDeserializer};
+use serde::Deserialize;
+
+use crate::color::Rgba;
+use crate::math3d::Vec3;
+use crate::path::Point;
+
+/// Maps linear progress (0–1) to eased progress. `In` variants start
+/// slowly, `Out` variants end slowly and `InOut` do both. Back and elastic
+/// easings overshoot outside 0–1 before settling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    SineIn,
+    SineOut,
+    SineInOut,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    /// Parses a snake_case easing name such as `cubic_in_out`, as written
+    /// in config files.
+    pub fn parse(name: &str) -> Result<Self> {
+        let deserializer: value::StrDeserializer<value::Error> = name.trim().into_deserializer();
+        Self::deserialize(deserializer).map_err(|_| anyhow!("Unknown easing '{name}'"))
+    }
+
+    /// Eased progress for `t`, which is clamped to 0–1. Every easing maps
+    /// 0 to 0 and 1 to 1.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        // Overshoot of the back easings: about 10%.
+        const BACK: f32 = 1.70158;
+        const BACK_IN_OUT: f32 = BACK * 1.525;
+        match self {
+            Easing::Linear => t,
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => -((t * PI).cos() - 1.0) / 2.0,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => in_out(t, |t| t * t),
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => in_out(t, |t| t * t * t),
+            Easing::BackIn => (BACK + 1.0) * t * t * t - BACK * t * t,
+            Easing::BackOut => 1.0 + (BACK + 1.0) * (t - 1.0).powi(3) + BACK * (t - 1.0).powi(2),
+            Easing::BackInOut => {
+                in_out(t, |t| (BACK_IN_OUT + 1.0) * t * t * t - BACK_IN_OUT * t * t)
+            }
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => 1.0 - elastic_in(1.0 - t),
+            Easing::ElasticInOut => in_out(t, elastic_in),
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => in_out(t, |t| 1.0 - bounce_out(1.0 - t)),
+        }
+    }
+}
+
+/// Runs the `ease_in` curve over the first half and its mirror image over
+/// the second.
+fn in_out(t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(t * 2.0) / 2.0
+    } else {
+        1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+    }
+}
+
+fn elastic_in(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// Values a `Tween` can interpolate. `t` may leave 0–1 for overshooting
+/// easings.
+pub trait Lerp: Clone {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Point {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Point::lerp(*self, *other, t)
+    }
+}
+
+impl Lerp for Vec3 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vec3::lerp(*self, *other, t)
+    }
+}
+
+impl Lerp for Rgba {
+    /// Blends channel by channel, alpha included, clamping overshoot.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let mix = |a: u8, b: u8| {
+            (a as f32 + (b as f32 - a as f32) * t)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Rgba::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+            mix(self.a, other.a),
+        )
+    }
+}
+
+/// A value changing over time. `sample` must accept any time, holding the
+/// first value before 0 and the last after `duration`.
+pub trait Animation {
+    type Value;
+
+    /// Length in seconds; infinite for endless repeats.
+    fn duration(&self) -> f32;
+
+    fn sample(&self, time: f32) -> Self::Value;
+
+    /// Holds the starting value for `seconds` before playing.
+    fn delay(self, seconds: f32) -> Delay<Self>
+    where
+        Self: Sized,
+    {
+        Delay {
+            inner: self,
+            seconds: seconds.max(0.0),
+        }
+    }
+
+    /// Plays `count` times in a row.
+    fn repeat(self, count: u32) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        Repeat {
+            inner: self,
+            count: Some(count.max(1)),
+        }
+    }
+
+    fn repeat_forever(self) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        Repeat {
+            inner: self,
+            count: None,
+        }
+    }
+
+    /// Plays forwards, then backwards back to the start.
+    fn yoyo(self) -> Yoyo<Self>
+    where
+        Self: Sized,
+    {
+        Yoyo { inner: self }
+    }
+
+    /// Plays alongside `other`, giving both values as a pair. The shorter
+    /// one holds its last value until the longer finishes.
+    fn join<B: Animation>(self, other: B) -> Parallel<Self, B>
+    where
+        Self: Sized,
+    {
+        Parallel {
+            first: self,
+            second: other,
+        }
+    }
+}
+
+impl<A: Animation + ?Sized> Animation for Box<A> {
+    type Value = A::Value;
+
+    fn duration(&self) -> f32 {
+        (**self).duration()
+    }
+
+    fn sample(&self, time: f32) -> Self::Value {
+        (**self).sample(time)
+    }
+}
+
+/// Interpolates from one value to another over `duration` seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween<T> {
+    pub from: T,
+    pub to: T,
+    pub duration: f32,
+    pub easing: Easing,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration: duration.max(0.0),
+            easing: Easing::Linear,
+        }
+    }
+
+    pub fn with_easing(self, easing: Easing) -> Self {
+        Self { easing, ..self }
+    }
+}
+
+impl<T: Lerp> Animation for Tween<T> {
+    type Value = T;
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn sample(&self, time: f32) -> T {
+        if self.duration <= 0.0 {
+            return if time < 0.0 { &self.from } else { &self.to }.clone();
+        }
+        let t = self.easing.apply(time / self.duration);
+        self.from.lerp(&self.to, t)
+    }
+}
+
+/// A value that stays put for `duration` seconds, for pauses in a
+/// `Sequence`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hold<T> {
+    pub value: T,
+    pub duration: f32,
+}
+
+impl<T: Clone> Hold<T> {
+    pub fn new(value: T, duration: f32) -> Self {
+        Self {
+            value,
+            duration: duration.max(0.0),
+        }
+    }
+}
+
+impl<T: Clone> Animation for Hold<T> {
+    type Value = T;
+
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn sample(&self, _time: f32) -> T {
+        self.value.clone()
+    }
+}
+
+/// See `Animation::delay`.
+#[derive(Debug, Clone)]
+pub struct Delay<A> {
+    inner: A,
+    seconds: f32,
+}
+
+impl<A: Animation> Animation for Delay<A> {
+    type Value = A::Value;
+
+    fn duration(&self) -> f32 {
+        self.seconds + self.inner.duration()
+    }
+
+    fn sample(&self, time: f32) -> A::Value {
+        self.inner.sample(time - self.seconds)
+    }
+}
+
+/// See `Animation::repeat` and `Animation::repeat_forever`.
+#[derive(Debug, Clone)]
+pub struct Repeat<A> {
+    inner: A,
+    /// Number of plays; `None` repeats forever.
+    count: Option<u32>,
+}
+
+impl<A: Animation> Animation for Repeat<A> {
+    type Value = A::Value;
+
+    fn duration(&self) -> f32 {
+        match self.count {
+            Some(count) => self.inner.duration() * count as f32,
+            None => f32::INFINITY,
+        }
+    }
+
+    fn sample(&self, time: f32) -> A::Value {
+        let length = self.inner.duration();
+        if time >= self.duration() || length <= 0.0 {
+            return self.inner.sample(time.max(0.0).min(length.max(0.0)));
+        }
+        if time < 0.0 {
+            return self.inner.sample(time);
+        }
+        self.inner.sample(time % length)
+    }
+}
+
+/// See `Animation::yoyo`.
+#[derive(Debug, Clone)]
+pub struct Yoyo<A> {
+    inner: A,
+}
+
+impl<A: Animation> Animation for Yoyo<A> {
+    type Value = A::Value;
+
+    fn duration(&self) -> f32 {
+        self.inner.duration() * 2.0
+    }
+
+    fn sample(&self, time: f32) -> A::Value {
+        let length = self.inner.duration();
+        if time <= length {
+            self.inner.sample(time)
+        } else {
+            self.inner.sample((2.0 * length - time).max(0.0))
+        }
+    }
+}
+
+/// See `Animation::join`.
+#[derive(Debug, Clone)]
+pub struct Parallel<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Animation, B: Animation> Animation for Parallel<A, B> {
+    type Value = (A::Value, B::Value);
+
+    fn duration(&self) -> f32 {
+        self.first.duration().max(self.second.duration())
+    }
+
+    fn sample(&self, time: f32) -> Self::Value {
+        (self.first.sample(time), self.second.sample(time))
+    }
+}
+
+/// Animations of the same value played one after another.
+pub struct Sequence<T> {
+    steps: Vec<Box<dyn Animation<Value = T>>>,
+}
+
+impl<T: 'static> Sequence<T> {
+    pub fn new(first: impl Animation<Value = T> + 'static) -> Self {
+        Self {
+            steps: vec![Box::new(first)],
+        }
+    }
+
+    /// Plays `next` once everything before it has finished.
+    pub fn then(mut self, next: impl Animation<Value = T> + 'static) -> Self {
+        self.steps.push(Box::new(next));
+        self
+    }
+
+    /// Holds the value reached so far for `seconds`.
+    pub fn wait(self, seconds: f32) -> Self
+    where
+        T: Clone,
+    {
+        let end = self.sample(self.duration());
+        self.then(Hold::new(end, seconds))
+    }
+}
+
+impl<T> Animation for Sequence<T> {
+    type Value = T;
+
+    fn duration(&self) -> f32 {
+        self.steps.iter().map(|step| step.duration()).sum()
+    }
+
+    fn sample(&self, time: f32) -> T {
+        let mut start = 0.0;
+        let last = self.steps.len() - 1;
+        for (index, step) in self.steps.iter().enumerate() {
+            let length = step.duration();
+            if index == last || time < start + length {
+                return step.sample(time - start);
+            }
+            start += length;
+        }
+        unreachable!("a sequence always has a step")
+    }
+}
+
+/// Plays an animation against a clock of its own. Call `update` from
+/// `Scene::on_update` with `Context::delta_time`, which is zero while the
+/// engine is paused, so the timeline stops with the rest of the scene.
+#[derive(Debug, Clone)]
+pub struct Timeline<A> {
+    animation: A,
+    time: f32,
+    /// Playback rate; 2 plays twice as fast.
+    pub speed: f32,
+    playing: bool,
+}
+
+impl<A: Animation> Timeline<A> {
+    pub fn new(animation: A) -> Self {
+        Self {
+            animation,
+            time: 0.0,
+            speed: 1.0,
+            playing: true,
+        }
+    }
+
+    pub fn with_speed(self, speed: f32) -> Self {
+        Self { speed, ..self }
+    }
+
+    /// Advances the clock by `delta_time` seconds unless paused, stopping
+    /// at the end.
+    pub fn update(&mut self, delta_time: f32) {
+        if self.playing {
+            self.seek(self.time + delta_time * self.speed);
+        }
+    }
+
+    /// The animation's value at the current time.
+    pub fn value(&self) -> A::Value {
+        self.animation.sample(self.time)
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Jumps to `time` seconds, clamped to the animation.
+    pub fn seek(&mut self, time: f32) {
+        self.time = time.clamp(0.0, self.animation.duration());
+    }
+
+    /// Share of the animation played, 0–1; always 0 for endless ones.
+    pub fn progress(&self) -> f32 {
+        let duration = self.animation.duration();
+        if !duration.is_finite() {
+            return 0.0;
+        }
+        if duration <= 0.0 {
+            return 1.0;
+        }
+        self.time / duration
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.time >= self.animation.duration()
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Rewinds to the start and plays.
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+        self.playing = true;
+    }
+
+    pub fn animation(&self) -> &A {
+        &self.animation
+    }
+
+    pub fn animation_mut(&mut self) -> &mut A {
+        &mut self.animation
+    }
+}
+
+/// A value moving through `(time, value)` keys, eased between each pair.
+/// It holds the first value before the first key and the last after the
+/// last key, unless `looping` restarts it from time 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframes {
+    keys: Vec<(f32, f32)>,
+    pub easing: Easing,
+    pub looping: bool,
+}
+
+impl Keyframes {
+    /// Keyframes from `(time, value)` pairs in any order. Without keys the
+    /// value is always 0.
+    pub fn new(keys: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        let mut keys: Vec<_> = keys.into_iter().collect();
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            keys,
+            easing: Easing::Linear,
+            looping: false,
+        }
+    }
+
+    pub fn with_easing(self, easing: Easing) -> Self {
+        Self { easing, ..self }
+    }
+
+    pub fn with_looping(self, looping: bool) -> Self {
+        Self { looping, ..self }
+    }
+
+    pub fn keys(&self) -> &[(f32, f32)] {
+        &self.keys
+    }
+}
+
+impl Animation for Keyframes {
+    type Value = f32;
+
+    fn duration(&self) -> f32 {
+        if self.looping {
+            return f32::INFINITY;
+        }
+        self.keys.last().map_or(0.0, |&(time, _)| time.max(0.0))
+    }
+
+    fn sample(&self, time: f32) -> f32 {
+        let (Some(&first), Some(&last)) = (self.keys.first(), self.keys.last()) else {
+            return 0.0;
+        };
+        let time = if self.looping && last.0 > 0.0 {
+            time.rem_euclid(last.0)
+        } else {
+            time
+        };
+        if time <= first.0 {
+            return first.1;
+        }
+        let next = self.keys.partition_point(|&(key_time, _)| key_time <= time);
+        let Some(&(end_time, end)) = self.keys.get(next) else {
+            return last.1;
+        };
+        let (start_time, start) = self.keys[next - 1];
+        let t = self
+            .easing
+            .apply((time - start_time) / (end_time - start_time));
+        start.lerp(&end, t)
+    }
+}
+
+/// A number that is either fixed or keyframed, such as a scene setting
+/// written as `amp = 2.0` or `amp = { keys = [[0.0, 2.0], [4.0, 8.0]] }`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Param {
+    Constant(f32),
+    Keyframed(Keyframes),
+}
+
+impl Param {
+    /// The value `time` seconds in.
+    pub fn value(&self, time: f32) -> f32 {
+        match self {
+            Param::Constant(value) => *value,
+            Param::Keyframed(keys) => keys.sample(time),
+        }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        matches!(self, Param::Constant(_))
+    }
+}
+
+impl From<f32> for Param {
+    fn from(value: f32) -> Self {
+        Param::Constant(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_EASINGS: [Easing; 19] = [
+        Easing::Linear,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::ElasticInOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+    ];
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn easings_pin_their_endpoints() {
+        for easing in ALL_EASINGS {
+            assert_near(easing.apply(0.0), 0.0);
+            assert_near(easing.apply(1.0), 1.0);
+            // Progress outside 0–1 is clamped.
+            assert_near(easing.apply(-1.0), 0.0);
+            assert_near(easing.apply(2.0), 1.0);
+        }
+        for easing in [Easing::SineInOut, Easing::QuadInOut, Easing::CubicInOut] {
+            assert_near(easing.apply(0.5), 0.5);
+        }
+        assert_near(Easing::QuadIn.apply(0.5), 0.25);
+        assert_near(Easing::QuadOut.apply(0.5), 0.75);
+    }
+
+    #[test]
+    fn back_and_elastic_easings_overshoot() {
+        assert!(Easing::BackIn.apply(0.2) < 0.0);
+        assert!(Easing::BackOut.apply(0.8) > 1.0);
+        assert!((1..10).any(|i| Easing::ElasticOut.apply(i as f32 / 10.0) > 1.0));
+        for i in 0..=20 {
+            let t = Easing::BounceOut.apply(i as f32 / 20.0);
+            assert!((0.0..=1.0 + 1e-6).contains(&t), "{t}");
+        }
+    }
+
+    #[test]
+    fn parses_easing_names() {
+        assert_eq!(Easing::parse("cubic_in_out").unwrap(), Easing::CubicInOut);
+        assert_eq!(Easing::parse(" linear ").unwrap(), Easing::Linear);
+        assert!(Easing::parse("CubicInOut").is_err());
+        assert!(Easing::parse("wobble").is_err());
+    }
+
+    #[test]
+    fn tweens_hold_their_ends_outside_their_duration() {
+        let tween = Tween::new(0.0, 10.0, 2.0);
+        assert_near(tween.sample(-1.0), 0.0);
+        assert_near(tween.sample(0.5), 2.5);
+        assert_near(tween.sample(3.0), 10.0);
+
+        let eased = Tween::new(0.0, 10.0, 2.0).with_easing(Easing::QuadIn);
+        assert_near(eased.sample(1.0), 2.5);
+
+        let instant = Tween::new(0.0, 10.0, 0.0);
+        assert_near(instant.sample(-0.1), 0.0);
+        assert_near(instant.sample(0.0), 10.0);
+
+        let colour = Tween::new(Rgba::rgb(0, 0, 0), Rgba::rgb(200, 100, 255), 1.0)
+            .with_easing(Easing::BackOut);
+        let overshot = colour.sample(0.8);
+        assert_eq!(overshot.b, 255);
+        assert!(overshot.r > 200);
+    }
+
+    #[test]
+    fn delay_holds_the_start() {
+        let delayed = Tween::new(0.0, 10.0, 1.0).delay(0.5);
+        assert_near(delayed.duration(), 1.5);
+        assert_near(delayed.sample(0.25), 0.0);
+        assert_near(delayed.sample(1.0), 5.0);
+    }
+
+    #[test]
+    fn repeat_restarts_and_then_holds() {
+        let repeated = Tween::new(0.0, 10.0, 2.0).repeat(3);
+        assert_near(repeated.duration(), 6.0);
+        assert_near(repeated.sample(-1.0), 0.0);
+        assert_near(repeated.sample(1.0), 5.0);
+        assert_near(repeated.sample(3.0), 5.0);
+        assert_near(repeated.sample(5.5), 7.5);
+        assert_near(repeated.sample(6.0), 10.0);
+        assert_near(repeated.sample(9.0), 10.0);
+
+        let forever = Tween::new(0.0, 10.0, 2.0).repeat_forever();
+        assert_eq!(forever.duration(), f32::INFINITY);
+        assert_near(forever.sample(101.0), 5.0);
+    }
+
+    #[test]
+    fn yoyo_plays_back_to_the_start() {
+        let yoyo = Tween::new(0.0, 10.0, 2.0).yoyo();
+        assert_near(yoyo.duration(), 4.0);
+        assert_near(yoyo.sample(1.0), 5.0);
+        assert_near(yoyo.sample(2.0), 10.0);
+        assert_near(yoyo.sample(3.0), 5.0);
+        assert_near(yoyo.sample(5.0), 0.0);
+
+        let bouncing = Tween::new(0.0, 10.0, 1.0).yoyo().repeat_forever();
+        assert_near(bouncing.sample(2.5), 5.0);
+        assert_near(bouncing.sample(3.25), 7.5);
+    }
+
+    #[test]
+    fn join_holds_the_shorter_animation() {
+        let joined = Tween::new(0.0, 10.0, 1.0).join(Tween::new(0.0, 4.0, 2.0));
+        assert_near(joined.duration(), 2.0);
+        let (a, b) = joined.sample(1.5);
+        assert_near(a, 10.0);
+        assert_near(b, 3.0);
+    }
+
+    #[test]
+    fn sequences_play_steps_in_turn() {
+        let sequence = Sequence::new(Tween::new(0.0, 10.0, 1.0))
+            .then(Tween::new(10.0, 0.0, 2.0))
+            .wait(1.0)
+            .then(Tween::new(0.0, -4.0, 1.0));
+        assert_near(sequence.duration(), 5.0);
+        assert_near(sequence.sample(-1.0), 0.0);
+        assert_near(sequence.sample(0.5), 5.0);
+        assert_near(sequence.sample(1.0), 10.0);
+        assert_near(sequence.sample(2.0), 5.0);
+        assert_near(sequence.sample(3.5), 0.0);
+        assert_near(sequence.sample(4.5), -2.0);
+        assert_near(sequence.sample(10.0), -4.0);
+    }
+
+    #[test]
+    fn timelines_advance_pause_and_stop_at_the_end() {
+        let mut timeline = Timeline::new(Tween::new(0.0, 10.0, 2.0));
+        timeline.update(1.0);
+        assert_near(timeline.value(), 5.0);
+        assert_near(timeline.progress(), 0.5);
+
+        timeline.pause();
+        timeline.update(1.0);
+        assert_near(timeline.time(), 1.0);
+        assert!(!timeline.is_playing());
+
+        timeline.play();
+        timeline.speed = 2.0;
+        timeline.update(1.0);
+        assert_near(timeline.time(), 2.0);
+        assert!(timeline.is_finished());
+        assert_near(timeline.value(), 10.0);
+
+        timeline.seek(-3.0);
+        assert_near(timeline.time(), 0.0);
+        timeline.update(0.5);
+        timeline.restart();
+        assert_near(timeline.time(), 0.0);
+        assert!(timeline.is_playing());
+
+        let endless = Timeline::new(Tween::new(0.0, 1.0, 1.0).repeat_forever());
+        assert_near(endless.progress(), 0.0);
+        assert!(!endless.is_finished());
+    }
+
+    #[test]
+    fn keyframes_interpolate_and_hold_outside_their_keys() {
+        let keys = Keyframes::new([(3.0, 4.0), (1.0, 0.0), (2.0, 10.0)]);
+        assert_eq!(keys.keys(), &[(1.0, 0.0), (2.0, 10.0), (3.0, 4.0)]);
+        assert_near(keys.duration(), 3.0);
+        assert_near(keys.sample(0.0), 0.0);
+        assert_near(keys.sample(1.5), 5.0);
+        assert_near(keys.sample(2.0), 10.0);
+        assert_near(keys.sample(2.5), 7.0);
+        assert_near(keys.sample(9.0), 4.0);
+
+        let eased = Keyframes::new([(0.0, 0.0), (2.0, 8.0)]).with_easing(Easing::QuadIn);
+        assert_near(eased.sample(1.0), 2.0);
+
+        let empty = Keyframes::new([]);
+        assert_near(empty.sample(1.0), 0.0);
+        assert_near(empty.duration(), 0.0);
+    }
+
+    #[test]
+    fn looping_keyframes_wrap_around_the_last_key() {
+        let keys = Keyframes::new([(0.0, 0.0), (2.0, 10.0)]).with_looping(true);
+        assert_eq!(keys.duration(), f32::INFINITY);
+        assert_near(keys.sample(1.0), 5.0);
+        assert_near(keys.sample(3.0), 5.0);
+        assert_near(keys.sample(4.5), 2.5);
+        // Reaching the last key starts over, and negative time wraps too.
+        assert_near(keys.sample(2.0), 0.0);
+        assert_near(keys.sample(-0.5), 7.5);
+
+        // A late first key holds its value at the start of each loop.
+        let late = Keyframes::new([(1.0, 2.0), (2.0, 4.0)]).with_looping(true);
+        assert_near(late.sample(2.5), 2.0);
+        assert_near(late.sample(3.5), 3.0);
+
+        // A single key at time 0 cannot loop and just holds.
+        let single = Keyframes::new([(0.0, 7.0)]).with_looping(true);
+        assert_near(single.sample(5.0), 7.0);
+    }
+
+    #[test]
+    fn params_are_fixed_or_keyframed() {
+        let constant = Param::from(3.0);
+        assert!(constant.is_constant());
+        assert_near(constant.value(100.0), 3.0);
+
+        let keyed = Param::Keyframed(Keyframes::new([(0.0, 0.0), (4.0, 8.0)]));
+        assert!(!keyed.is_constant());
+        assert_near(keyed.value(1.0), 2.0);
+    }
+}
@@ -0,0 +1,700 @@
+use std::{fmt, fs, path::Path};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use rand::Rng;
+
+use crate::color::Rgba;
+use crate::draw::{Canvas, PixelMode};
+use crate::image::{ImageCell, BRAILLE_DOTS};
+
+/// Glyphs for live states in ASCII mode, from fully alive to nearly dead.
+const DEFAULT_STATE_GLYPHS: &str = "#o:.";
+
+/// How a cellular automaton updates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomatonRule {
+    /// Outer-totalistic rules on the Moore neighbourhood, such as Conway's
+    /// `B3/S23`. Bit `n` of `birth`/`survival` is set when `n` live
+    /// neighbours give birth to / keep alive a cell.
+    ///
+    /// With `states` above 2 this is a "Generations" rule: a live cell that
+    /// does not survive ages through states 2, 3... before dying, and only
+    /// state 1 counts as a live neighbour (Brian's Brain is `B2/S/C3`).
+    Totalistic {
+        birth: u16,
+        survival: u16,
+        states: u8,
+    },
+    /// A one-dimensional Wolfram rule. Each generation is a new row below
+    /// the previous one; once the grid is full it scrolls up.
+    Elementary(u8),
+}
+
+impl AutomatonRule {
+    pub const LIFE: AutomatonRule = AutomatonRule::Totalistic {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+        states: 2,
+    };
+
+    /// Parses a rule:
+    ///
+    /// ```text
+    /// B3/S23      birth / survival neighbour counts (also S/B order: 23/3)
+    /// B2/S/C3     a Generations rule with 3 states (also 23/3/3)
+    /// W110        Wolfram elementary rule 110 (also "rule 110")
+    /// ```
+    ///
+    /// and the names `life`, `highlife`, `seeds`, `day_and_night` and
+    /// `brians_brain`.
+    pub fn parse(text: &str) -> Result<Self> {
+        Self::parse_inner(text.trim()).with_context(|| format!("Invalid rule '{text}'"))
+    }
+
+    fn parse_inner(text: &str) -> Result<Self> {
+        let lower = text.to_ascii_lowercase();
+        let named = match lower.as_str() {
+            "life" | "conway" => Some("B3/S23"),
+            "highlife" => Some("B36/S23"),
+            "seeds" => Some("B2/S"),
+            "day_and_night" => Some("B3678/S34678"),
+            "brians_brain" => Some("B2/S/C3"),
+            _ => None,
+        };
+        if let Some(rule) = named {
+            return Self::parse_inner(rule);
+        }
+        let wolfram = lower
+            .strip_prefix('w')
+            .or_else(|| lower.strip_prefix("rule"));
+        if let Some(number) = wolfram {
+            let number = number.trim();
+            return number
+                .parse()
+                .map(AutomatonRule::Elementary)
+                .map_err(|_| anyhow!("Wolfram rules are numbered 0-255, got '{number}'"));
+        }
+
+        let counts = |digits: &str| -> Result<u16> {
+            digits
+                .chars()
+                .try_fold(0u16, |mask, c| match c.to_digit(10) {
+                    Some(n) if n <= 8 => Ok(mask | 1 << n),
+                    _ => bail!("Neighbour counts are digits 0-8, got '{c}'"),
+                })
+        };
+        let states = |digits: &str| -> Result<u8> {
+            match digits.parse() {
+                Ok(n) if n >= 2 => Ok(n),
+                _ => bail!("Expected a state count of at least 2, got '{digits}'"),
+            }
+        };
+        let parts: Vec<&str> = lower.split('/').collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            bail!("Expected B/S notation such as 'B3/S23'");
+        }
+        let (mut birth, mut survival, mut count) = (None, None, 2);
+        for (index, part) in parts.iter().enumerate() {
+            if let Some(digits) = part.strip_prefix('b') {
+                birth = Some(counts(digits)?);
+            } else if let Some(digits) = part.strip_prefix('s') {
+                survival = Some(counts(digits)?);
+            } else if let Some(digits) = part.strip_prefix('c').or(part.strip_prefix('g')) {
+                count = states(digits)?;
+            } else {
+                // Plain digits: survival/birth[/states]
+                match index {
+                    0 => survival = Some(counts(part)?),
+                    1 => birth = Some(counts(part)?),
+                    _ => count = states(part)?,
+                }
+            }
+        }
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(AutomatonRule::Totalistic {
+                birth,
+                survival,
+                states: count,
+            }),
+            _ => bail!("Expected both birth and survival counts"),
+        }
+    }
+
+    /// The number of cell states, dead included.
+    pub fn states(&self) -> u8 {
+        match self {
+            AutomatonRule::Totalistic { states, .. } => *states,
+            AutomatonRule::Elementary(_) => 2,
+        }
+    }
+}
+
+impl fmt::Display for AutomatonRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            AutomatonRule::Totalistic {
+                birth,
+                survival,
+                states,
+            } => {
+                let digits = |mask: u16| -> String {
+                    (0..=8)
+                        .filter(|n| mask & 1 << n != 0)
+                        .map(|n| char::from(b'0' + n as u8))
+                        .collect()
+                };
+                write!(f, "B{}/S{}", digits(birth), digits(survival))?;
+                if states > 2 {
+                    write!(f, "/C{states}")?;
+                }
+                Ok(())
+            }
+            AutomatonRule::Elementary(number) => write!(f, "W{number}"),
+        }
+    }
+}
+
+/// What lies beyond the grid's edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// Edges wrap around to the opposite side.
+    #[default]
+    Toroidal,
+    /// Cells beyond the edges are always dead.
+    Bounded,
+}
+
+/// A pattern of live cells, typically loaded from an RLE file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CellPattern {
+    pub width: usize,
+    pub height: usize,
+    /// `(x, y, state)` of every non-dead cell.
+    pub cells: Vec<(usize, usize, u8)>,
+    /// The rule named in the RLE header, if any.
+    pub rule: Option<String>,
+}
+
+impl CellPattern {
+    pub fn load_rle(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read pattern at {}", path.display()))?;
+        Self::parse_rle(&source)
+            .with_context(|| format!("Failed to parse pattern at {}", path.display()))
+    }
+
+    /// Parses run-length encoded patterns as used by Golly and LifeWiki:
+    /// `#` comment lines, an optional `x = 3, y = 3, rule = B3/S23` header,
+    /// then runs of `b`/`.` (dead), `o` (alive) or `A`–`X` (states 1–24),
+    /// `$` ending rows and `!` ending the pattern.
+    pub fn parse_rle(source: &str) -> Result<Self> {
+        let mut pattern = CellPattern::default();
+        let (mut x, mut y) = (0, 0);
+        let mut run = 0usize;
+        'lines: for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            if line.starts_with('x') {
+                for field in line.split(',') {
+                    if let Some((key, value)) = field.split_once('=') {
+                        match key.trim() {
+                            "x" => pattern.width = value.trim().parse().unwrap_or(0),
+                            "y" => pattern.height = value.trim().parse().unwrap_or(0),
+                            "rule" => pattern.rule = Some(value.trim().to_string()),
+                            _ => {}
+                        }
+                    }
+                }
+                continue;
+            }
+            for c in line.chars() {
+                let count = run.max(1);
+                match c {
+                    '0'..='9' => {
+                        run = run * 10 + c.to_digit(10).unwrap_or(0) as usize;
+                        continue;
+                    }
+                    'b' | '.' => x += count,
+                    'o' | 'A'..='X' => {
+                        let state = if c == 'o' { 1 } else { c as u8 - b'A' + 1 };
+                        pattern
+                            .cells
+                            .extend((x..x + count).map(|cx| (cx, y, state)));
+                        x += count;
+                    }
+                    '$' => {
+                        y += count;
+                        x = 0;
+                    }
+                    '!' => break 'lines,
+                    c if c.is_whitespace() => {}
+                    other => bail!("Line {}: unexpected '{other}'", number + 1),
+                }
+                run = 0;
+            }
+        }
+        if pattern.cells.is_empty() {
+            bail!("CellPattern has no live cells");
+        }
+        for &(cx, cy, _) in &pattern.cells {
+            pattern.width = pattern.width.max(cx + 1);
+            pattern.height = pattern.height.max(cy + 1);
+        }
+        Ok(pattern)
+    }
+}
+
+/// A grid of cells evolving under an `AutomatonRule`. Draw it with
+/// `Automaton::draw`, which maps each cell to one logical pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Automaton {
+    pub rule: AutomatonRule,
+    pub topology: Topology,
+    /// Colour per live state, state 1 first; empty uses the canvas'
+    /// foreground colour and states beyond the list reuse its last colour.
+    pub colors: Vec<Rgba>,
+    /// ASCII glyph per live state, state 1 first.
+    pub glyphs: Vec<char>,
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+    scratch: Vec<u8>,
+    generation: u64,
+}
+
+impl Automaton {
+    pub fn new(width: usize, height: usize, rule: AutomatonRule) -> Self {
+        Self {
+            rule,
+            topology: Topology::default(),
+            colors: Vec::new(),
+            glyphs: DEFAULT_STATE_GLYPHS.chars().collect(),
+            width,
+            height,
+            cells: vec![0; width * height],
+            scratch: vec![0; width * height],
+            generation: 0,
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn with_colors(self, colors: Vec<Rgba>) -> Self {
+        Self { colors, ..self }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The number of cells in state 1.
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&state| state == 1).count()
+    }
+
+    /// The state at `(x, y)`; 0 (dead) outside the grid.
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x]
+        } else {
+            0
+        }
+    }
+
+    /// Sets the state at `(x, y)`, clamped to the rule's states. Cells
+    /// outside the grid are ignored.
+    pub fn set(&mut self, x: usize, y: usize, state: u8) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = state.min(self.rule.states() - 1);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+        self.generation = 0;
+    }
+
+    /// Changes the grid size, keeping the cells that still fit.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+        let mut cells = vec![0; width * height];
+        for y in 0..height.min(self.height) {
+            for x in 0..width.min(self.width) {
+                cells[y * width + x] = self.cells[y * self.width + x];
+            }
+        }
+        self.cells = cells;
+        self.scratch = vec![0; width * height];
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Clears the grid and makes each cell alive with probability
+    /// `density`. Elementary rules only seed the first row.
+    pub fn randomize(&mut self, density: f32, rng: &mut impl Rng) {
+        self.clear();
+        let seeded = match self.rule {
+            AutomatonRule::Elementary(_) => self.width.min(self.cells.len()),
+            AutomatonRule::Totalistic { .. } => self.cells.len(),
+        };
+        for cell in &mut self.cells[..seeded] {
+            *cell = u8::from(rng.gen::<f32>() < density);
+        }
+    }
+
+    /// Stamps `pattern` with its top-left corner at `(x, y)`, wrapping or
+    /// clipping it according to the topology.
+    pub fn place(&mut self, pattern: &CellPattern, x: usize, y: usize) {
+        for &(px, py, state) in &pattern.cells {
+            let (mut cx, mut cy) = (x + px, y + py);
+            if self.topology == Topology::Toroidal && self.width > 0 && self.height > 0 {
+                cx %= self.width;
+                cy %= self.height;
+            }
+            self.set(cx, cy, state);
+        }
+    }
+
+    /// Advances one generation.
+    pub fn step(&mut self) {
+        if self.cells.is_empty() {
+            return;
+        }
+        match self.rule {
+            AutomatonRule::Totalistic {
+                birth,
+                survival,
+                states,
+            } => self.step_totalistic(birth, survival, states),
+            AutomatonRule::Elementary(number) => self.step_elementary(number),
+        }
+        self.generation += 1;
+    }
+
+    fn step_totalistic(&mut self, birth: u16, survival: u16, states: u8) {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let toroidal = self.topology == Topology::Toroidal;
+        let cells = &self.cells;
+        let alive = |x: isize, y: isize| -> u16 {
+            let (x, y) = if toroidal {
+                (x.rem_euclid(width), y.rem_euclid(height))
+            } else if x < 0 || y < 0 || x >= width || y >= height {
+                return 0;
+            } else {
+                (x, y)
+            };
+            u16::from(cells[(y * width + x) as usize] == 1)
+        };
+        for y in 0..height {
+            for x in 0..width {
+                let neighbours = alive(x - 1, y - 1)
+                    + alive(x, y - 1)
+                    + alive(x + 1, y - 1)
+                    + alive(x - 1, y)
+                    + alive(x + 1, y)
+                    + alive(x - 1, y + 1)
+                    + alive(x, y + 1)
+                    + alive(x + 1, y + 1);
+                let index = (y * width + x) as usize;
+                self.scratch[index] = match cells[index] {
+                    0 => u8::from(birth & 1 << neighbours != 0),
+                    1 if survival & 1 << neighbours != 0 => 1,
+                    state => (state + 1) % states,
+                };
+            }
+        }
+        std::mem::swap(&mut self.cells, &mut self.scratch);
+    }
+
+    fn step_elementary(&mut self, number: u8) {
+        let width = self.width;
+        let newest = (self.generation as usize).min(self.height - 1);
+        for x in 0..width {
+            let cell = |dx: isize| -> u8 {
+                let nx = x as isize + dx;
+                let nx = match self.topology {
+                    Topology::Toroidal => nx.rem_euclid(width as isize),
+                    Topology::Bounded if nx < 0 || nx >= width as isize => return 0,
+                    Topology::Bounded => nx,
+                };
+                self.cells[newest * width + nx as usize]
+            };
+            let neighbourhood = cell(-1) << 2 | cell(0) << 1 | cell(1);
+            self.scratch[x] = number >> neighbourhood & 1;
+        }
+        let target = if newest + 1 < self.height {
+            newest + 1
+        } else {
+            self.cells.copy_within(width.., 0);
+            newest
+        };
+        self.cells[target * width..(target + 1) * width].copy_from_slice(&self.scratch[..width]);
+    }
+
+    fn color(&self, state: u8) -> Option<Rgba> {
+        let index = (state as usize - 1).min(self.colors.len().checked_sub(1)?);
+        Some(self.colors[index])
+    }
+
+    fn glyph(&self, state: u8) -> char {
+        let last = self.glyphs.len().saturating_sub(1);
+        self.glyphs
+            .get((state as usize - 1).min(last))
+            .copied()
+            .unwrap_or('#')
+    }
+
+    /// Draws the grid with its top-left cell at `(x, y)`, one grid cell per
+    /// logical pixel of the canvas' pixel mode. Dead cells leave the canvas
+    /// untouched and states without a colour use the current foreground.
+    /// Like images, the grid stays aligned to the cell grid.
+    pub fn draw(&self, canvas: &mut Canvas<'_>, x: i32, y: i32) {
+        let mode = canvas.current_pixel_mode;
+        let (columns, cells) = automaton_cells(self, mode, canvas.foreground_rgba());
+        canvas.draw_cells(x, y, columns, &cells);
+    }
+}
+
+/// Cells for the grid drawn one cell per logical pixel of `mode`, row by
+/// row, covering `columns` x `rows` device cells. Cells without live
+/// pixels come back as `None`.
+fn automaton_cells(
+    automaton: &Automaton,
+    mode: PixelMode,
+    default_color: Rgba,
+) -> (usize, Vec<Option<ImageCell>>) {
+    let (sx, sy) = mode.cell_resolution();
+    let columns = automaton.width.div_ceil(sx);
+    let rows = automaton.height.div_ceil(sy);
+    let color = |state: u8| automaton.color(state).unwrap_or(default_color);
+    let mut cells = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        for column in 0..columns {
+            let state = |dx: usize, dy: usize| automaton.get(column * sx + dx, row * sy + dy);
+            let cell = match mode {
+                PixelMode::Ascii => match state(0, 0) {
+                    0 => None,
+                    s => Some(ImageCell {
+                        symbol: automaton.glyph(s),
+                        fg: Some(color(s).to_color()),
+                        bg: None,
+                    }),
+                },
+                PixelMode::HalfBlock => match (state(0, 0), state(0, 1)) {
+                    (0, 0) => None,
+                    (0, bottom) => Some(ImageCell {
+                        symbol: '▄',
+                        fg: Some(color(bottom).to_color()),
+                        bg: None,
+                    }),
+                    (top, bottom) => Some(ImageCell {
+                        symbol: '▀',
+                        fg: Some(color(top).to_color()),
+                        bg: (bottom != 0).then(|| color(bottom).to_color()),
+                    }),
+                },
+                PixelMode::Braille => {
+                    // The youngest live state colours the cell.
+                    let (mut bits, mut youngest) = (0, u8::MAX);
+                    for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                        for (dx, bit) in dots.iter().enumerate() {
+                            let s = state(dx, dy);
+                            if s != 0 {
+                                bits |= bit;
+                                youngest = youngest.min(s);
+                            }
+                        }
+                    }
+                    (bits != 0).then(|| ImageCell {
+                        symbol: char::from_u32(0x2800 + bits).unwrap_or(' '),
+                        fg: Some(color(youngest).to_color()),
+                        bg: None,
+                    })
+                }
+            };
+            cells.push(cell);
+        }
+    }
+    (columns, cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+
+    fn live_cells(automaton: &Automaton) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        for y in 0..automaton.height() {
+            for x in 0..automaton.width() {
+                if automaton.get(x, y) != 0 {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn parses_birth_survival_rules() {
+        assert_eq!(AutomatonRule::parse("B3/S23").unwrap(), AutomatonRule::LIFE);
+        assert_eq!(AutomatonRule::parse("23/3").unwrap(), AutomatonRule::LIFE);
+        assert_eq!(AutomatonRule::parse(" Life ").unwrap(), AutomatonRule::LIFE);
+        assert_eq!(
+            AutomatonRule::parse("B2/S/C3").unwrap(),
+            AutomatonRule::Totalistic {
+                birth: 1 << 2,
+                survival: 0,
+                states: 3,
+            }
+        );
+        assert_eq!(
+            AutomatonRule::parse("brians_brain").unwrap(),
+            AutomatonRule::parse("/2/3").unwrap()
+        );
+        assert_eq!(
+            AutomatonRule::parse("rule 110").unwrap(),
+            AutomatonRule::Elementary(110)
+        );
+        assert_eq!(
+            AutomatonRule::parse("W30").unwrap(),
+            AutomatonRule::Elementary(30)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        for rule in ["B9/S23", "B3", "B3/S23/C1", "B3/S23/C3/x", "W256", "Bx/S2"] {
+            assert!(AutomatonRule::parse(rule).is_err(), "{rule}");
+        }
+    }
+
+    #[test]
+    fn displays_rules_in_canonical_form() {
+        for (rule, shown) in [
+            ("23/3", "B3/S23"),
+            ("highlife", "B36/S23"),
+            ("B2/S/C3", "B2/S/C3"),
+            ("rule 90", "W90"),
+        ] {
+            let parsed = AutomatonRule::parse(rule).unwrap();
+            assert_eq!(parsed.to_string(), shown);
+            assert_eq!(AutomatonRule::parse(shown).unwrap(), parsed);
+        }
+    }
+
+    #[test]
+    fn parses_rle_patterns() {
+        let glider = CellPattern::parse_rle(GLIDER).unwrap();
+        assert_eq!((glider.width, glider.height), (3, 3));
+        assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(
+            glider.cells,
+            vec![(1, 0, 1), (2, 1, 1), (0, 2, 1), (1, 2, 1), (2, 2, 1)]
+        );
+
+        // Runs span lines, `$` runs skip rows and letters are states.
+        let pattern = CellPattern::parse_rle("2o\n3$A\nC!").unwrap();
+        assert_eq!(
+            pattern.cells,
+            vec![(0, 0, 1), (1, 0, 1), (0, 3, 1), (1, 3, 3)]
+        );
+        assert_eq!((pattern.width, pattern.height), (2, 4));
+
+        assert!(CellPattern::parse_rle("x = 1, y = 1\nb!").is_err());
+        assert!(CellPattern::parse_rle("oz!").is_err());
+    }
+
+    #[test]
+    fn glider_moves_one_cell_diagonally_every_four_generations() {
+        let glider = CellPattern::parse_rle(GLIDER).unwrap();
+        let rule = AutomatonRule::parse(glider.rule.as_deref().unwrap()).unwrap();
+        let mut automaton = Automaton::new(8, 8, rule);
+        automaton.place(&glider, 6, 6);
+        let start = live_cells(&automaton);
+        assert_eq!(start.len(), 5);
+
+        // Eight generations carry it across the wrapped corner and back to
+        // where it started, shifted by two cells.
+        for _ in 0..8 {
+            automaton.step();
+        }
+        assert_eq!(automaton.generation(), 8);
+        let mut shifted: Vec<(usize, usize)> = live_cells(&automaton)
+            .into_iter()
+            .map(|(x, y)| ((x + 6) % 8, (y + 6) % 8))
+            .collect();
+        shifted.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(shifted, start);
+    }
+
+    #[test]
+    fn topology_decides_what_lies_past_the_edges() {
+        let blinker = CellPattern::parse_rle("3o!").unwrap();
+        let mut toroidal = Automaton::new(5, 5, AutomatonRule::LIFE);
+        toroidal.place(&blinker, 4, 2);
+        toroidal.step();
+        assert_eq!(live_cells(&toroidal), vec![(0, 1), (0, 2), (0, 3)]);
+
+        let mut bounded =
+            Automaton::new(5, 5, AutomatonRule::LIFE).with_topology(Topology::Bounded);
+        bounded.place(&blinker, 0, 0);
+        bounded.step();
+        assert_eq!(live_cells(&bounded), vec![(1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn generations_rules_age_dying_cells() {
+        let mut automaton = Automaton::new(4, 4, AutomatonRule::parse("B2/S/C3").unwrap())
+            .with_topology(Topology::Bounded);
+        automaton.set(1, 1, 1);
+        automaton.set(2, 1, 1);
+        automaton.step();
+        assert_eq!(automaton.get(1, 1), 2);
+        assert_eq!(automaton.get(2, 1), 2);
+        assert_eq!(automaton.population(), 4);
+        automaton.step();
+        assert_eq!(automaton.get(1, 1), 0);
+        assert_eq!(automaton.get(1, 0), 2);
+
+        // States past the rule's count are clamped.
+        automaton.set(3, 3, 9);
+        assert_eq!(automaton.get(3, 3), 2);
+    }
+
+    #[test]
+    fn elementary_rules_fill_rows_then_scroll() {
+        let mut automaton = Automaton::new(5, 2, AutomatonRule::Elementary(90));
+        automaton.set(2, 0, 1);
+        automaton.step();
+        assert_eq!(live_cells(&automaton), vec![(2, 0), (1, 1), (3, 1)]);
+        automaton.step();
+        assert_eq!(live_cells(&automaton), vec![(1, 0), (3, 0), (0, 1), (4, 1)]);
+    }
+
+    #[test]
+    fn resize_keeps_cells_that_fit() {
+        let mut automaton = Automaton::new(4, 4, AutomatonRule::LIFE);
+        automaton.set(1, 1, 1);
+        automaton.set(3, 3, 1);
+        automaton.resize(2, 5);
+        assert_eq!((automaton.width(), automaton.height()), (2, 5));
+        assert_eq!(live_cells(&automaton), vec![(1, 1)]);
+    }
+}
@@ -0,0 +1,293 @@
+use rand::Rng;
+
+use crate::color::Rgba;
+use crate::draw::Canvas;
+use crate::path::Point;
+use crate::plot::PlotPoint;
+use crate::spatial::SpatialHash;
+
+/// Arrows for ASCII mode, by heading in eighths of a turn from east.
+const HEADING_GLYPHS: [char; 8] = ['→', '↗', '↑', '↖', '←', '↙', '↓', '↘'];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Boid {
+    /// Position in canvas cells.
+    pub position: Point,
+    /// Velocity in square units per second (see `Flock::aspect`).
+    pub velocity: Point,
+}
+
+impl Boid {
+    /// An arrow pointing along the boid's heading, as drawn in ASCII mode.
+    pub fn glyph(&self) -> char {
+        // Screen y grows downwards, so flip it to measure counter-clockwise.
+        let angle = (-self.velocity.y).atan2(self.velocity.x);
+        let octant = (angle / std::f32::consts::FRAC_PI_4).round() as i32;
+        HEADING_GLYPHS[octant.rem_euclid(8) as usize]
+    }
+}
+
+/// Reynolds' boids: separation, alignment and cohesion steering, with
+/// neighbours found through a `SpatialHash`.
+///
+/// Boids live in a `width` x `height` cell area and wrap around its edges,
+/// seeing neighbours across them through the nearest wrapped copy.
+/// Distances and velocities are measured in square units, vertical cell
+/// distances being divided by `aspect`, so the flock moves alike in every
+/// direction. Draw with `Flock::draw`.
+#[derive(Debug, Clone)]
+pub struct Flock {
+    pub boids: Vec<Boid>,
+    /// How far a boid sees its neighbours.
+    pub perception: f32,
+    /// Neighbours closer than this are steered away from.
+    pub separation_distance: f32,
+    pub separation: f32,
+    pub alignment: f32,
+    pub cohesion: f32,
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Largest steering acceleration, in units per second squared.
+    pub max_force: f32,
+    /// Scale from square units to cells vertically; 0.5 for cells twice as
+    /// tall as they are wide.
+    pub aspect: f32,
+    /// Boid colour; `None` uses the canvas' foreground colour.
+    pub color: Option<Rgba>,
+    width: f32,
+    height: f32,
+    hash: SpatialHash,
+}
+
+impl Flock {
+    /// `count` boids at random positions and headings.
+    pub fn new(count: usize, width: f32, height: f32, rng: &mut impl Rng) -> Self {
+        let mut flock = Self {
+            boids: Vec::with_capacity(count),
+            perception: 6.0,
+            separation_distance: 2.0,
+            separation: 1.5,
+            alignment: 1.0,
+            cohesion: 1.0,
+            min_speed: 4.0,
+            max_speed: 12.0,
+            max_force: 24.0,
+            aspect: 0.5,
+            color: None,
+            width,
+            height,
+            hash: SpatialHash::new(6.0),
+        };
+        for _ in 0..count {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = rng.gen_range(flock.min_speed..=flock.max_speed.max(flock.min_speed));
+            flock.boids.push(Boid {
+                position: Point::new(rng.gen::<f32>() * width, rng.gen::<f32>() * height),
+                velocity: Point::new(angle.cos() * speed, angle.sin() * speed),
+            });
+        }
+        flock
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    /// Changes the area; boids outside it wrap back in on the next update.
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Steers and moves every boid for `delta_time` seconds.
+    pub fn update(&mut self, delta_time: f32) {
+        let aspect = self.aspect.max(f32::EPSILON);
+        let square = |p: Point| Point::new(p.x, p.y / aspect);
+        if (self.hash.cell_size() - self.perception).abs() > f32::EPSILON {
+            self.hash = SpatialHash::new(self.perception);
+        }
+        self.hash.clear();
+        for (index, boid) in self.boids.iter().enumerate() {
+            self.hash.insert(index, square(boid.position));
+        }
+
+        let steering: Vec<Point> = self
+            .boids
+            .iter()
+            .enumerate()
+            .map(|(index, boid)| self.steer(index, boid, square(boid.position)))
+            .collect();
+
+        for (boid, force) in self.boids.iter_mut().zip(steering) {
+            let mut v = Point::new(
+                boid.velocity.x + force.x * delta_time,
+                boid.velocity.y + force.y * delta_time,
+            );
+            let speed = v.x.hypot(v.y);
+            let clamped = speed.clamp(self.min_speed, self.max_speed);
+            if speed > f32::EPSILON {
+                v = Point::new(v.x / speed * clamped, v.y / speed * clamped);
+            }
+            boid.velocity = v;
+            boid.position = Point::new(
+                (boid.position.x + v.x * delta_time).rem_euclid(self.width.max(1.0)),
+                (boid.position.y + v.y * aspect * delta_time).rem_euclid(self.height.max(1.0)),
+            );
+        }
+    }
+
+    /// Draws each boid as one logical pixel through `Canvas::plot_points`,
+    /// or as an arrow along its heading in ASCII mode.
+    pub fn draw(&self, canvas: &mut Canvas<'_>) {
+        let color = self.color.unwrap_or_else(|| canvas.foreground_rgba());
+        canvas.plot_points(self.boids.iter().map(|boid| PlotPoint {
+            position: boid.position,
+            color,
+            glyph: boid.glyph(),
+        }));
+    }
+
+    /// The combined steering acceleration for one boid.
+    fn steer(&self, index: usize, boid: &Boid, position: Point) -> Point {
+        let aspect = self.aspect.max(f32::EPSILON);
+        let (width, height) = (self.width.max(1.0), self.height.max(1.0) / aspect);
+        // The shortest way round the torus from `position`.
+        let wrap = |d: f32, size: f32| {
+            if d > size * 0.5 {
+                d - size
+            } else if d < -size * 0.5 {
+                d + size
+            } else {
+                d
+            }
+        };
+        // Separation push and the summed offset to neighbours.
+        let (mut separation, mut heading, mut offset) =
+            (Point::default(), Point::default(), Point::default());
+        let mut neighbours = 0;
+        for other_index in self
+            .hash
+            .query_wrapped(position, self.perception, width, height)
+        {
+            if other_index == index {
+                continue;
+            }
+            let other = &self.boids[other_index];
+            let dx = wrap(other.position.x - position.x, width);
+            let dy = wrap(other.position.y / aspect - position.y, height);
+            let distance = dx.hypot(dy);
+            if distance > self.perception {
+                continue;
+            }
+            neighbours += 1;
+            heading.x += other.velocity.x;
+            heading.y += other.velocity.y;
+            offset.x += dx;
+            offset.y += dy;
+            if distance < self.separation_distance && distance > f32::EPSILON {
+                // Push away harder the closer the neighbour is.
+                let push = (self.separation_distance - distance) / distance;
+                separation.x -= dx * push;
+                separation.y -= dy * push;
+            }
+        }
+        if neighbours == 0 {
+            return Point::default();
+        }
+        let n = neighbours as f32;
+        // Steer towards a desired velocity at full speed, Reynolds-style.
+        let seek = |desired: Point| {
+            let length = desired.x.hypot(desired.y);
+            if length <= f32::EPSILON {
+                return Point::default();
+            }
+            Point::new(
+                desired.x / length * self.max_speed - boid.velocity.x,
+                desired.y / length * self.max_speed - boid.velocity.y,
+            )
+        };
+        let align = seek(Point::new(heading.x / n, heading.y / n));
+        let cohere = seek(Point::new(offset.x / n, offset.y / n));
+        let separate = seek(separation);
+        let force = Point::new(
+            separate.x * self.separation + align.x * self.alignment + cohere.x * self.cohesion,
+            separate.y * self.separation + align.y * self.alignment + cohere.y * self.cohesion,
+        );
+        let length = force.x.hypot(force.y);
+        if length > self.max_force {
+            Point::new(
+                force.x / length * self.max_force,
+                force.y / length * self.max_force,
+            )
+        } else {
+            force
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn flock_at(boids: &[(f32, f32)]) -> Flock {
+        let mut flock = Flock::new(0, 20.0, 10.0, &mut StdRng::seed_from_u64(1));
+        flock.boids = boids
+            .iter()
+            .map(|&(x, y)| Boid {
+                position: Point::new(x, y),
+                velocity: Point::new(0.0, -8.0),
+            })
+            .collect();
+        flock
+    }
+
+    #[test]
+    fn glyphs_follow_the_heading() {
+        let glyph = |x, y| {
+            Boid {
+                position: Point::default(),
+                velocity: Point::new(x, y),
+            }
+            .glyph()
+        };
+        assert_eq!(glyph(1.0, 0.0), '→');
+        assert_eq!(glyph(0.0, -1.0), '↑');
+        assert_eq!(glyph(-1.0, 1.0), '↙');
+    }
+
+    #[test]
+    fn neighbours_are_seen_across_the_edges() {
+        let mut flock = flock_at(&[(0.5, 5.0), (19.5, 5.0)]);
+        flock.separation = 0.0;
+        flock.update(0.1);
+        // Cohesion pulls them together the short way round.
+        assert!(flock.boids[0].velocity.x < 0.0);
+        assert!(flock.boids[1].velocity.x > 0.0);
+
+        let mut flock = flock_at(&[(5.0, 0.2), (5.0, 9.8)]);
+        flock.separation = 0.0;
+        flock.alignment = 0.0;
+        flock.update(0.1);
+        assert!(flock.boids[0].velocity.y < -8.0);
+    }
+
+    #[test]
+    fn separation_pushes_apart_across_the_edges() {
+        let mut flock = flock_at(&[(0.5, 5.0), (19.5, 5.0)]);
+        flock.cohesion = 0.0;
+        flock.update(0.1);
+        assert!(flock.boids[0].velocity.x > 0.0);
+        assert!(flock.boids[1].velocity.x < 0.0);
+    }
+
+    #[test]
+    fn boids_wrap_around_the_area() {
+        let mut flock = flock_at(&[(10.0, 0.1)]);
+        flock.update(0.1);
+        let position = flock.boids[0].position;
+        assert!(position.y > 9.0 && position.y < 10.0, "{position:?}");
+        assert!((position.x - 10.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,279 @@
+use serde::Deserialize;
+
+/// Glyph set used by `Canvas::draw_box` and the other line-art primitives.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BorderStyle {
+    #[default]
+    Light,
+    Heavy,
+    Double,
+    /// Light lines with arc corners (`╭ ╮ ╰ ╯`).
+    Rounded,
+    /// `+`, `-` and `|` for terminals and fonts without box-drawing glyphs.
+    Ascii,
+}
+
+impl BorderStyle {
+    fn weight(self) -> Weight {
+        match self {
+            BorderStyle::Light | BorderStyle::Rounded | BorderStyle::Ascii => LIGHT,
+            BorderStyle::Heavy => HEAVY,
+            BorderStyle::Double => DOUBLE,
+        }
+    }
+}
+
+type Weight = u8;
+
+const NONE: Weight = 0;
+const LIGHT: Weight = 1;
+const HEAVY: Weight = 2;
+const DOUBLE: Weight = 3;
+
+/// Line weight leaving a cell in each direction: up, right, down, left.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Arms([Weight; 4]);
+
+impl Arms {
+    pub(crate) fn horizontal(style: BorderStyle, left: bool, right: bool) -> Self {
+        let w = style.weight();
+        Arms([
+            NONE,
+            if right { w } else { NONE },
+            NONE,
+            if left { w } else { NONE },
+        ])
+    }
+
+    pub(crate) fn vertical(style: BorderStyle, up: bool, down: bool) -> Self {
+        let w = style.weight();
+        Arms([
+            if up { w } else { NONE },
+            NONE,
+            if down { w } else { NONE },
+            NONE,
+        ])
+    }
+
+    /// Combines two sets of arms, keeping the heavier line in each direction.
+    pub(crate) fn merge(self, other: Arms) -> Arms {
+        let mut arms = self.0;
+        for (arm, other) in arms.iter_mut().zip(other.0) {
+            *arm = (*arm).max(other);
+        }
+        Arms(arms)
+    }
+
+    fn is_empty(self) -> bool {
+        self.0 == [NONE; 4]
+    }
+
+    /// Every non-empty arm replaced with `weight`.
+    fn uniform(self, weight: Weight) -> Arms {
+        Arms(self.0.map(|arm| if arm == NONE { NONE } else { weight }))
+    }
+}
+
+/// Merges `arms` into whatever line art `existing` already shows and returns
+/// the glyph for the combined junction. Non-line glyphs are simply replaced.
+pub(crate) fn merge_glyph(existing: char, arms: Arms, style: BorderStyle) -> char {
+    let existing_arms = if style == BorderStyle::Ascii {
+        decode_ascii(existing).or_else(|| decode(existing))
+    } else {
+        decode(existing)
+    };
+    let arms = existing_arms.map_or(arms, |existing| existing.merge(arms));
+
+    match style {
+        BorderStyle::Ascii => encode_ascii(arms),
+        BorderStyle::Rounded => encode_rounded(arms).unwrap_or_else(|| encode(arms)),
+        _ => encode(arms),
+    }
+}
+
+fn decode(symbol: char) -> Option<Arms> {
+    if let Some(&(_, arms)) = GLYPHS.iter().find(|(glyph, _)| *glyph == symbol) {
+        return Some(Arms(arms));
+    }
+    ROUNDED
+        .iter()
+        .find(|(glyph, _)| *glyph == symbol)
+        .map(|&(_, arms)| Arms(arms))
+}
+
+fn decode_ascii(symbol: char) -> Option<Arms> {
+    match symbol {
+        '-' => Some(Arms([NONE, LIGHT, NONE, LIGHT])),
+        '|' => Some(Arms([LIGHT, NONE, LIGHT, NONE])),
+        '+' => Some(Arms([LIGHT; 4])),
+        _ => None,
+    }
+}
+
+/// Finds the closest available glyph. Unicode lacks most double/heavy mixes
+/// and double stubs, so those degrade to the heaviest weight present and
+/// finally to light lines.
+fn encode(arms: Arms) -> char {
+    if arms.is_empty() {
+        return ' ';
+    }
+    let heaviest = arms.0.iter().copied().max().unwrap_or(LIGHT);
+    [arms, arms.uniform(heaviest), arms.uniform(LIGHT)]
+        .iter()
+        .find_map(|candidate| {
+            GLYPHS
+                .iter()
+                .find(|(_, glyph_arms)| *glyph_arms == candidate.0)
+                .map(|(glyph, _)| *glyph)
+        })
+        .unwrap_or('+')
+}
+
+fn encode_rounded(arms: Arms) -> Option<char> {
+    ROUNDED
+        .iter()
+        .find(|(_, glyph_arms)| *glyph_arms == arms.0)
+        .map(|(glyph, _)| *glyph)
+}
+
+fn encode_ascii(arms: Arms) -> char {
+    let [up, right, down, left] = arms.0.map(|arm| arm != NONE);
+    match (up || down, left || right) {
+        (true, true) => '+',
+        (true, false) => '|',
+        (false, true) => '-',
+        (false, false) => ' ',
+    }
+}
+
+#[rustfmt::skip]
+const ROUNDED: &[(char, [Weight; 4])] = &[
+    ('╭', [0, 1, 1, 0]), ('╮', [0, 0, 1, 1]), ('╯', [1, 0, 0, 1]), ('╰', [1, 1, 0, 0]),
+];
+
+/// Box-drawing glyphs from U+2500–U+257F with their arms (up, right, down, left).
+#[rustfmt::skip]
+const GLYPHS: &[(char, [Weight; 4])] = &[
+    ('─', [0, 1, 0, 1]), ('━', [0, 2, 0, 2]), ('│', [1, 0, 1, 0]), ('┃', [2, 0, 2, 0]),
+    ('┌', [0, 1, 1, 0]), ('┍', [0, 2, 1, 0]), ('┎', [0, 1, 2, 0]), ('┏', [0, 2, 2, 0]),
+    ('┐', [0, 0, 1, 1]), ('┑', [0, 0, 1, 2]), ('┒', [0, 0, 2, 1]), ('┓', [0, 0, 2, 2]),
+    ('└', [1, 1, 0, 0]), ('┕', [1, 2, 0, 0]), ('┖', [2, 1, 0, 0]), ('┗', [2, 2, 0, 0]),
+    ('┘', [1, 0, 0, 1]), ('┙', [1, 0, 0, 2]), ('┚', [2, 0, 0, 1]), ('┛', [2, 0, 0, 2]),
+    ('├', [1, 1, 1, 0]), ('┝', [1, 2, 1, 0]), ('┞', [2, 1, 1, 0]), ('┟', [1, 1, 2, 0]),
+    ('┠', [2, 1, 2, 0]), ('┡', [2, 2, 1, 0]), ('┢', [1, 2, 2, 0]), ('┣', [2, 2, 2, 0]),
+    ('┤', [1, 0, 1, 1]), ('┥', [1, 0, 1, 2]), ('┦', [2, 0, 1, 1]), ('┧', [1, 0, 2, 1]),
+    ('┨', [2, 0, 2, 1]), ('┩', [2, 0, 1, 2]), ('┪', [1, 0, 2, 2]), ('┫', [2, 0, 2, 2]),
+    ('┬', [0, 1, 1, 1]), ('┭', [0, 1, 1, 2]), ('┮', [0, 2, 1, 1]), ('┯', [0, 2, 1, 2]),
+    ('┰', [0, 1, 2, 1]), ('┱', [0, 1, 2, 2]), ('┲', [0, 2, 2, 1]), ('┳', [0, 2, 2, 2]),
+    ('┴', [1, 1, 0, 1]), ('┵', [1, 1, 0, 2]), ('┶', [1, 2, 0, 1]), ('┷', [1, 2, 0, 2]),
+    ('┸', [2, 1, 0, 1]), ('┹', [2, 1, 0, 2]), ('┺', [2, 2, 0, 1]), ('┻', [2, 2, 0, 2]),
+    ('┼', [1, 1, 1, 1]), ('┽', [1, 1, 1, 2]), ('┾', [1, 2, 1, 1]), ('┿', [1, 2, 1, 2]),
+    ('╀', [2, 1, 1, 1]), ('╁', [1, 1, 2, 1]), ('╂', [2, 1, 2, 1]), ('╃', [2, 1, 1, 2]),
+    ('╄', [2, 2, 1, 1]), ('╅', [1, 1, 2, 2]), ('╆', [1, 2, 2, 1]), ('╇', [2, 2, 1, 2]),
+    ('╈', [1, 2, 2, 2]), ('╉', [2, 1, 2, 2]), ('╊', [2, 2, 2, 1]), ('╋', [2, 2, 2, 2]),
+    ('═', [0, 3, 0, 3]), ('║', [3, 0, 3, 0]),
+    ('╒', [0, 3, 1, 0]), ('╓', [0, 1, 3, 0]), ('╔', [0, 3, 3, 0]),
+    ('╕', [0, 0, 1, 3]), ('╖', [0, 0, 3, 1]), ('╗', [0, 0, 3, 3]),
+    ('╘', [1, 3, 0, 0]), ('╙', [3, 1, 0, 0]), ('╚', [3, 3, 0, 0]),
+    ('╛', [1, 0, 0, 3]), ('╜', [3, 0, 0, 1]), ('╝', [3, 0, 0, 3]),
+    ('╞', [1, 3, 1, 0]), ('╟', [3, 1, 3, 0]), ('╠', [3, 3, 3, 0]),
+    ('╡', [1, 0, 1, 3]), ('╢', [3, 0, 3, 1]), ('╣', [3, 0, 3, 3]),
+    ('╤', [0, 3, 1, 3]), ('╥', [0, 1, 3, 1]), ('╦', [0, 3, 3, 3]),
+    ('╧', [1, 3, 0, 3]), ('╨', [3, 1, 0, 1]), ('╩', [3, 3, 0, 3]),
+    ('╪', [1, 3, 1, 3]), ('╫', [3, 1, 3, 1]), ('╬', [3, 3, 3, 3]),
+    ('╴', [0, 0, 0, 1]), ('╵', [1, 0, 0, 0]), ('╶', [0, 1, 0, 0]), ('╷', [0, 0, 1, 0]),
+    ('╸', [0, 0, 0, 2]), ('╹', [2, 0, 0, 0]), ('╺', [0, 2, 0, 0]), ('╻', [0, 0, 2, 0]),
+    ('╼', [0, 2, 0, 1]), ('╽', [1, 0, 2, 0]), ('╾', [0, 1, 0, 2]), ('╿', [2, 0, 1, 0]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use BorderStyle::{Ascii, Double, Heavy, Light, Rounded};
+
+    fn across(style: BorderStyle) -> Arms {
+        Arms::horizontal(style, true, true)
+    }
+
+    fn down(style: BorderStyle) -> Arms {
+        Arms::vertical(style, false, true)
+    }
+
+    #[test]
+    fn crossing_lines_form_junctions() {
+        assert_eq!(
+            merge_glyph('─', Arms::vertical(Light, true, true), Light),
+            '┼'
+        );
+        assert_eq!(
+            merge_glyph('│', Arms::horizontal(Light, false, true), Light),
+            '├'
+        );
+        assert_eq!(merge_glyph('─', down(Light), Light), '┬');
+        assert_eq!(merge_glyph('┌', across(Light), Light), '┬');
+    }
+
+    #[test]
+    fn non_line_glyphs_are_replaced() {
+        assert_eq!(merge_glyph('x', across(Light), Light), '─');
+        assert_eq!(merge_glyph(' ', down(Heavy), Heavy), '╻');
+    }
+
+    #[test]
+    fn mixed_weights_keep_each_arm() {
+        assert_eq!(
+            merge_glyph('━', Arms::vertical(Light, true, true), Light),
+            '┿'
+        );
+        assert_eq!(
+            merge_glyph('═', Arms::vertical(Light, true, true), Light),
+            '╪'
+        );
+        assert_eq!(merge_glyph('│', across(Heavy), Heavy), '┿');
+        // The heavier line wins where both run the same way.
+        assert_eq!(merge_glyph('─', across(Heavy), Heavy), '━');
+    }
+
+    #[test]
+    fn missing_glyphs_degrade_to_the_nearest_weight() {
+        // Unicode has no double/heavy crossings, so the heaviest arm wins.
+        assert_eq!(
+            merge_glyph('═', Arms::vertical(Heavy, true, true), Heavy),
+            '╬'
+        );
+        // Nor a lone double stub, which falls back to a light one.
+        assert_eq!(merge_glyph(' ', down(Double), Double), '╷');
+    }
+
+    #[test]
+    fn rounded_corners_turn_square_in_junctions() {
+        assert_eq!(
+            merge_glyph(' ', across(Rounded).merge(down(Rounded)), Rounded),
+            '┬'
+        );
+        assert_eq!(
+            merge_glyph(
+                ' ',
+                Arms::horizontal(Rounded, false, true).merge(down(Rounded)),
+                Rounded
+            ),
+            '╭'
+        );
+        assert_eq!(merge_glyph('╭', across(Rounded), Rounded), '┬');
+    }
+
+    #[test]
+    fn ascii_merges_into_plus_signs() {
+        assert_eq!(
+            merge_glyph('-', Arms::vertical(Ascii, true, true), Ascii),
+            '+'
+        );
+        assert_eq!(merge_glyph('|', across(Ascii), Ascii), '+');
+        assert_eq!(merge_glyph(' ', down(Ascii), Ascii), '|');
+        // Unicode line art is understood too.
+        assert_eq!(merge_glyph('│', across(Ascii), Ascii), '+');
+        assert_eq!(merge_glyph('═', Arms::default(), Ascii), '-');
+    }
+}
@@ -0,0 +1,258 @@
+use crossterm::style::Color;
+use serde::Deserialize;
+
+/// Parses a colour written in a config or asset file.
+///
+/// Accepts crossterm colour names in snake case (`red`, `dark_blue`, `grey`),
+/// `#rrggbb` hex triplets and ANSI palette indices (`0`–`255`).
+pub fn parse_color(text: &str) -> Option<Color> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        return Some(Color::Rgb {
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+        });
+    }
+    if let Ok(index) = text.parse::<u8>() {
+        return Some(Color::AnsiValue(index));
+    }
+
+    let color = match text.to_ascii_lowercase().replace(['-', ' '], "_").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "dark_grey" | "dark_gray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "dark_red" => Color::DarkRed,
+        "green" => Color::Green,
+        "dark_green" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "dark_yellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "dark_blue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "dark_magenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "dark_cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// RGB used for `Color::Reset` foregrounds when blending.
+pub(crate) const DEFAULT_FOREGROUND: Rgba = Rgba::rgb(229, 229, 229);
+/// RGB used for `Color::Reset` backgrounds when blending.
+pub(crate) const DEFAULT_BACKGROUND: Rgba = Rgba::rgb(0, 0, 0);
+
+/// An 8-bit RGB colour with coverage, used for translucent drawing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Rgba {
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Opaque RGB equivalent of a terminal colour, using the xterm palette for
+    /// named and indexed colours. `Color::Reset` has no fixed value.
+    pub fn from_color(color: Color) -> Option<Self> {
+        let (r, g, b) = match color {
+            Color::Reset => return None,
+            Color::Rgb { r, g, b } => (r, g, b),
+            Color::AnsiValue(index) => return Some(ansi_to_rgba(index)),
+            Color::Black => (0, 0, 0),
+            Color::DarkRed => (205, 0, 0),
+            Color::DarkGreen => (0, 205, 0),
+            Color::DarkYellow => (205, 205, 0),
+            Color::DarkBlue => (0, 0, 238),
+            Color::DarkMagenta => (205, 0, 205),
+            Color::DarkCyan => (0, 205, 205),
+            Color::Grey => (229, 229, 229),
+            Color::DarkGrey => (127, 127, 127),
+            Color::Red => (255, 0, 0),
+            Color::Green => (0, 255, 0),
+            Color::Yellow => (255, 255, 0),
+            Color::Blue => (92, 92, 255),
+            Color::Magenta => (255, 0, 255),
+            Color::Cyan => (0, 255, 255),
+            Color::White => (255, 255, 255),
+        };
+        Some(Self::rgb(r, g, b))
+    }
+
+    /// The colour as a truecolor terminal colour; alpha is dropped.
+    pub fn to_color(self) -> Color {
+        Color::Rgb {
+            r: self.r,
+            g: self.g,
+            b: self.b,
+        }
+    }
+}
+
+fn ansi_to_rgba(index: u8) -> Rgba {
+    const BASIC: [Color; 16] = [
+        Color::Black,
+        Color::DarkRed,
+        Color::DarkGreen,
+        Color::DarkYellow,
+        Color::DarkBlue,
+        Color::DarkMagenta,
+        Color::DarkCyan,
+        Color::Grey,
+        Color::DarkGrey,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => Rgba::from_color(BASIC[index as usize]).unwrap_or(DEFAULT_BACKGROUND),
+        16..=231 => {
+            let i = index - 16;
+            Rgba::rgb(
+                CUBE[(i / 36) as usize],
+                CUBE[(i / 6 % 6) as usize],
+                CUBE[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            Rgba::rgb(level, level, level)
+        }
+    }
+}
+
+/// How a translucent colour combines with what is already underneath.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendMode {
+    /// Plain alpha blending.
+    #[default]
+    Normal,
+    /// Sums the colours, brightening towards white; good for glows.
+    Add,
+    /// Multiplies the colours, darkening; good for shadows and tints.
+    Multiply,
+    /// Inverse of multiply, brightening without blowing out as fast as add.
+    Screen,
+}
+
+impl BlendMode {
+    fn mix(self, backdrop: f32, source: f32) -> f32 {
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Add => (backdrop + source).min(1.0),
+            BlendMode::Multiply => backdrop * source,
+            BlendMode::Screen => backdrop + source - backdrop * source,
+        }
+    }
+
+    /// Composites `source` over `backdrop` (W3C "source over" with this
+    /// blend function). The result is opaque whenever the backdrop is.
+    pub fn composite(self, backdrop: Rgba, source: Rgba) -> Rgba {
+        let source_alpha = source.a as f32 / 255.0;
+        let backdrop_alpha = backdrop.a as f32 / 255.0;
+        let alpha = source_alpha + backdrop_alpha * (1.0 - source_alpha);
+        if alpha <= 0.0 {
+            return Rgba::new(0, 0, 0, 0);
+        }
+        let channel = |b: u8, s: u8| {
+            let (b, s) = (b as f32 / 255.0, s as f32 / 255.0);
+            let blended = (1.0 - backdrop_alpha) * s + backdrop_alpha * self.mix(b, s);
+            let value =
+                (source_alpha * blended + backdrop_alpha * b * (1.0 - source_alpha)) / alpha;
+            (value.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        Rgba::new(
+            channel(backdrop.r, source.r),
+            channel(backdrop.g, source.g),
+            channel(backdrop.b, source.b),
+            (alpha * 255.0).round() as u8,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALF: u8 = 128;
+
+    #[test]
+    fn normal_blends_by_alpha() {
+        let result = BlendMode::Normal.composite(Rgba::rgb(0, 0, 200), Rgba::new(255, 0, 0, HALF));
+        assert_eq!(result, Rgba::rgb(128, 0, 100));
+    }
+
+    #[test]
+    fn add_brightens_and_clamps() {
+        let result =
+            BlendMode::Add.composite(Rgba::rgb(100, 100, 100), Rgba::new(200, 50, 0, HALF));
+        assert_eq!(result, Rgba::rgb(178, 125, 100));
+    }
+
+    #[test]
+    fn multiply_darkens() {
+        let result =
+            BlendMode::Multiply.composite(Rgba::rgb(200, 100, 0), Rgba::new(128, 255, 255, HALF));
+        assert_eq!(result, Rgba::rgb(150, 100, 0));
+    }
+
+    #[test]
+    fn screen_lightens() {
+        let result =
+            BlendMode::Screen.composite(Rgba::rgb(100, 0, 255), Rgba::new(100, 255, 0, HALF));
+        assert_eq!(result, Rgba::rgb(131, 128, 255));
+    }
+
+    #[test]
+    fn transparent_backdrops_keep_the_source() {
+        let source = Rgba::new(10, 20, 30, 64);
+        for mode in [
+            BlendMode::Normal,
+            BlendMode::Add,
+            BlendMode::Multiply,
+            BlendMode::Screen,
+        ] {
+            assert_eq!(mode.composite(Rgba::new(0, 0, 0, 0), source), source);
+        }
+        assert_eq!(
+            BlendMode::Normal
+                .composite(Rgba::new(0, 0, 0, 0), Rgba::new(0, 0, 0, 0))
+                .a,
+            0
+        );
+    }
+
+    #[test]
+    fn translucent_backdrops_stay_translucent() {
+        let result =
+            BlendMode::Normal.composite(Rgba::new(0, 0, 255, HALF), Rgba::new(255, 0, 0, HALF));
+        assert_eq!(result.a, 192);
+        assert!(result.r > result.b, "{result:?}");
+    }
+}
@@ -1,10 +1,15 @@
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{Context as AnyhowContext, Result};
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
 use serde::Deserialize;
-use toml::value::Table;
+use toml::{value::Table, Value};
 
+use crate::anim::{Easing, Keyframes, Param};
 use crate::draw::PixelMode;
+use crate::effects::Effect;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct EngineSettings {
@@ -13,13 +18,24 @@ pub struct EngineSettings {
     pub framerate: u32,
     #[serde(default)]
     pub mode: PixelMode,
+    /// Seed for `Context::rng` and seeded generators such as noise, so runs
+    /// can be reproduced. The CLI picks a random one when it is absent.
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct SceneConfig {
     pub name: String,
+    /// Which scene to run; defaults to `name`, so a stock scene can be
+    /// listed under its own name or several times under different ones.
+    #[serde(default, rename = "type")]
+    pub scene_type: Option<String>,
     #[serde(default)]
     pub duration_ms: Option<u64>,
+    /// Post-processing applied to the scene's frames, in order.
+    #[serde(default)]
+    pub effects: Vec<Effect>,
     #[serde(flatten)]
     pub settings: Table,
 }
@@ -28,6 +44,100 @@ impl SceneConfig {
     pub fn duration_seconds(&self) -> Option<f32> {
         self.duration_ms.map(|ms| ms as f32 / 1_000.0)
     }
+
+    /// The `type` setting, or the scene's name when it has none.
+    pub fn scene_type(&self) -> &str {
+        self.scene_type.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Reads a numeric setting that is fixed for the scene's lifetime,
+    /// accepting integers as well as floats. Keyframe tables are rejected;
+    /// settings that can change over time are read with `param`.
+    pub fn float_setting(&self, key: &str) -> Result<Option<f32>> {
+        let Some(value) = self.settings.get(key) else {
+            return Ok(None);
+        };
+        let name = &self.name;
+        if value.is_table() {
+            bail!("Scene '{name}': {key} cannot be keyframed");
+        }
+        number(value)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Scene '{name}': {key} must be a number"))
+    }
+
+    /// Reads a setting that may change over the scene's lifetime: either a
+    /// plain number or a table of keyframes, evaluated against
+    /// `Context::scene_time`:
+    ///
+    /// ```text
+    /// amp = 2.0
+    /// amp = { keys = [[0.0, 2.0], [4.0, 8.0]], ease = "cubic_in_out", loop = true }
+    /// ```
+    ///
+    /// Keys are `[seconds, value]` pairs. `ease` (default `linear`) names
+    /// an `Easing` applied between each pair of keys, and `loop` restarts
+    /// the keys after the last one.
+    pub fn param(&self, key: &str) -> Result<Option<Param>> {
+        let Some(value) = self.settings.get(key) else {
+            return Ok(None);
+        };
+        if let Some(number) = number(value) {
+            return Ok(Some(Param::Constant(number)));
+        }
+        let name = &self.name;
+        let table = value.as_table().ok_or_else(|| {
+            anyhow!("Scene '{name}': {key} must be a number or a table of keyframes")
+        })?;
+        let keys = table
+            .get("keys")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Scene '{name}': {key} needs keys = [[time, value], ...]"))?;
+        let keys = keys
+            .iter()
+            .map(|key_value| match key_value.as_array().map(Vec::as_slice) {
+                Some([time, value]) => number(time).zip(number(value)),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|keys| !keys.is_empty())
+            .ok_or_else(|| {
+                anyhow!("Scene '{name}': {key} keys must be a non-empty list of [time, value]")
+            })?;
+        let easing = match table.get("ease") {
+            Some(ease) => {
+                let ease = ease.as_str().unwrap_or_default();
+                Easing::parse(ease)
+                    .map_err(|_| anyhow!("Scene '{name}': {key} has unknown ease '{ease}'"))?
+            }
+            None => Easing::Linear,
+        };
+        let looping = table.get("loop").and_then(Value::as_bool).unwrap_or(false);
+        Ok(Some(Param::Keyframed(
+            Keyframes::new(keys)
+                .with_easing(easing)
+                .with_looping(looping),
+        )))
+    }
+
+    /// Reads a string setting naming a file (sprite, font, image...) and
+    /// resolves it relative to the directory containing the config file.
+    pub fn path_setting(&self, key: &str, config_path: impl AsRef<Path>) -> Option<PathBuf> {
+        let value = self.settings.get(key)?.as_str()?;
+        let base = config_path
+            .as_ref()
+            .parent()
+            .unwrap_or_else(|| Path::new(""));
+        Some(base.join(value))
+    }
+}
+
+/// A float or integer setting value as `f32`.
+fn number(value: &Value) -> Option<f32> {
+    value
+        .as_float()
+        .or_else(|| value.as_integer().map(|i| i as f64))
+        .map(|v| v as f32)
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -47,3 +157,87 @@ pub fn load_config(path: impl AsRef<Path>) -> Result<EngineConfig> {
 
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(settings: &str) -> SceneConfig {
+        toml::from_str(&format!("name = \"test\"\n{settings}")).unwrap()
+    }
+
+    #[test]
+    fn params_accept_numbers_and_keyframes() {
+        let scene = scene(
+            "amp = 2.0\n\
+             count = 3\n\
+             wave = { keys = [[0.0, 0.0], [2.0, 4.0]] }\n\
+             eased = { keys = [[0, 0], [1, 1]], ease = \"quad_in\" }",
+        );
+        let value = |key: &str, time: f32| scene.param(key).unwrap().unwrap().value(time);
+        assert_eq!(value("amp", 5.0), 2.0);
+        assert_eq!(value("count", 0.0), 3.0);
+        assert_eq!(value("wave", 1.0), 2.0);
+        assert_eq!(value("wave", 9.0), 4.0);
+        assert_eq!(value("eased", 0.5), 0.25);
+        assert!(scene.param("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn looping_params_wrap_around() {
+        let scene = scene("wave = { keys = [[0.0, 0.0], [2.0, 4.0]], loop = true }");
+        let wave = scene.param("wave").unwrap().unwrap();
+        assert_eq!(wave.value(1.0), 2.0);
+        assert_eq!(wave.value(3.0), 2.0);
+    }
+
+    #[test]
+    fn malformed_params_are_reported_against_the_scene() {
+        let scene = scene(
+            "text = \"fast\"\n\
+             no_keys = { ease = \"linear\" }\n\
+             empty = { keys = [] }\n\
+             short = { keys = [[0.0]] }\n\
+             ease = { keys = [[0.0, 1.0]], ease = \"wobbly\" }",
+        );
+        let error = |key: &str| scene.param(key).unwrap_err().to_string();
+        assert_eq!(
+            error("text"),
+            "Scene 'test': text must be a number or a table of keyframes"
+        );
+        assert!(error("no_keys").contains("needs keys"));
+        assert!(error("empty").contains("non-empty"));
+        assert!(error("short").contains("non-empty"));
+        assert_eq!(
+            error("ease"),
+            "Scene 'test': ease has unknown ease 'wobbly'"
+        );
+    }
+
+    #[test]
+    fn float_settings_reject_keyframes() {
+        let scene = scene(
+            "count = 12\n\
+             size = 1.5\n\
+             text = \"big\"\n\
+             wave = { keys = [[0.0, 1.0]] }",
+        );
+        assert_eq!(scene.float_setting("count").unwrap(), Some(12.0));
+        assert_eq!(scene.float_setting("size").unwrap(), Some(1.5));
+        assert_eq!(scene.float_setting("missing").unwrap(), None);
+        assert_eq!(
+            scene.float_setting("text").unwrap_err().to_string(),
+            "Scene 'test': text must be a number"
+        );
+        assert_eq!(
+            scene.float_setting("wave").unwrap_err().to_string(),
+            "Scene 'test': wave cannot be keyframed"
+        );
+    }
+
+    #[test]
+    fn scene_type_defaults_to_the_name() {
+        assert_eq!(scene("").scene_type(), "test");
+        assert_eq!(scene("type = \"plasma\"").scene_type(), "plasma");
+    }
+}
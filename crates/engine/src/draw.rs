@@ -1,22 +1,30 @@
+use crate::border::{merge_glyph, Arms, BorderStyle};
+use crate::color::{BlendMode, Rgba, DEFAULT_FOREGROUND};
+use crate::figlet::FigletFont;
+use crate::fill::{mix_stops, Connectivity, Fill, FloodMode, GradientSample};
+use crate::image::{render_cells, Image, ImageCell, ImageOptions};
+use crate::path::{arc_segment_count, Path, Point, Polyline, DEFAULT_TOLERANCE};
+use crate::plot::{plot_cells, PlotPoint};
 use crate::render::Cell;
+use crate::shader::{shade_cells, Shade};
+use crate::sprite::{flipped_source, mirror_symbol, Flip, Sprite};
+use crate::stroke::{dash_polyline, stroke_outline, LineCap, StrokeStyle};
+use crate::text::{char_width, text_width, truncate_with_ellipsis, wrap_text, TextAlign};
+use crate::transform::Transform;
 use crossterm::style::Color;
 use serde::Deserialize;
+use std::f32::consts::PI;
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PixelMode {
+    #[default]
     Ascii,
     #[serde(alias = "halfblock")]
     HalfBlock,
     Braille,
 }
 
-impl Default for PixelMode {
-    fn default() -> Self {
-        PixelMode::Ascii
-    }
-}
-
 impl PixelMode {
     pub fn next(self) -> Self {
         match self {
@@ -25,6 +33,43 @@ impl PixelMode {
             PixelMode::Braille => PixelMode::Ascii,
         }
     }
+
+    /// Logical pixels per cell, horizontally and vertically.
+    pub fn cell_resolution(self) -> (usize, usize) {
+        match self {
+            PixelMode::Ascii => (1, 1),
+            PixelMode::HalfBlock => (1, 2),
+            PixelMode::Braille => (2, 4),
+        }
+    }
+}
+
+/// Device-space rectangle outside of which drawing is discarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ClipRect {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+
+    pub fn intersect(&self, other: &ClipRect) -> ClipRect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        ClipRect {
+            x,
+            y,
+            width: (right - x).max(0),
+            height: (bottom - y).max(0),
+        }
+    }
 }
 
 #[allow(dead_code)] // Fields will be used in future steps
@@ -35,7 +80,19 @@ pub struct Canvas<'a> {
     current_fg: Color,
     current_bg: Color,
     current_symbol: char,
+    fg_alpha: u8,
+    bg_alpha: u8,
+    blend: BlendMode,
     pub current_pixel_mode: PixelMode,
+    transform: Transform,
+    transform_stack: Vec<(Transform, Option<ClipRect>)>,
+    clip: Option<ClipRect>,
+    stroke: StrokeStyle,
+    fill: Fill,
+    filling: bool,
+    /// Cells already painted by the shape being drawn, while translucent or
+    /// blended paint must reach each cell only once (see `once_per_cell`).
+    covered: Option<Vec<bool>>,
 }
 
 impl<'a> Canvas<'a> {
@@ -47,97 +104,193 @@ impl<'a> Canvas<'a> {
             current_fg: Color::Reset,
             current_bg: Color::Reset,
             current_symbol: ' ',
+            fg_alpha: 255,
+            bg_alpha: 255,
+            blend: BlendMode::Normal,
             current_pixel_mode: PixelMode::Ascii,
+            transform: Transform::IDENTITY,
+            transform_stack: Vec::new(),
+            clip: None,
+            stroke: StrokeStyle::default(),
+            fill: Fill::Solid,
+            filling: false,
+            covered: None,
         }
     }
 
+    /// Draws `text` left to right starting at the transformed anchor `(x, y)`.
+    /// Glyphs themselves are never rotated or scaled.
+    ///
+    /// Wide glyphs (CJK, emoji) take two cells, the second holding
+    /// `Cell::CONTINUATION`; a wide glyph that would straddle the right edge is
+    /// dropped. Zero-width characters attach to the preceding glyph; a cell
+    /// holds one such mark, so any further marks on the same glyph are dropped.
     pub fn draw_text(&mut self, x: u16, y: u16, text: &str) {
-        if y >= self.height {
+        let (mut x, y) = self.to_device(x as f32, y as f32);
+        if y < 0 || y >= self.height as i32 {
             return;
         }
-        for (i, c) in text.chars().enumerate() {
-            let x = x + i as u16;
-            if x >= self.width {
+        let width = self.width as i32;
+        let mut last_index: Option<usize> = None;
+        for c in text.chars() {
+            if x >= width {
                 break;
             }
-            let index = (y * self.width + x) as usize;
-            self.buffer[index] = Cell {
-                symbol: c,
-                fg: self.current_fg,
-                bg: self.current_bg,
+            if c.is_control() {
+                continue;
+            }
+            let glyph_width = char_width(c);
+            if glyph_width == 0 {
+                if let Some(index) = last_index {
+                    self.buffer[index].combining.get_or_insert(c);
+                }
+                continue;
+            }
+
+            let glyph_width = glyph_width as i32;
+            let (Some(index), Some(_)) =
+                (self.index_of(x, y), self.index_of(x + glyph_width - 1, y))
+            else {
+                last_index = None;
+                x += glyph_width;
+                continue;
             };
+            for offset in 0..glyph_width {
+                self.release_wide_glyph(x + offset, y);
+            }
+            self.write(index, c, self.current_fg, self.current_bg);
+            if glyph_width == 2 {
+                self.write(
+                    index + 1,
+                    Cell::CONTINUATION,
+                    self.current_fg,
+                    self.current_bg,
+                );
+            }
+            last_index = Some(index);
+            x += glyph_width;
         }
     }
 
-    pub fn draw_point(&mut self, x: u16, y: u16) {
-        if x < self.width && y < self.height {
-            let index = (y * self.width + x) as usize;
-            self.buffer[index] = Cell {
-                symbol: self.current_symbol,
-                fg: self.current_fg,
-                bg: self.current_bg,
+    /// Lays `text` out inside a `w` x `h` cell box: words wrap at the box
+    /// edge, each line is aligned with `align`, and text that runs past the
+    /// last line is cut off with `…`. Lines past the end of the coordinate
+    /// range are skipped.
+    pub fn draw_text_box(&mut self, x: u16, y: u16, w: u16, h: u16, text: &str, align: TextAlign) {
+        let lines = wrap_text(text, w as usize, Some(h as usize));
+        for (row, line) in lines.iter().enumerate() {
+            let slack = (w as usize).saturating_sub(text_width(line));
+            let offset = match align {
+                TextAlign::Left => 0,
+                TextAlign::Center => slack / 2,
+                TextAlign::Right => slack,
             };
+            let (Some(x), Some(y)) = (x.checked_add(offset as u16), y.checked_add(row as u16))
+            else {
+                continue;
+            };
+            self.draw_text(x, y, line);
         }
     }
 
-    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
-        let mut x0 = x0;
-        let mut y0 = y0;
-        let dx = (x1 - x0).abs();
-        let sx = if x0 < x1 { 1 } else { -1 };
-        let dy = -(y1 - y0).abs();
-        let sy = if y0 < y1 { 1 } else { -1 };
-        let mut err = dx + dy; // error value e_xy
+    pub fn draw_point(&mut self, x: u16, y: u16) {
+        let (x, y) = self.to_device(x as f32, y as f32);
+        self.plot(x, y);
+    }
 
-        loop {
-            self.draw_point(x0 as u16, y0 as u16);
-            if x0 == x1 && y0 == y1 {
-                break;
-            }
-            let e2 = 2 * err;
-            if e2 >= dy {
-                // e_xy + e_x > 0
-                err += dy;
-                x0 += sx;
-            }
-            if e2 <= dx {
-                // e_xy + e_y < 0
-                err += dx;
-                y0 += sy;
-            }
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
+        if !self.stroke.is_hairline() {
+            let from = self.transform.apply(Point::new(x0 as f32, y0 as f32));
+            let to = self.transform.apply(Point::new(x1 as f32, y1 as f32));
+            self.stroke_device(&[from, to], false);
+            return;
         }
+        let (x0, y0) = self.to_device(x0 as f32, y0 as f32);
+        let (x1, y1) = self.to_device(x1 as f32, y1 as f32);
+        self.raster_line(x0, y0, x1, y1);
     }
 
     pub fn draw_rect(&mut self, x: u16, y: u16, w: u16, h: u16, filled: bool) {
+        if w == 0 || h == 0 {
+            return;
+        }
+        if filled && !self.filling {
+            return self.as_fill(|canvas| canvas.draw_rect(x, y, w, h, true));
+        }
+        if self.needs_coverage() {
+            return self.once_per_cell(|canvas| canvas.draw_rect(x, y, w, h, filled));
+        }
+        if !self.transform.is_translation() || (!filled && !self.stroke.is_hairline()) {
+            let (x, y, w, h) = (x as f32, y as f32, w as f32, h as f32);
+            if filled {
+                self.fill_transformed_cells(x, y, w, h);
+            } else {
+                let corners = [
+                    Point::new(x, y),
+                    Point::new(x + w - 1.0, y),
+                    Point::new(x + w - 1.0, y + h - 1.0),
+                    Point::new(x, y + h - 1.0),
+                ];
+                let ring = corners.map(|p| self.transform.apply(p));
+                self.stroke_device(&ring, true);
+            }
+            return;
+        }
+
+        let (x, y) = self.to_device(x as f32, y as f32);
+        let (w, h) = (w as i32, h as i32);
         if filled {
             for current_y in y..(y + h) {
                 for current_x in x..(x + w) {
-                    self.draw_point(current_x, current_y);
+                    self.plot(current_x, current_y);
                 }
             }
         } else {
             // Top line
-            self.draw_line(x as i32, y as i32, (x + w - 1) as i32, y as i32);
+            self.raster_line(x, y, x + w - 1, y);
             // Bottom line
-            self.draw_line(
-                x as i32,
-                (y + h - 1) as i32,
-                (x + w - 1) as i32,
-                (y + h - 1) as i32,
-            );
+            self.raster_line(x, y + h - 1, x + w - 1, y + h - 1);
             // Left line
-            self.draw_line(x as i32, y as i32, x as i32, (y + h - 1) as i32);
+            self.raster_line(x, y, x, y + h - 1);
             // Right line
-            self.draw_line(
-                (x + w - 1) as i32,
-                y as i32,
-                (x + w - 1) as i32,
-                (y + h - 1) as i32,
-            );
+            self.raster_line(x + w - 1, y, x + w - 1, y + h - 1);
         }
     }
 
     pub fn draw_circle(&mut self, cx: i32, cy: i32, r: i32, filled: bool) {
+        if filled && !self.filling {
+            return self.as_fill(|canvas| canvas.draw_circle(cx, cy, r, true));
+        }
+        if self.needs_coverage() {
+            return self.once_per_cell(|canvas| canvas.draw_circle(cx, cy, r, filled));
+        }
+        if !self.transform.is_translation() || (!filled && !self.stroke.is_hairline()) {
+            // Rotation and scaling turn the circle into an ellipse, and styled outlines
+            // need the stroker, so rasterize a polygon.
+            let radius = if filled { r as f32 + 0.5 } else { r as f32 };
+            let steps = arc_segment_count(
+                radius * self.transform.max_scale(),
+                2.0 * PI,
+                DEFAULT_TOLERANCE,
+            );
+            let ring: Vec<Point> = (0..steps)
+                .map(|i| {
+                    let angle = 2.0 * PI * i as f32 / steps as f32;
+                    self.transform.apply(Point::new(
+                        cx as f32 + radius * angle.cos(),
+                        cy as f32 + radius * angle.sin(),
+                    ))
+                })
+                .collect();
+            if filled {
+                self.fill_polygons(&[&ring]);
+            } else {
+                self.stroke_device(&ring, true);
+            }
+            return;
+        }
+
+        let (cx, cy) = self.to_device(cx as f32, cy as f32);
         if filled {
             // Filled circle (draw horizontal lines)
             let mut x = r;
@@ -145,10 +298,10 @@ impl<'a> Canvas<'a> {
             let mut err = 0;
 
             while x >= y {
-                self.draw_line(cx - x, cy + y, cx + x, cy + y);
-                self.draw_line(cx - y, cy + x, cx + y, cy + x);
-                self.draw_line(cx - x, cy - y, cx + x, cy - y);
-                self.draw_line(cx - y, cy - x, cx + y, cy - x);
+                self.raster_line(cx - x, cy + y, cx + x, cy + y);
+                self.raster_line(cx - y, cy + x, cx + y, cy + x);
+                self.raster_line(cx - x, cy - y, cx + x, cy - y);
+                self.raster_line(cx - y, cy - x, cx + y, cy - x);
 
                 y += 1;
                 err += 1 + 2 * y;
@@ -164,14 +317,14 @@ impl<'a> Canvas<'a> {
             let mut err = 0;
 
             while x >= y {
-                self.draw_point((cx + x) as u16, (cy + y) as u16);
-                self.draw_point((cx + y) as u16, (cy + x) as u16);
-                self.draw_point((cx - y) as u16, (cy + x) as u16);
-                self.draw_point((cx - x) as u16, (cy + y) as u16);
-                self.draw_point((cx - x) as u16, (cy - y) as u16);
-                self.draw_point((cx - y) as u16, (cy - x) as u16);
-                self.draw_point((cx + y) as u16, (cy - x) as u16);
-                self.draw_point((cx + x) as u16, (cy - y) as u16);
+                self.plot(cx + x, cy + y);
+                self.plot(cx + y, cy + x);
+                self.plot(cx - y, cy + x);
+                self.plot(cx - x, cy + y);
+                self.plot(cx - x, cy - y);
+                self.plot(cx - y, cy - x);
+                self.plot(cx + y, cy - x);
+                self.plot(cx + x, cy - y);
 
                 y += 1;
                 err += 1 + 2 * y;
@@ -183,15 +336,1033 @@ impl<'a> Canvas<'a> {
         }
     }
 
+    /// Draws `text` in large FIGlet lettering with its top-left corner at
+    /// `(x, y)`. Blank parts of the glyphs leave the canvas untouched. The
+    /// banner follows the transform stack: rotated or scaled banners are
+    /// rasterized cell by cell.
+    pub fn draw_banner(&mut self, x: i32, y: i32, text: &str, font: &FigletFont) {
+        self.draw_banner_with_colors(x, y, text, font, &[]);
+    }
+
+    /// Like `draw_banner`, but colours each glyph by cycling through `colors`.
+    /// An empty slice uses the current foreground colour.
+    pub fn draw_banner_with_colors(
+        &mut self,
+        x: i32,
+        y: i32,
+        text: &str,
+        font: &FigletFont,
+        colors: &[Color],
+    ) {
+        let saved_symbol = self.current_symbol;
+        let saved_fg = self.current_fg;
+        for (row, cells) in font.layout_cells(text).iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                if cell.symbol == ' ' {
+                    continue;
+                }
+                if !colors.is_empty() {
+                    self.current_fg = colors[cell.glyph % colors.len()];
+                }
+                self.current_symbol = cell.symbol;
+                self.stamp(x + column as i32, y + row as i32);
+            }
+        }
+        self.current_symbol = saved_symbol;
+        self.current_fg = saved_fg;
+    }
+
+    /// Draws frame `frame` of `sprite` with its top-left corner at `(x, y)`.
+    /// Transparent cells leave the canvas untouched and cells without a colour
+    /// of their own use the current foreground. Pair with `Sprite::frame_at`
+    /// to animate from `Context::total_time`.
+    pub fn draw_sprite(&mut self, sprite: &Sprite, x: i32, y: i32, frame: usize, flip: Flip) {
+        let Some(frame) = sprite.frames().get(frame % sprite.frame_count().max(1)) else {
+            return;
+        };
+        let saved_symbol = self.current_symbol;
+        let saved_fg = self.current_fg;
+        for row in 0..frame.height {
+            for column in 0..frame.width {
+                let (sx, sy) = flipped_source(frame, column, row, flip);
+                let Some(cell) = frame.cell(sx, sy) else {
+                    continue;
+                };
+                self.current_symbol = mirror_symbol(cell.symbol, flip);
+                self.current_fg = cell.color.unwrap_or(saved_fg);
+                let wide = char_width(cell.symbol) == 2;
+                // A wide glyph starts in its left column, which mirroring
+                // moves one to the left of the source cell.
+                let left = if wide && flip.horizontal() {
+                    column as i32 - 1
+                } else {
+                    column as i32
+                };
+                self.stamp(x + left, y + row as i32);
+                if wide && self.transform.is_translation() {
+                    let (dx, dy) = self.to_device((x + left + 1) as f32, (y + row as i32) as f32);
+                    self.put(dx, dy, Cell::CONTINUATION);
+                }
+            }
+        }
+        self.current_symbol = saved_symbol;
+        self.current_fg = saved_fg;
+    }
+
+    /// Draws `image` scaled into the `w` x `h` cell box at `(x, y)` using the
+    /// canvas' current pixel mode and default `ImageOptions`.
+    pub fn draw_image(&mut self, image: &Image, x: i32, y: i32, w: u16, h: u16) {
+        self.draw_image_with_options(image, x, y, w, h, &ImageOptions::default());
+    }
+
+    /// Draws `image` scaled into the `w` x `h` cell box at `(x, y)`.
+    ///
+    /// Half blocks give two colour pixels per cell, braille 2x4 thresholded
+    /// dots and ASCII one luminance-mapped glyph per cell. Transparent areas
+    /// leave the canvas untouched. Like text, the box is anchored through the
+    /// transform but stays aligned to the cell grid; use `Image::fit` to keep
+    /// the aspect ratio.
+    pub fn draw_image_with_options(
+        &mut self,
+        image: &Image,
+        x: i32,
+        y: i32,
+        w: u16,
+        h: u16,
+        options: &ImageOptions,
+    ) {
+        if w == 0 || h == 0 || image.is_empty() {
+            return;
+        }
+        let mode = options.mode.unwrap_or(self.current_pixel_mode);
+        let (columns, rows) = (w as usize, h as usize);
+        let cells = render_cells(image, columns, rows, mode, options);
+        self.draw_cells(x, y, columns, &cells);
+    }
+
+    /// Draws prerendered `cells`, `columns` to a row, with the top-left cell
+    /// at `(x, y)`. `None` cells leave the canvas untouched and cells without
+    /// colours use the current ones. Like images, the block is anchored
+    /// through the transform but stays aligned to the cell grid.
+    pub fn draw_cells(&mut self, x: i32, y: i32, columns: usize, cells: &[Option<ImageCell>]) {
+        if columns == 0 {
+            return;
+        }
+        let (x, y) = self.to_device(x as f32, y as f32);
+        for (index, cell) in cells.iter().enumerate() {
+            let Some(cell) = cell else {
+                continue;
+            };
+            let (column, row) = ((index % columns) as i32, (index / columns) as i32);
+            self.put_cell(x + column, y + row, cell);
+        }
+    }
+
+    /// Plots `points` at their transformed positions in the current pixel
+    /// mode: one glyph per cell in ASCII, two colour pixels per cell in half
+    /// blocks and 2x4 dots in braille. Later points paint over earlier ones,
+    /// cells without points are left untouched and the clip rectangle
+    /// applies.
+    pub fn plot_points(&mut self, points: impl IntoIterator<Item = PlotPoint>) {
+        let cells = plot_cells(points, self.current_pixel_mode, &self.transform);
+        for ((x, y), cell) in cells {
+            self.put_cell(x, y, &cell);
+        }
+    }
+
+    /// Evaluates `shader(x, y, time)` at every logical pixel of the current
+    /// pixel mode and paints the result: density-ramp glyphs in ASCII,
+    /// colour pairs in half blocks and ordered-dithered dots in braille.
+    ///
+    /// `x` and `y` are device logical pixels (see `logical_size`); the
+    /// transform is ignored and only pixels inside the clip rectangle are
+    /// evaluated. With the `parallel` feature rows are shaded on rayon's
+    /// thread pool, which is why the closure must be `Sync`.
+    pub fn shade<F, S>(&mut self, time: f32, shader: F)
+    where
+        F: Fn(f32, f32, f32) -> S + Sync,
+        S: Into<Shade>,
+    {
+        let bounds = ClipRect {
+            x: 0,
+            y: 0,
+            width: self.width as i32,
+            height: self.height as i32,
+        };
+        let region = self.clip.map_or(bounds, |clip| clip.intersect(&bounds));
+        if region.width <= 0 || region.height <= 0 {
+            return;
+        }
+        let default_color = self.foreground_rgba();
+        let columns = region.width as usize;
+        let cells = shade_cells(
+            (region.x as usize, region.y as usize),
+            (columns, region.height as usize),
+            self.current_pixel_mode,
+            time,
+            default_color,
+            &shader,
+        );
+        for (index, cell) in cells.iter().enumerate() {
+            let (column, row) = ((index % columns) as i32, (index / columns) as i32);
+            self.put_cell(region.x + column, region.y + row, cell);
+        }
+    }
+
+    /// Paints a grid of values through `shade`, one grid cell per logical
+    /// pixel from the top-left of the canvas. `value(x, y)` is clamped to
+    /// 0–1 and picks the glyph or dot density and the colour along
+    /// `colors`; an empty ramp keeps the current foreground.
+    pub fn shade_grid<F>(&mut self, colors: &[Rgba], value: F)
+    where
+        F: Fn(usize, usize) -> f32 + Sync,
+    {
+        self.shade(0.0, |x, y, _| {
+            let t = value(x as usize, y as usize).clamp(0.0, 1.0);
+            let shade = Shade::new(t);
+            match mix_stops(colors, t) {
+                Some(color) => shade.with_color(color),
+                None => shade,
+            }
+        });
+    }
+
+    /// The canvas size in logical pixels of the current pixel mode.
+    pub fn logical_size(&self) -> (usize, usize) {
+        let (sx, sy) = self.current_pixel_mode.cell_resolution();
+        (self.width as usize * sx, self.height as usize * sy)
+    }
+
+    /// Draws a frame of box-drawing glyphs. Lines merge with any line art
+    /// already on the canvas, so touching or overlapping boxes get proper
+    /// junctions (`┬ ├ ┼`). Like text, the frame is anchored through the
+    /// transform but stays aligned to the cell grid.
+    pub fn draw_box(&mut self, x: u16, y: u16, w: u16, h: u16, style: BorderStyle) {
+        if w == 0 || h == 0 {
+            return;
+        }
+        let (x, y) = self.to_device(x as f32, y as f32);
+        let (w, h) = (w as i32, h as i32);
+        if h == 1 {
+            self.line_art_row(x, y, w, style);
+            return;
+        }
+        if w == 1 {
+            self.line_art_column(x, y, h, style);
+            return;
+        }
+
+        // Corners are written in one go: Unicode has no glyph for a lone double
+        // stub, so merging the two edges one after the other would lose weight.
+        let (right, bottom) = (x + w - 1, y + h - 1);
+        let corners = [
+            (
+                x,
+                y,
+                Arms::horizontal(style, false, true),
+                Arms::vertical(style, false, true),
+            ),
+            (
+                right,
+                y,
+                Arms::horizontal(style, true, false),
+                Arms::vertical(style, false, true),
+            ),
+            (
+                x,
+                bottom,
+                Arms::horizontal(style, false, true),
+                Arms::vertical(style, true, false),
+            ),
+            (
+                right,
+                bottom,
+                Arms::horizontal(style, true, false),
+                Arms::vertical(style, true, false),
+            ),
+        ];
+        for (cx, cy, horizontal, vertical) in corners {
+            self.merge_line_art(cx, cy, horizontal.merge(vertical), style);
+        }
+        for i in (x + 1)..right {
+            self.merge_line_art(i, y, Arms::horizontal(style, true, true), style);
+            self.merge_line_art(i, bottom, Arms::horizontal(style, true, true), style);
+        }
+        for i in (y + 1)..bottom {
+            self.merge_line_art(x, i, Arms::vertical(style, true, true), style);
+            self.merge_line_art(right, i, Arms::vertical(style, true, true), style);
+        }
+    }
+
+    /// Draws a box with `title` set into its top edge, truncated to fit.
+    pub fn draw_titled_box(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        style: BorderStyle,
+        title: &str,
+    ) {
+        self.draw_box(x, y, w, h, style);
+        let room = (w as usize).saturating_sub(4);
+        if room == 0 || title.is_empty() {
+            return;
+        }
+        let label = truncate_with_ellipsis(&format!(" {title} "), room);
+        if let Some(x) = x.checked_add(2) {
+            self.draw_text(x, y, &label);
+        }
+    }
+
+    /// Horizontal line-art rule of `len` cells that merges with existing line art.
+    pub fn draw_hline(&mut self, x: u16, y: u16, len: u16, style: BorderStyle) {
+        let (x, y) = self.to_device(x as f32, y as f32);
+        self.line_art_row(x, y, len as i32, style);
+    }
+
+    /// Vertical line-art rule of `len` cells that merges with existing line art.
+    pub fn draw_vline(&mut self, x: u16, y: u16, len: u16, style: BorderStyle) {
+        let (x, y) = self.to_device(x as f32, y as f32);
+        self.line_art_column(x, y, len as i32, style);
+    }
+
+    pub fn stroke_path(&mut self, path: &Path) {
+        if self.needs_coverage() {
+            return self.once_per_cell(|canvas| canvas.stroke_path(path));
+        }
+        for polyline in self.flatten_transformed(path) {
+            self.stroke_device(&polyline.points, polyline.closed);
+        }
+    }
+
+    /// Fills the area enclosed by `path` using the non-zero winding rule.
+    /// Open sub-paths are implicitly closed.
+    pub fn fill_path(&mut self, path: &Path) {
+        let polylines = self.flatten_transformed(path);
+        let rings: Vec<&[Point]> = polylines.iter().map(|p| p.points.as_slice()).collect();
+        self.as_fill(|canvas| canvas.fill_polygons(&rings));
+    }
+
+    /// Fills the region connected to `(x, y)` with the current fill. The
+    /// region grows through neighbouring cells that match the seed cell
+    /// under `mode`, so it stops at outlines of a different symbol or colour.
+    /// It never leaves the canvas or the clip rectangle.
+    pub fn flood_fill(&mut self, x: i32, y: i32, mode: FloodMode, connectivity: Connectivity) {
+        let (x, y) = self.to_device(x as f32, y as f32);
+        let Some(seed_index) = self.index_of(x, y) else {
+            return;
+        };
+        let seed = self.buffer[seed_index];
+        let mut visited = vec![false; self.buffer.len()];
+        visited[seed_index] = true;
+        let mut region = Vec::new();
+        let mut pending = vec![(x, y)];
+        while let Some((cx, cy)) = pending.pop() {
+            region.push((cx, cy));
+            for &(dx, dy) in connectivity.offsets() {
+                let (nx, ny) = (cx + dx, cy + dy);
+                let Some(index) = self.index_of(nx, ny) else {
+                    continue;
+                };
+                if !visited[index] && mode.matches(&seed, &self.buffer[index]) {
+                    visited[index] = true;
+                    pending.push((nx, ny));
+                }
+            }
+        }
+        self.as_fill(|canvas| {
+            for (x, y) in region {
+                canvas.plot(x, y);
+            }
+        });
+    }
+
+    /// Saves the current transform and clip so a later `pop` can restore them.
+    pub fn push(&mut self) {
+        self.transform_stack.push((self.transform, self.clip));
+    }
+
+    /// Restores the state saved by the matching `push`. Unbalanced pops are ignored.
+    pub fn pop(&mut self) {
+        if let Some((transform, clip)) = self.transform_stack.pop() {
+            self.transform = transform;
+            self.clip = clip;
+        }
+    }
+
+    /// Restricts drawing to the rectangle `(x, y, w, h)`, intersected with any
+    /// active clip. The rectangle is mapped through the current transform and
+    /// the clip becomes its axis-aligned bounding box in cells.
+    pub fn clip_rect(&mut self, x: i32, y: i32, w: u16, h: u16) {
+        let corners = [
+            (x, y),
+            (x + w as i32, y),
+            (x, y + h as i32),
+            (x + w as i32, y + h as i32),
+        ]
+        .map(|(cx, cy)| self.transform.apply(Point::new(cx as f32, cy as f32)));
+        let min_x = corners.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+        let min_y = corners.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_x = corners
+            .iter()
+            .map(|p| p.x)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let max_y = corners
+            .iter()
+            .map(|p| p.y)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let rect = ClipRect {
+            x: min_x.round() as i32,
+            y: min_y.round() as i32,
+            width: (max_x - min_x).round() as i32,
+            height: (max_y - min_y).round() as i32,
+        };
+        self.clip = Some(match self.clip {
+            Some(current) => current.intersect(&rect),
+            None => rect,
+        });
+    }
+
+    pub fn reset_clip(&mut self) {
+        self.clip = None;
+    }
+
+    pub fn clip(&self) -> Option<ClipRect> {
+        self.clip
+    }
+
+    pub fn translate(&mut self, x: f32, y: f32) {
+        self.apply_transform(&Transform::translation(x, y));
+    }
+
+    /// Rotates subsequent drawing by `radians` around the current origin.
+    pub fn rotate(&mut self, radians: f32) {
+        self.apply_transform(&Transform::rotation(radians));
+    }
+
+    pub fn scale(&mut self, sx: f32, sy: f32) {
+        self.apply_transform(&Transform::scaling(sx, sy));
+    }
+
+    /// Concatenates `transform` with the current one; it applies to coordinates first.
+    pub fn apply_transform(&mut self, transform: &Transform) {
+        self.transform = self.transform.multiply(transform);
+    }
+
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
+    pub fn reset_transform(&mut self) {
+        self.transform = Transform::IDENTITY;
+    }
+
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    fn to_device(&self, x: f32, y: f32) -> (i32, i32) {
+        let p = self.transform.apply(Point::new(x, y));
+        (p.x.round() as i32, p.y.round() as i32)
+    }
+
+    /// Writes the current symbol into the local cell `(x, y)`, covering its
+    /// whole transformed footprint when the canvas is rotated or scaled.
+    fn stamp(&mut self, x: i32, y: i32) {
+        if self.transform.is_translation() {
+            let (dx, dy) = self.to_device(x as f32, y as f32);
+            self.plot(dx, dy);
+        } else {
+            self.fill_transformed_cells(x as f32, y as f32, 1.0, 1.0);
+        }
+    }
+
+    /// Fills the transformed area of a `w` x `h` block of local cells. Cells are
+    /// sampled at their centres, so the area spans half a cell beyond the
+    /// outermost centres.
+    fn fill_transformed_cells(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        let corners = [
+            Point::new(x - 0.5, y - 0.5),
+            Point::new(x + w - 0.5, y - 0.5),
+            Point::new(x + w - 0.5, y + h - 0.5),
+            Point::new(x - 0.5, y + h - 0.5),
+        ];
+        let ring = corners.map(|p| self.transform.apply(p));
+        self.fill_polygons(&[&ring]);
+    }
+
+    /// Flattens `path` in local space, tightening the tolerance by the transform's
+    /// scale, and maps the result to device space.
+    fn flatten_transformed(&self, path: &Path) -> Vec<Polyline> {
+        let scale = self.transform.max_scale().max(f32::EPSILON);
+        let mut polylines = path.flatten(DEFAULT_TOLERANCE / scale);
+        if !self.transform.is_identity() {
+            for polyline in &mut polylines {
+                for point in &mut polyline.points {
+                    *point = self.transform.apply(*point);
+                }
+            }
+        }
+        polylines
+    }
+
+    /// Strokes a device-space polyline with the current stroke style.
+    fn stroke_device(&mut self, points: &[Point], closed: bool) {
+        let Some(pattern) = self.stroke.dash_pattern() else {
+            self.stroke_pieces(&[(points.to_vec(), closed)]);
+            return;
+        };
+        let scale = self.transform.max_scale();
+        let pattern: Vec<f32> = pattern.iter().map(|d| d * scale).collect();
+        let pieces: Vec<(Vec<Point>, bool)> =
+            dash_polyline(points, closed, &pattern, self.stroke.dash_offset * scale)
+                .into_iter()
+                .map(|dash| (dash, false))
+                .collect();
+        self.stroke_pieces(&pieces);
+    }
+
+    fn stroke_pieces(&mut self, pieces: &[(Vec<Point>, bool)]) {
+        let width = self.stroke.width * self.transform.max_scale();
+        if width <= 1.0 {
+            for (points, closed) in pieces {
+                self.stroke_polyline(points, *closed);
+            }
+            return;
+        }
+
+        let rings: Vec<Vec<Point>> = pieces
+            .iter()
+            .flat_map(|(points, closed)| {
+                stroke_outline(
+                    points,
+                    *closed,
+                    width * 0.5,
+                    self.stroke.cap,
+                    DEFAULT_TOLERANCE,
+                )
+            })
+            .collect();
+        let rings: Vec<&[Point]> = rings.iter().map(Vec::as_slice).collect();
+        self.fill_polygons(&rings);
+    }
+
+    fn stroke_polyline(&mut self, points: &[Point], closed: bool) {
+        match points {
+            [] => {}
+            [p] => self.plot(p.x.round() as i32, p.y.round() as i32),
+            _ => {
+                for segment in points.windows(2) {
+                    self.raster_line_f(segment[0], segment[1]);
+                }
+                if closed && points.len() > 2 {
+                    self.raster_line_f(points[points.len() - 1], points[0]);
+                }
+            }
+        }
+    }
+
+    fn raster_line_f(&mut self, from: Point, to: Point) {
+        self.raster_line(
+            from.x.round() as i32,
+            from.y.round() as i32,
+            to.x.round() as i32,
+            to.y.round() as i32,
+        );
+    }
+
+    /// Bresenham's line between two device-space cells.
+    fn raster_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
+        let mut x0 = x0;
+        let mut y0 = y0;
+        let dx = (x1 - x0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let dy = -(y1 - y0).abs();
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy; // error value e_xy
+
+        loop {
+            self.plot(x0, y0);
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                // e_xy + e_x > 0
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                // e_xy + e_y < 0
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    /// Scanline fill sampling each cell at its centre.
+    fn fill_polygons(&mut self, rings: &[&[Point]]) {
+        let mut edges = Vec::new();
+        let mut min_y = f32::INFINITY;
+        let mut max_y = f32::NEG_INFINITY;
+        for ring in rings {
+            for (i, &a) in ring.iter().enumerate() {
+                let b = ring[(i + 1) % ring.len()];
+                min_y = min_y.min(a.y);
+                max_y = max_y.max(a.y);
+                if a.y != b.y {
+                    edges.push((a, b));
+                }
+            }
+        }
+        if edges.is_empty() {
+            return;
+        }
+
+        let first_row = min_y.ceil().max(0.0) as i32;
+        let last_row = max_y.ceil().min(self.height as f32) as i32;
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        for row in first_row..last_row {
+            let sample_y = row as f32;
+            crossings.clear();
+            for &(a, b) in &edges {
+                let (top, bottom, winding) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+                if sample_y >= top.y && sample_y < bottom.y {
+                    let t = (sample_y - top.y) / (bottom.y - top.y);
+                    crossings.push((top.x + (bottom.x - top.x) * t, winding));
+                }
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                if winding != 0 {
+                    self.fill_span(row, pair[0].0, pair[1].0);
+                }
+            }
+        }
+    }
+
+    /// Plots every cell in `row` whose centre lies in `[x_start, x_end)`.
+    fn fill_span(&mut self, row: i32, x_start: f32, x_end: f32) {
+        let first = x_start.ceil().max(0.0) as i32;
+        let last = x_end.ceil().min(self.width as f32) as i32;
+        for x in first..last {
+            self.plot(x, row);
+        }
+    }
+
+    fn line_art_row(&mut self, x: i32, y: i32, len: i32, style: BorderStyle) {
+        for i in 0..len {
+            let left = i > 0 || len == 1;
+            let right = i < len - 1 || len == 1;
+            self.merge_line_art(x + i, y, Arms::horizontal(style, left, right), style);
+        }
+    }
+
+    fn line_art_column(&mut self, x: i32, y: i32, len: i32, style: BorderStyle) {
+        for i in 0..len {
+            let up = i > 0 || len == 1;
+            let down = i < len - 1 || len == 1;
+            self.merge_line_art(x, y + i, Arms::vertical(style, up, down), style);
+        }
+    }
+
+    fn merge_line_art(&mut self, x: i32, y: i32, arms: Arms, style: BorderStyle) {
+        if let Some(index) = self.index_of(x, y) {
+            let symbol = merge_glyph(self.buffer[index].symbol, arms, style);
+            self.put(x, y, symbol);
+        }
+    }
+
+    /// Writes the current style into a device-space cell, discarding anything off-canvas.
+    fn plot(&mut self, x: i32, y: i32) {
+        if self.filling && !matches!(self.fill, Fill::Solid) {
+            self.plot_fill(x, y);
+        } else {
+            self.put(x, y, self.current_symbol);
+        }
+    }
+
+    /// Runs `draw` with plotted cells painted by the current fill.
+    fn as_fill(&mut self, draw: impl FnOnce(&mut Self)) {
+        self.filling = true;
+        draw(self);
+        self.filling = false;
+    }
+
+    /// Whether the next shape has to track its cells: overlapping rows,
+    /// corners and joints would otherwise composite translucent or blended
+    /// paint (including a translucent gradient) more than once.
+    fn needs_coverage(&self) -> bool {
+        self.covered.is_none()
+            && (!self.is_opaque() || (self.filling && !matches!(self.fill, Fill::Solid)))
+    }
+
+    /// Runs `draw` with every cell painted at most once; later hits on a
+    /// cell it has already painted are dropped.
+    fn once_per_cell(&mut self, draw: impl FnOnce(&mut Self)) {
+        self.covered = Some(vec![false; self.buffer.len()]);
+        draw(self);
+        self.covered = None;
+    }
+
+    /// Plots one cell of a shape fill through the current gradient or pattern.
+    fn plot_fill(&mut self, x: i32, y: i32) {
+        let sample = match &self.fill {
+            Fill::Solid => None,
+            Fill::Gradient(gradient) => {
+                let t = gradient.position(Point::new(x as f32, y as f32), &self.transform);
+                gradient.sample(t)
+            }
+            Fill::Pattern(pattern) => {
+                if pattern.covers(x, y) {
+                    self.put(x, y, self.current_symbol);
+                }
+                return;
+            }
+        };
+        match sample {
+            None => self.put(x, y, self.current_symbol),
+            Some(GradientSample::Glyph(symbol)) => self.put(x, y, symbol),
+            Some(GradientSample::Color(color)) => {
+                let saved = (
+                    self.current_fg,
+                    self.fg_alpha,
+                    self.current_bg,
+                    self.bg_alpha,
+                );
+                if self.current_symbol == ' ' {
+                    self.set_background_rgba(color);
+                } else {
+                    self.set_foreground_rgba(color);
+                }
+                self.put(x, y, self.current_symbol);
+                (
+                    self.current_fg,
+                    self.fg_alpha,
+                    self.current_bg,
+                    self.bg_alpha,
+                ) = saved;
+            }
+        }
+    }
+
+    /// Writes `symbol` in the current colours at a device-space cell, discarding
+    /// anything off-canvas.
+    fn put(&mut self, x: i32, y: i32, symbol: char) {
+        self.put_colored(x, y, symbol, self.current_fg, self.current_bg);
+    }
+
+    fn put_cell(&mut self, x: i32, y: i32, cell: &ImageCell) {
+        let fg = cell.fg.unwrap_or(self.current_fg);
+        let bg = cell.bg.unwrap_or(self.current_bg);
+        self.put_colored(x, y, cell.symbol, fg, bg);
+    }
+
+    fn put_colored(&mut self, x: i32, y: i32, symbol: char, fg: Color, bg: Color) {
+        if let Some(index) = self.index_of(x, y) {
+            if let Some(covered) = &mut self.covered {
+                if std::mem::replace(&mut covered[index], true) {
+                    return;
+                }
+            }
+            if symbol != ' ' || self.is_opaque() {
+                self.release_wide_glyph(x, y);
+            }
+            self.write(index, symbol, fg, bg);
+        }
+    }
+
+    /// Stores a cell with the current alpha and blend mode. Opaque normal
+    /// paint replaces the cell; anything else is composited over it.
+    fn write(&mut self, index: usize, symbol: char, fg: Color, bg: Color) {
+        let cell = Cell {
+            fg_alpha: self.fg_alpha,
+            bg_alpha: self.bg_alpha,
+            blend: self.blend,
+            ..Cell::new(symbol, fg, bg)
+        };
+        self.buffer[index] = if cell.is_opaque() {
+            cell
+        } else {
+            self.buffer[index].composite(&cell)
+        };
+    }
+
+    fn is_opaque(&self) -> bool {
+        self.fg_alpha == 255 && self.bg_alpha == 255 && self.blend == BlendMode::Normal
+    }
+
+    fn index_of(&self, x: i32, y: i32) -> Option<usize> {
+        let on_canvas = x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32;
+        if on_canvas && self.clip.is_none_or(|clip| clip.contains(x, y)) {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Blanks the other half of a double-width glyph covering `(x, y)` so that
+    /// overwriting one half never leaves an orphaned half behind.
+    fn release_wide_glyph(&mut self, x: i32, y: i32) {
+        let Some(index) = self.index_of(x, y) else {
+            return;
+        };
+        if self.buffer[index].is_continuation() {
+            if x > 0 {
+                self.buffer[index - 1].symbol = ' ';
+                self.buffer[index - 1].combining = None;
+            }
+        } else if x + 1 < self.width as i32 && self.buffer[index + 1].is_continuation() {
+            self.buffer[index + 1].symbol = ' ';
+        }
+    }
+
+    pub fn set_stroke_width(&mut self, width: f32) {
+        self.stroke.width = width.max(0.0);
+    }
+
+    /// Sets alternating on/off dash lengths; an empty pattern draws solid strokes.
+    pub fn set_line_dash(&mut self, pattern: &[f32], offset: f32) {
+        self.stroke.dash = pattern.to_vec();
+        self.stroke.dash_offset = offset;
+    }
+
+    pub fn set_dash_offset(&mut self, offset: f32) {
+        self.stroke.dash_offset = offset;
+    }
+
+    pub fn set_line_cap(&mut self, cap: LineCap) {
+        self.stroke.cap = cap;
+    }
+
+    pub fn set_stroke_style(&mut self, style: StrokeStyle) {
+        self.stroke = style;
+    }
+
+    pub fn stroke_style(&self) -> &StrokeStyle {
+        &self.stroke
+    }
+
+    /// Sets how filled shapes and `flood_fill` paint their cells.
+    pub fn set_fill(&mut self, fill: Fill) {
+        self.fill = fill;
+    }
+
+    pub fn reset_fill(&mut self) {
+        self.fill = Fill::Solid;
+    }
+
+    pub fn fill(&self) -> &Fill {
+        &self.fill
+    }
+
     pub fn set_symbol(&mut self, symbol: char) {
         self.current_symbol = symbol;
     }
 
+    /// Runs `draw`, then restores the foreground, symbol and stroke style,
+    /// so helpers can style their own lines without leaking into the caller.
+    pub(crate) fn with_saved_pen<R>(&mut self, draw: impl FnOnce(&mut Self) -> R) -> R {
+        let saved = (
+            self.current_fg,
+            self.fg_alpha,
+            self.current_symbol,
+            self.stroke.clone(),
+        );
+        let result = draw(self);
+        (
+            self.current_fg,
+            self.fg_alpha,
+            self.current_symbol,
+            self.stroke,
+        ) = saved;
+        result
+    }
+
     pub fn set_foreground_color(&mut self, color: Color) {
         self.current_fg = color;
+        self.fg_alpha = 255;
     }
 
     pub fn set_background_color(&mut self, color: Color) {
         self.current_bg = color;
+        self.bg_alpha = 255;
+    }
+
+    /// The current foreground as RGBA, for simulations that fall back to it
+    /// when they have no colour of their own.
+    pub fn foreground_rgba(&self) -> Rgba {
+        Rgba::from_color(self.current_fg).unwrap_or(DEFAULT_FOREGROUND)
+    }
+
+    /// Sets a possibly translucent foreground; glyphs drawn with alpha below
+    /// 255 blend with the glyph or background beneath them.
+    pub fn set_foreground_rgba(&mut self, color: Rgba) {
+        self.current_fg = color.to_color();
+        self.fg_alpha = color.a;
+    }
+
+    /// Sets a possibly translucent background. Blank cells drawn with it
+    /// (e.g. a filled rect of spaces) tint what is beneath instead of hiding it.
+    pub fn set_background_rgba(&mut self, color: Rgba) {
+        self.current_bg = color.to_color();
+        self.bg_alpha = color.a;
+    }
+
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend = mode;
+    }
+
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u16 = 12;
+
+    /// Draws on a blank `SIZE` x `SIZE` canvas with a red background of
+    /// `alpha` and returns the background alpha of every cell, 0 where
+    /// nothing was painted.
+    fn background_alphas(alpha: u8, draw: &impl Fn(&mut Canvas<'_>)) -> Vec<u8> {
+        let mut buffer = vec![Cell::default(); SIZE as usize * SIZE as usize];
+        let mut canvas = Canvas::new(SIZE, SIZE, &mut buffer);
+        canvas.set_background_rgba(Rgba::new(255, 0, 0, alpha));
+        draw(&mut canvas);
+        buffer
+            .iter()
+            .map(|cell| match cell.bg {
+                Color::Reset => 0,
+                _ => cell.bg_alpha,
+            })
+            .collect()
+    }
+
+    /// Checks that translucent paint covers the same cells as opaque paint
+    /// and reaches each of them exactly once.
+    fn assert_painted_once(draw: impl Fn(&mut Canvas<'_>)) {
+        let opaque = background_alphas(255, &draw);
+        let translucent = background_alphas(64, &draw);
+        for (opaque, translucent) in opaque.iter().zip(&translucent) {
+            assert_eq!(
+                *translucent,
+                if *opaque > 0 { 64 } else { 0 },
+                "{translucent:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn translucent_filled_circles_composite_each_cell_once() {
+        assert_painted_once(|canvas| canvas.draw_circle(5, 5, 3, true));
+    }
+
+    #[test]
+    fn translucent_rect_outlines_composite_corners_once() {
+        assert_painted_once(|canvas| canvas.draw_rect(1, 1, 5, 4, false));
+    }
+
+    #[test]
+    fn translucent_polylines_composite_joints_once() {
+        let path = Path::new()
+            .move_to(1.0, 1.0)
+            .line_to(6.0, 1.0)
+            .line_to(6.0, 5.0)
+            .close();
+        assert_painted_once(|canvas| canvas.stroke_path(&path));
+    }
+
+    #[test]
+    fn glyphs_keep_only_their_first_combining_mark() {
+        let mut buffer = vec![Cell::default(); 4];
+        Canvas::new(4, 1, &mut buffer).draw_text(0, 0, "e\u{301}\u{302}x");
+        assert_eq!(buffer[0].symbol, 'e');
+        assert_eq!(buffer[0].combining, Some('\u{301}'));
+        assert_eq!(buffer[1].symbol, 'x');
+    }
+
+    #[test]
+    fn wide_sprite_glyphs_keep_their_continuation_cell() {
+        let sprite = Sprite::parse("---\n日x\n").unwrap();
+        let symbols = |flip| {
+            let mut buffer = vec![Cell::default(); 3];
+            Canvas::new(3, 1, &mut buffer).draw_sprite(&sprite, 0, 0, 0, flip);
+            buffer.iter().map(|cell| cell.symbol).collect::<String>()
+        };
+        assert_eq!(symbols(Flip::None), "日\0x");
+        assert_eq!(symbols(Flip::Horizontal), "x日\0");
+    }
+
+    #[test]
+    fn boxes_near_the_coordinate_limit_do_not_overflow() {
+        let mut buffer = vec![Cell::default(); 16];
+        let mut canvas = Canvas::new(4, 4, &mut buffer);
+        canvas.translate(-(u16::MAX as f32) + 2.0, 0.0);
+        canvas.draw_text_box(u16::MAX - 2, u16::MAX - 1, 4, 3, "a b c", TextAlign::Right);
+        canvas.draw_titled_box(u16::MAX - 1, 0, 3, 2, BorderStyle::Light, "t");
+
+        let mut buffer = vec![Cell::default(); 16];
+        let mut canvas = Canvas::new(4, 4, &mut buffer);
+        canvas.translate(0.0, -(u16::MAX as f32));
+        canvas.draw_text_box(0, u16::MAX - 1, 4, 3, "ab cd ef", TextAlign::Left);
+        // The first line lands above the canvas and the third is out of range.
+        assert_eq!(buffer[0].symbol, 'c');
+        assert_eq!(buffer[4].symbol, ' ');
+    }
+
+    /// Flood fills a `width` x `height` canvas holding `cells` from the top
+    /// left corner and returns how many cells were filled.
+    fn flood(cells: Vec<Cell>, width: u16, mode: FloodMode, connectivity: Connectivity) -> usize {
+        let height = (cells.len() / width as usize) as u16;
+        let mut buffer = cells;
+        let mut canvas = Canvas::new(width, height, &mut buffer);
+        canvas.set_symbol('o');
+        canvas.flood_fill(0, 0, mode, connectivity);
+        buffer.iter().filter(|cell| cell.symbol == 'o').count()
+    }
+
+    #[test]
+    fn flood_fills_only_leak_through_diagonal_gaps_with_eight_neighbours() {
+        let wall = Cell::new('#', Color::Reset, Color::Reset);
+        let mut cells = vec![Cell::default(); 9];
+        cells[1] = wall;
+        cells[3] = wall;
+        assert_eq!(
+            flood(cells.clone(), 3, FloodMode::Symbol, Connectivity::Four),
+            1
+        );
+        assert_eq!(flood(cells, 3, FloodMode::Symbol, Connectivity::Eight), 7);
+    }
+
+    #[test]
+    fn flood_modes_choose_what_counts_as_the_same_region() {
+        let red = Color::Rgb { r: 255, g: 0, b: 0 };
+        let cells = vec![
+            Cell::new(' ', Color::Reset, red),
+            Cell::new(' ', Color::Green, red),
+            Cell::new('x', Color::Reset, red),
+            Cell::new(' ', Color::Reset, Color::Blue),
+        ];
+        let fill = |mode| flood(cells.clone(), 4, mode, Connectivity::Four);
+        assert_eq!(fill(FloodMode::Symbol), 2);
+        assert_eq!(fill(FloodMode::Color), 3);
+        assert_eq!(fill(FloodMode::Cell), 1);
+    }
+
+    #[test]
+    fn later_shapes_still_composite_over_earlier_ones() {
+        let alphas = background_alphas(64, &|canvas: &mut Canvas<'_>| {
+            canvas.draw_rect(1, 1, 2, 1, true);
+            canvas.draw_rect(2, 1, 2, 1, true);
+        });
+        let row = SIZE as usize;
+        assert_eq!(&alphas[row + 1..row + 4], &[64, 112, 64]);
     }
 }
@@ -0,0 +1,572 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Deserialize;
+
+use crate::color::{BlendMode, Rgba, DEFAULT_BACKGROUND, DEFAULT_FOREGROUND};
+use crate::render::Cell;
+use crate::text::char_width;
+
+/// Brightest channel below which a fading trail cell is dropped.
+const TRAIL_CUTOFF: u8 = 8;
+
+/// A post-processing filter applied to the composed frame, configured per
+/// scene as `[[scenes.effects]]` tables with a `type` key:
+///
+/// ```toml
+/// [[scenes.effects]]
+/// type = "trails"
+/// half_life = 0.3
+///
+/// [[scenes.effects]]
+/// type = "scanlines"
+/// dim = 0.4
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Effect {
+    /// Keeps fading copies of earlier frames wherever the new frame is empty.
+    Trails {
+        /// Seconds for a trail to lose half its brightness.
+        #[serde(default = "default_half_life")]
+        half_life: f32,
+    },
+    /// Box-blurs the light emitted by each cell (glyph colour, or background
+    /// for blank cells) and screens it into the backgrounds, giving a glow.
+    Blur {
+        #[serde(default = "default_blur_radius")]
+        radius: usize,
+        /// How much of the blurred light is added, 0–1.
+        #[serde(default = "default_strength")]
+        strength: f32,
+    },
+    /// Darkens every `spacing`-th row like a CRT.
+    Scanlines {
+        /// Fraction of brightness removed from the dimmed rows, 0–1.
+        #[serde(default = "default_scanline_dim")]
+        dim: f32,
+        #[serde(default = "default_scanline_spacing")]
+        spacing: usize,
+    },
+    /// Shifts random rows sideways (wrapping around) each frame.
+    Glitch {
+        /// Largest shift in cells.
+        #[serde(default = "default_glitch_shift")]
+        max_shift: usize,
+        /// Chance, per row and frame, that the row is shifted.
+        #[serde(default = "default_glitch_rate")]
+        rate: f32,
+    },
+    /// Rotates the hue of every colour over time.
+    ColorCycle {
+        /// Full turns around the colour wheel per second.
+        #[serde(default = "default_cycle_speed")]
+        speed: f32,
+    },
+    /// Samples the red channel `offset` cells to the left and the blue channel
+    /// `offset` cells to the right, fringing edges like a misaligned lens.
+    ChromaticOffset {
+        #[serde(default = "default_chromatic_offset")]
+        offset: usize,
+    },
+}
+
+fn default_half_life() -> f32 {
+    0.25
+}
+
+fn default_blur_radius() -> usize {
+    1
+}
+
+fn default_strength() -> f32 {
+    0.5
+}
+
+fn default_scanline_dim() -> f32 {
+    0.35
+}
+
+fn default_scanline_spacing() -> usize {
+    2
+}
+
+fn default_glitch_shift() -> usize {
+    4
+}
+
+fn default_glitch_rate() -> f32 {
+    0.05
+}
+
+fn default_cycle_speed() -> f32 {
+    0.1
+}
+
+fn default_chromatic_offset() -> usize {
+    1
+}
+
+/// An ordered list of effects plus the state they carry between frames.
+pub struct EffectChain {
+    effects: Vec<Effect>,
+    previous: Vec<Cell>,
+    rng: StdRng,
+}
+
+impl EffectChain {
+    /// A chain whose random effects (glitch) are seeded with 0; use
+    /// `with_seed` to tie them to the run's seed.
+    pub fn new(effects: Vec<Effect>) -> Self {
+        Self {
+            effects,
+            previous: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            ..self
+        }
+    }
+
+    pub fn effects(&self) -> &[Effect] {
+        &self.effects
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Forgets history such as trails, e.g. when the scene changes.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+
+    /// Runs every effect in order over a composed, fully resolved frame.
+    pub fn apply(
+        &mut self,
+        cells: &mut [Cell],
+        width: u16,
+        height: u16,
+        total_time: f32,
+        delta_time: f32,
+    ) {
+        let (width, height) = (width as usize, height as usize);
+        if cells.len() != width * height {
+            return;
+        }
+        for effect in &self.effects {
+            match *effect {
+                Effect::Trails { half_life } => {
+                    trails(cells, &mut self.previous, half_life, delta_time)
+                }
+                Effect::Blur { radius, strength } => blur(cells, width, height, radius, strength),
+                Effect::Scanlines { dim, spacing } => scanlines(cells, width, dim, spacing),
+                Effect::Glitch { max_shift, rate } => {
+                    glitch(cells, width, max_shift, rate, &mut self.rng)
+                }
+                Effect::ColorCycle { speed } => {
+                    let degrees = (total_time * speed).fract() * 360.0;
+                    for cell in cells.iter_mut() {
+                        map_colors(cell, |color| rotate_hue(color, degrees));
+                    }
+                }
+                Effect::ChromaticOffset { offset } => chromatic_offset(cells, width, offset),
+            }
+        }
+    }
+}
+
+/// Applies `f` to the cell's background and, when it shows a glyph, its
+/// foreground. `Color::Reset` backgrounds are left alone.
+fn map_colors(cell: &mut Cell, f: impl Fn(Rgba) -> Rgba) {
+    if cell.has_glyph() {
+        let fg = Rgba::from_color(cell.fg).unwrap_or(DEFAULT_FOREGROUND);
+        cell.fg = f(fg).to_color();
+    }
+    if let Some(bg) = Rgba::from_color(cell.bg) {
+        cell.bg = f(bg).to_color();
+    }
+}
+
+fn scale(color: Rgba, factor: f32) -> Rgba {
+    let channel = |c: u8| (c as f32 * factor).round().clamp(0.0, 255.0) as u8;
+    Rgba::rgb(channel(color.r), channel(color.g), channel(color.b))
+}
+
+/// The colour a cell shows most of: its glyph colour, else its background.
+fn emitted(cell: &Cell) -> Rgba {
+    if cell.has_glyph() {
+        Rgba::from_color(cell.fg).unwrap_or(DEFAULT_FOREGROUND)
+    } else {
+        Rgba::from_color(cell.bg).unwrap_or(DEFAULT_BACKGROUND)
+    }
+}
+
+fn trails(cells: &mut [Cell], previous: &mut Vec<Cell>, half_life: f32, delta_time: f32) {
+    if previous.len() == cells.len() {
+        let factor = 0.5f32.powf(delta_time / half_life.max(f32::EPSILON));
+        for (cell, old) in cells.iter_mut().zip(previous.iter()) {
+            if *cell != Cell::default() || *old == Cell::default() {
+                continue;
+            }
+            let mut faded = *old;
+            map_colors(&mut faded, |color| scale(color, factor));
+            let brightest = |color| Rgba::from_color(color).map_or(0, |c| c.r.max(c.g).max(c.b));
+            let visible = if faded.has_glyph() {
+                brightest(faded.fg) >= TRAIL_CUTOFF
+            } else {
+                brightest(faded.bg) >= TRAIL_CUTOFF
+            };
+            if visible {
+                *cell = faded;
+            }
+        }
+    }
+    previous.clear();
+    previous.extend_from_slice(cells);
+}
+
+/// Averages `values` over a window of `radius` on each side along `lines`
+/// runs of `len` samples. A run starts at `line * line_stride` and advances
+/// by `step`, so rows and columns use the same pass.
+fn box_pass(
+    values: &[[f32; 3]],
+    (len, lines): (usize, usize),
+    (line_stride, step): (usize, usize),
+    radius: usize,
+) -> Vec<[f32; 3]> {
+    let mut out = vec![[0.0; 3]; values.len()];
+    for line in 0..lines {
+        let base = line * line_stride;
+        for i in 0..len {
+            let start = i.saturating_sub(radius);
+            let end = (i + radius).min(len - 1);
+            let mut sum = [0.0; 3];
+            for j in start..=end {
+                for (total, value) in sum.iter_mut().zip(values[base + j * step]) {
+                    *total += value;
+                }
+            }
+            let count = (end - start + 1) as f32;
+            out[base + i * step] = sum.map(|s| s / count);
+        }
+    }
+    out
+}
+
+fn blur(cells: &mut [Cell], width: usize, height: usize, radius: usize, strength: f32) {
+    if radius == 0 || width == 0 || height == 0 {
+        return;
+    }
+    let light: Vec<[f32; 3]> = cells
+        .iter()
+        .map(|cell| {
+            let color = emitted(cell);
+            [color.r as f32, color.g as f32, color.b as f32]
+        })
+        .collect();
+    let horizontal = box_pass(&light, (width, height), (width, 1), radius);
+    let blurred = box_pass(&horizontal, (height, width), (1, width), radius);
+    let strength = strength.clamp(0.0, 1.0);
+    for (cell, glow) in cells.iter_mut().zip(blurred) {
+        let glow = Rgba::new(
+            glow[0] as u8,
+            glow[1] as u8,
+            glow[2] as u8,
+            (strength * 255.0) as u8,
+        );
+        let bg = Rgba::from_color(cell.bg).unwrap_or(DEFAULT_BACKGROUND);
+        let result = BlendMode::Screen.composite(bg, glow);
+        if result != bg {
+            cell.bg = result.to_color();
+        }
+    }
+}
+
+fn scanlines(cells: &mut [Cell], width: usize, dim: f32, spacing: usize) {
+    let spacing = spacing.max(1);
+    let factor = 1.0 - dim.clamp(0.0, 1.0);
+    for (row, line) in cells.chunks_mut(width).enumerate() {
+        if row % spacing == spacing - 1 {
+            for cell in line {
+                map_colors(cell, |color| scale(color, factor));
+            }
+        }
+    }
+}
+
+fn glitch(cells: &mut [Cell], width: usize, max_shift: usize, rate: f32, rng: &mut StdRng) {
+    if max_shift == 0 || width == 0 {
+        return;
+    }
+    for line in cells.chunks_mut(width) {
+        if rng.gen::<f32>() >= rate {
+            continue;
+        }
+        let shift = rng.gen_range(1..=max_shift.min(width));
+        if rng.gen() {
+            line.rotate_right(shift % width);
+        } else {
+            line.rotate_left(shift % width);
+        }
+        repair_wide_glyphs(line);
+    }
+}
+
+/// Blanks halves of double-width glyphs that a row shift separated.
+fn repair_wide_glyphs(line: &mut [Cell]) {
+    let is_wide = |cell: &Cell| char_width(cell.symbol) == 2;
+    for i in 0..line.len() {
+        let followed = line.get(i + 1).is_some_and(Cell::is_continuation);
+        let preceded = i > 0 && is_wide(&line[i - 1]);
+        if (is_wide(&line[i]) && !followed) || (line[i].is_continuation() && !preceded) {
+            line[i].symbol = ' ';
+        }
+    }
+}
+
+fn chromatic_offset(cells: &mut [Cell], width: usize, offset: usize) {
+    if offset == 0 || width == 0 {
+        return;
+    }
+    let source = cells.to_vec();
+    for (row, line) in cells.chunks_mut(width).enumerate() {
+        for (x, cell) in line.iter_mut().enumerate() {
+            let left = &source[row * width + x.saturating_sub(offset)];
+            let right = &source[row * width + (x + offset).min(width - 1)];
+            let (left, right) = (emitted(left), emitted(right));
+            map_colors(cell, |color| Rgba::rgb(left.r, color.g, right.b));
+        }
+    }
+}
+
+fn rotate_hue(color: Rgba, degrees: f32) -> Rgba {
+    let (r, g, b) = (
+        color.r as f32 / 255.0,
+        color.g as f32 / 255.0,
+        color.b as f32 / 255.0,
+    );
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    if chroma <= f32::EPSILON {
+        return color;
+    }
+    let hue = if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let hue = (hue + degrees).rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let to_byte = |v: f32| ((v + min) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgba::rgb(to_byte(r), to_byte(g), to_byte(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::Color;
+
+    use super::*;
+
+    const WHITE: Color = Color::Rgb {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+
+    fn gray(level: u8) -> Color {
+        Color::Rgb {
+            r: level,
+            g: level,
+            b: level,
+        }
+    }
+
+    fn run(effect: Effect, cells: &mut [Cell], width: u16, time: f32, delta: f32) {
+        let height = (cells.len() / width as usize) as u16;
+        EffectChain::new(vec![effect]).apply(cells, width, height, time, delta);
+    }
+
+    #[test]
+    fn effects_deserialize_by_type_with_defaults() {
+        #[derive(Deserialize)]
+        struct Scene {
+            effects: Vec<Effect>,
+        }
+        let scene: Scene = toml::from_str(
+            r#"
+            [[effects]]
+            type = "trails"
+            half_life = 0.3
+
+            [[effects]]
+            type = "scanlines"
+
+            [[effects]]
+            type = "chromatic_offset"
+            offset = 2
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            scene.effects,
+            vec![
+                Effect::Trails { half_life: 0.3 },
+                Effect::Scanlines {
+                    dim: 0.35,
+                    spacing: 2
+                },
+                Effect::ChromaticOffset { offset: 2 },
+            ]
+        );
+        assert!(toml::from_str::<Scene>("[[effects]]\ntype = \"noise\"").is_err());
+    }
+
+    #[test]
+    fn trails_fade_by_half_life_and_drop_when_dark() {
+        let mut chain = EffectChain::new(vec![Effect::Trails { half_life: 0.5 }]);
+        let mut frame = vec![Cell::new('@', WHITE, Color::Reset), Cell::default()];
+        chain.apply(&mut frame, 2, 1, 0.0, 0.5);
+
+        let mut frame = vec![Cell::default(), Cell::new('#', WHITE, Color::Reset)];
+        chain.apply(&mut frame, 2, 1, 0.5, 0.5);
+        assert_eq!(frame[0].symbol, '@');
+        assert_eq!(frame[0].fg, gray(128));
+        // New content is never covered by a trail.
+        assert_eq!(frame[1].symbol, '#');
+
+        // Five more half lives fade it below the cutoff.
+        let mut frame = vec![Cell::default(); 2];
+        chain.apply(&mut frame, 2, 1, 3.0, 2.5);
+        assert_eq!(frame[0], Cell::default());
+
+        chain.reset();
+        let mut frame = vec![Cell::default(); 2];
+        chain.apply(&mut frame, 2, 1, 3.5, 0.5);
+        assert_eq!(frame[1], Cell::default());
+    }
+
+    #[test]
+    fn scanlines_dim_every_nth_row() {
+        let mut cells = vec![Cell::new(' ', Color::Reset, gray(200)); 3];
+        cells.push(Cell::default());
+        let effect = Effect::Scanlines {
+            dim: 0.5,
+            spacing: 2,
+        };
+        run(effect, &mut cells, 2, 0.0, 0.0);
+        assert_eq!(cells[0].bg, gray(200));
+        assert_eq!(cells[1].bg, gray(200));
+        assert_eq!(cells[2].bg, gray(100));
+        // Default backgrounds have no colour to dim.
+        assert_eq!(cells[3], Cell::default());
+    }
+
+    #[test]
+    fn glitch_shifts_whole_rows() {
+        let row: Vec<Cell> = "abcd"
+            .chars()
+            .map(|c| Cell::new(c, Color::Reset, Color::Reset))
+            .collect();
+        let mut cells = row.clone();
+        let effect = Effect::Glitch {
+            max_shift: 1,
+            rate: 1.0,
+        };
+        run(effect, &mut cells, 4, 0.0, 0.0);
+        let text: String = cells.iter().map(|cell| cell.symbol).collect();
+        assert!(text == "dabc" || text == "bcda", "{text}");
+    }
+
+    #[test]
+    fn shifted_rows_never_split_wide_glyphs() {
+        let mut line = vec![
+            Cell::new(Cell::CONTINUATION, Color::Reset, Color::Reset),
+            Cell::new('a', Color::Reset, Color::Reset),
+            Cell::new('日', Color::Reset, Color::Reset),
+        ];
+        repair_wide_glyphs(&mut line);
+        let text: String = line.iter().map(|cell| cell.symbol).collect();
+        assert_eq!(text, " a ");
+    }
+
+    #[test]
+    fn color_cycle_rotates_hues() {
+        let red = Color::Rgb { r: 255, g: 0, b: 0 };
+        let mut cells = vec![Cell::new(' ', Color::Reset, red)];
+        // A third of a turn takes red to green.
+        run(
+            Effect::ColorCycle { speed: 1.0 },
+            &mut cells,
+            1,
+            1.0 / 3.0,
+            0.0,
+        );
+        assert_eq!(cells[0].bg, Color::Rgb { r: 0, g: 255, b: 0 });
+        assert_eq!(
+            rotate_hue(Rgba::rgb(90, 90, 90), 45.0),
+            Rgba::rgb(90, 90, 90)
+        );
+    }
+
+    #[test]
+    fn chromatic_offset_takes_red_from_the_left_and_blue_from_the_right() {
+        let mut cells = vec![
+            Cell::new(' ', Color::Reset, Color::Rgb { r: 200, g: 0, b: 0 }),
+            Cell::new(' ', Color::Reset, gray(50)),
+            Cell::new(' ', Color::Reset, Color::Rgb { r: 0, g: 0, b: 200 }),
+        ];
+        run(
+            Effect::ChromaticOffset { offset: 1 },
+            &mut cells,
+            3,
+            0.0,
+            0.0,
+        );
+        assert_eq!(
+            cells[1].bg,
+            Color::Rgb {
+                r: 200,
+                g: 50,
+                b: 200
+            }
+        );
+    }
+
+    #[test]
+    fn blur_spreads_light_into_neighbouring_backgrounds() {
+        let mut cells = vec![Cell::default(); 3];
+        cells[1] = Cell::new('*', WHITE, Color::Reset);
+        let effect = Effect::Blur {
+            radius: 1,
+            strength: 1.0,
+        };
+        run(effect, &mut cells, 3, 0.0, 0.0);
+        assert_ne!(cells[0].bg, Color::Reset);
+        assert_eq!(cells[0].bg, cells[2].bg);
+    }
+
+    #[test]
+    fn frames_of_the_wrong_size_are_left_alone() {
+        let mut cells = vec![Cell::new(' ', Color::Reset, gray(200)); 3];
+        let effect = Effect::Scanlines {
+            dim: 1.0,
+            spacing: 1,
+        };
+        EffectChain::new(vec![effect]).apply(&mut cells, 2, 2, 0.0, 0.0);
+        assert!(cells.iter().all(|cell| cell.bg == gray(200)));
+    }
+}
@@ -0,0 +1,503 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+
+/// Names accepted by `FigletFont::bundled`.
+pub const BUNDLED_FONTS: &[&str] = &["block", "mini"];
+
+const BLOCK_FONT: &str = include_str!("../assets/fonts/block.flf");
+const MINI_FONT: &str = include_str!("../assets/fonts/mini.flf");
+
+/// The German letters every FIGlet font defines after printable ASCII.
+const GERMAN_CODES: [u32; 7] = [196, 214, 220, 228, 246, 252, 223];
+
+const SMUSH_EQUAL: u32 = 1;
+const SMUSH_LOWLINE: u32 = 2;
+const SMUSH_HIERARCHY: u32 = 4;
+const SMUSH_PAIR: u32 = 8;
+const SMUSH_BIG_X: u32 = 16;
+const SMUSH_HARDBLANK: u32 = 32;
+const LAYOUT_KERNING: u32 = 64;
+const LAYOUT_SMUSHING: u32 = 128;
+
+/// How adjacent glyphs are joined horizontally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FigletLayout {
+    /// Every glyph keeps its full designed width.
+    FullWidth,
+    /// Glyphs slide together until they touch ("fitting").
+    Kerning,
+    /// Glyphs overlap by one more column where the touching characters can be
+    /// merged. `rules` holds the FIGlet controlled smushing bits; zero means
+    /// universal smushing.
+    Smushing { rules: u32 },
+}
+
+/// A FIGlet `.flf` font used by `Canvas::draw_banner`.
+#[derive(Debug, Clone)]
+pub struct FigletFont {
+    hardblank: char,
+    height: usize,
+    layout: FigletLayout,
+    glyphs: HashMap<char, Vec<Vec<char>>>,
+}
+
+/// One cell of rendered banner text, remembering which input glyph produced it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BannerCell {
+    pub symbol: char,
+    pub glyph: usize,
+}
+
+impl FigletFont {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read FIGlet font at {}", path.display()))?;
+        Self::parse(&raw)
+            .with_context(|| format!("Failed to parse FIGlet font at {}", path.display()))
+    }
+
+    /// Returns one of the fonts compiled into the engine (see `BUNDLED_FONTS`).
+    pub fn bundled(name: &str) -> Option<Self> {
+        let source = match name {
+            "block" => BLOCK_FONT,
+            "mini" => MINI_FONT,
+            _ => return None,
+        };
+        Self::parse(source).ok()
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut lines = source.lines();
+        let header = lines.next().ok_or_else(|| anyhow!("Font file is empty"))?;
+        let signature = header
+            .strip_prefix("flf2a")
+            .ok_or_else(|| anyhow!("Missing 'flf2a' signature"))?;
+        let hardblank = signature
+            .chars()
+            .next()
+            .ok_or_else(|| anyhow!("Missing hardblank character"))?;
+        let fields: Vec<i64> = signature[hardblank.len_utf8()..]
+            .split_whitespace()
+            .map(|field| field.parse::<i64>())
+            .collect::<Result<_, _>>()
+            .context("Malformed header")?;
+        if fields.len() < 5 {
+            bail!("Header has {} fields, expected at least 5", fields.len());
+        }
+
+        let height = usize::try_from(fields[0])
+            .ok()
+            .filter(|h| *h > 0)
+            .ok_or_else(|| anyhow!("Invalid glyph height {}", fields[0]))?;
+        let old_layout = fields[3];
+        let comment_lines = fields[4].max(0) as usize;
+        let full_layout = fields.get(6).copied();
+        let layout = layout_from_header(old_layout, full_layout);
+
+        let mut lines = lines.skip(comment_lines).peekable();
+        let mut glyphs = HashMap::new();
+
+        for code in (32..=126).chain(GERMAN_CODES) {
+            if lines.peek().is_none() {
+                // Many fonts in the wild omit the German characters.
+                if code > 126 {
+                    break;
+                }
+                bail!("Font ends before character {code}");
+            }
+            let glyph = read_glyph(&mut lines, height)
+                .with_context(|| format!("Truncated glyph for character {code}"))?;
+            if let Some(c) = char::from_u32(code) {
+                glyphs.insert(c, glyph);
+            }
+        }
+
+        // Code-tagged characters: a line starting with the code, then the glyph.
+        while let Some(tag) = lines.next() {
+            let Some(code) = tag.split_whitespace().next().and_then(parse_code) else {
+                continue;
+            };
+            let glyph = read_glyph(&mut lines, height)
+                .with_context(|| format!("Truncated glyph for character {code}"))?;
+            if let Some(c) = u32::try_from(code).ok().and_then(char::from_u32) {
+                glyphs.insert(c, glyph);
+            }
+        }
+
+        Ok(Self {
+            hardblank,
+            height,
+            layout,
+            glyphs,
+        })
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn layout(&self) -> FigletLayout {
+        self.layout
+    }
+
+    /// Overrides the layout declared in the font header.
+    pub fn set_layout(&mut self, layout: FigletLayout) {
+        self.layout = layout;
+    }
+
+    /// Renders `text` into banner rows. Each `\n` in the input starts a new
+    /// banner line below the previous one.
+    pub fn render(&self, text: &str) -> Vec<String> {
+        self.layout_cells(text)
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.symbol).collect())
+            .collect()
+    }
+
+    /// Width in cells of the widest row `render` would produce.
+    pub fn measure(&self, text: &str) -> usize {
+        self.layout_cells(text)
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn layout_cells(&self, text: &str) -> Vec<Vec<BannerCell>> {
+        let mut rows = Vec::new();
+        let mut glyph_index = 0;
+        for line in text.split('\n') {
+            let mut output: Vec<Vec<BannerCell>> = vec![Vec::new(); self.height];
+            let mut previous_width = 0;
+            for c in line.chars() {
+                let Some(glyph) = self.glyphs.get(&c) else {
+                    continue;
+                };
+                let width = glyph.iter().map(Vec::len).max().unwrap_or(0);
+                self.append_glyph(&mut output, glyph, glyph_index, previous_width, width);
+                previous_width = width;
+                glyph_index += 1;
+            }
+            for row in &mut output {
+                for cell in row.iter_mut() {
+                    if cell.symbol == self.hardblank {
+                        cell.symbol = ' ';
+                    }
+                }
+            }
+            rows.extend(output);
+        }
+        rows
+    }
+
+    fn append_glyph(
+        &self,
+        output: &mut [Vec<BannerCell>],
+        glyph: &[Vec<char>],
+        glyph_index: usize,
+        previous_width: usize,
+        width: usize,
+    ) {
+        let overlap = self.overlap(output, glyph, previous_width, width);
+        for (row, glyph_row) in output.iter_mut().zip(glyph) {
+            for k in 0..width {
+                let right = glyph_row.get(k).copied().unwrap_or(' ');
+                if k >= overlap {
+                    row.push(BannerCell {
+                        symbol: right,
+                        glyph: glyph_index,
+                    });
+                    continue;
+                }
+                // Leading blank columns of the very first glyph fall off the left edge.
+                let Some(position) = (row.len() + k).checked_sub(overlap) else {
+                    continue;
+                };
+                let cell = &mut row[position];
+                let merged = self
+                    .smush(cell.symbol, right, previous_width, width)
+                    .unwrap_or(right);
+                if merged != cell.symbol {
+                    *cell = BannerCell {
+                        symbol: merged,
+                        glyph: glyph_index,
+                    };
+                }
+            }
+        }
+    }
+
+    /// Number of columns the next glyph may slide left over the output.
+    fn overlap(
+        &self,
+        output: &[Vec<BannerCell>],
+        glyph: &[Vec<char>],
+        previous_width: usize,
+        width: usize,
+    ) -> usize {
+        if self.layout == FigletLayout::FullWidth {
+            return 0;
+        }
+
+        let mut max_overlap = width;
+        for (row, glyph_row) in output.iter().zip(glyph) {
+            let trailing = row.iter().rev().take_while(|c| c.symbol == ' ').count();
+            let last = row.iter().rev().find(|c| c.symbol != ' ').map(|c| c.symbol);
+            let leading = glyph_row.iter().take_while(|&&c| c == ' ').count();
+            let first = glyph_row.get(leading).copied();
+
+            let mut amount = trailing + leading;
+            if let (Some(left), Some(right)) = (last, first) {
+                if self.smush(left, right, previous_width, width).is_some() {
+                    amount += 1;
+                }
+            }
+            max_overlap = max_overlap.min(amount);
+        }
+        max_overlap
+    }
+
+    /// Merges two overlapping characters, or `None` when they cannot share a cell.
+    fn smush(&self, left: char, right: char, previous_width: usize, width: usize) -> Option<char> {
+        if left == ' ' {
+            return Some(right);
+        }
+        if right == ' ' {
+            return Some(left);
+        }
+        let FigletLayout::Smushing { rules } = self.layout else {
+            return None;
+        };
+        if previous_width < 2 || width < 2 {
+            return None;
+        }
+
+        let hardblank = self.hardblank;
+        if rules == 0 {
+            // Universal smushing: the later glyph wins, except over hardblanks.
+            if left == hardblank {
+                return Some(right);
+            }
+            return Some(if right == hardblank { left } else { right });
+        }
+
+        if rules & SMUSH_HARDBLANK != 0 && left == hardblank && right == hardblank {
+            return Some(left);
+        }
+        if left == hardblank || right == hardblank {
+            return None;
+        }
+        if rules & SMUSH_EQUAL != 0 && left == right {
+            return Some(left);
+        }
+        if rules & SMUSH_LOWLINE != 0 {
+            const REPLACERS: &str = "|/\\[]{}()<>";
+            if left == '_' && REPLACERS.contains(right) {
+                return Some(right);
+            }
+            if right == '_' && REPLACERS.contains(left) {
+                return Some(left);
+            }
+        }
+        if rules & SMUSH_HIERARCHY != 0 {
+            const CLASSES: [&str; 6] = ["|", "/\\", "[]", "{}", "()", "<>"];
+            let class_of = |c: char| CLASSES.iter().position(|class| class.contains(c));
+            if let (Some(l), Some(r)) = (class_of(left), class_of(right)) {
+                if l != r {
+                    return Some(if l > r { left } else { right });
+                }
+            }
+        }
+        if rules & SMUSH_PAIR != 0 {
+            if let ('[', ']') | (']', '[') | ('{', '}') | ('}', '{') | ('(', ')') | (')', '(') =
+                (left, right)
+            {
+                return Some('|');
+            }
+        }
+        if rules & SMUSH_BIG_X != 0 {
+            match (left, right) {
+                ('/', '\\') => return Some('|'),
+                ('\\', '/') => return Some('Y'),
+                ('>', '<') => return Some('X'),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+fn layout_from_header(old_layout: i64, full_layout: Option<i64>) -> FigletLayout {
+    match full_layout {
+        Some(full) if full >= 0 => {
+            let full = full as u32;
+            if full & LAYOUT_SMUSHING != 0 {
+                FigletLayout::Smushing { rules: full & 63 }
+            } else if full & LAYOUT_KERNING != 0 {
+                FigletLayout::Kerning
+            } else {
+                FigletLayout::FullWidth
+            }
+        }
+        _ => match old_layout {
+            i64::MIN..=-1 => FigletLayout::FullWidth,
+            0 => FigletLayout::Kerning,
+            rules => FigletLayout::Smushing {
+                rules: rules as u32 & 63,
+            },
+        },
+    }
+}
+
+/// Reads `height` lines of glyph art, stripping the trailing endmark characters.
+fn read_glyph<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    height: usize,
+) -> Result<Vec<Vec<char>>> {
+    (0..height)
+        .map(|_| {
+            let line = lines
+                .next()
+                .ok_or_else(|| anyhow!("Unexpected end of font"))?;
+            let line = line.trim_end();
+            let Some(endmark) = line.chars().last() else {
+                return Ok(Vec::new());
+            };
+            Ok(line.trim_end_matches(endmark).chars().collect())
+        })
+        .collect()
+}
+
+/// Parses code tags, which may be decimal, `0x` hexadecimal or `0` octal.
+fn parse_code(tag: &str) -> Option<i64> {
+    let (negative, digits) = match tag.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, tag),
+    };
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-row font with the given old layout, drawing each character as
+    /// `glyph(c)`.
+    fn font(old_layout: i64, glyph: impl Fn(char) -> String) -> String {
+        let mut source = format!("flf2a$ 1 1 8 {old_layout} 1\ntest font\n");
+        for c in (32..=126).chain(GERMAN_CODES).filter_map(char::from_u32) {
+            let art = glyph(c);
+            let endmark = if art.contains('@') { '#' } else { '@' };
+            source.push_str(&format!("{art}{endmark}\n"));
+        }
+        source
+    }
+
+    fn parse(old_layout: i64, glyph: impl Fn(char) -> String) -> FigletFont {
+        FigletFont::parse(&font(old_layout, glyph)).unwrap()
+    }
+
+    #[test]
+    fn bundled_fonts_parse() {
+        for name in BUNDLED_FONTS {
+            let font = FigletFont::bundled(name).unwrap();
+            assert!(font.height() > 0);
+            assert!(font.measure("Hi") > 0);
+        }
+        assert!(FigletFont::bundled("missing").is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        for source in ["", "flf3a$ 1 1 8 0 0", "flf2a$ 1 1 8", "flf2a$ 0 1 8 0 0"] {
+            assert!(FigletFont::parse(source).is_err(), "{source:?}");
+        }
+        assert!(FigletFont::parse("flf2a$ 1 1 8 0 0\nA@\n").is_err());
+    }
+
+    #[test]
+    fn reads_layouts_from_the_header() {
+        assert_eq!(layout_from_header(-1, None), FigletLayout::FullWidth);
+        assert_eq!(layout_from_header(0, None), FigletLayout::Kerning);
+        assert_eq!(
+            layout_from_header(15, None),
+            FigletLayout::Smushing { rules: 15 }
+        );
+        // The full layout takes precedence over the old one.
+        assert_eq!(layout_from_header(0, Some(64)), FigletLayout::Kerning);
+        assert_eq!(
+            layout_from_header(-1, Some(128 + 16)),
+            FigletLayout::Smushing { rules: 16 }
+        );
+        assert_eq!(layout_from_header(15, Some(0)), FigletLayout::FullWidth);
+    }
+
+    #[test]
+    fn parses_code_tags() {
+        assert_eq!(parse_code("65"), Some(65));
+        assert_eq!(parse_code("0x263A"), Some(0x263A));
+        assert_eq!(parse_code("0101"), Some(65));
+        assert_eq!(parse_code("-2"), Some(-2));
+        assert_eq!(parse_code("0"), Some(0));
+        assert_eq!(parse_code("abc"), None);
+    }
+
+    #[test]
+    fn reads_code_tagged_glyphs() {
+        let source = font(-1, |c| format!("{c}")) + "0x263A  SMILE\n:)@\n";
+        let font = FigletFont::parse(&source).unwrap();
+        assert_eq!(font.render("a☺"), vec!["a:)"]);
+    }
+
+    #[test]
+    fn joins_glyphs_by_layout() {
+        let glyph = |c: char| format!(" {c}{c} ");
+        assert_eq!(parse(-1, glyph).render("ab"), vec![" aa  bb "]);
+        // Kerning also drops the first glyph's leading blank column.
+        assert_eq!(parse(0, glyph).render("ab"), vec!["aabb "]);
+        assert_eq!(parse(0, glyph).render("ab\nc"), vec!["aabb ", "cc "]);
+    }
+
+    #[test]
+    fn hardblanks_block_kerning_and_render_as_spaces() {
+        let font = parse(0, |c| match c {
+            'a' => "a$".to_string(),
+            'b' => "$b".to_string(),
+            c => c.to_string(),
+        });
+        assert_eq!(font.render("ab"), vec!["a  b"]);
+    }
+
+    #[test]
+    fn big_x_smushing_merges_slashes() {
+        let font = parse(SMUSH_BIG_X as i64, |c| format!("{c}{c}"));
+        assert_eq!(font.render("/\\"), vec!["/|\\"]);
+        assert_eq!(font.render("\\/"), vec!["\\Y/"]);
+        assert_eq!(font.render("><"), vec![">X<"]);
+        // Pairs the rule does not cover stay apart.
+        assert_eq!(font.render("//"), vec!["////"]);
+    }
+
+    #[test]
+    fn controlled_smushing_rules() {
+        let font = parse((SMUSH_EQUAL | SMUSH_PAIR | SMUSH_HIERARCHY) as i64, |c| {
+            format!("{c}{c}")
+        });
+        assert_eq!(font.render("||"), vec!["|||"]);
+        assert_eq!(font.render("[]"), vec!["[|]"]);
+        assert_eq!(font.render("|/"), vec!["|//"]);
+        assert_eq!(font.render("ab"), vec!["aabb"]);
+    }
+}
@@ -0,0 +1,331 @@
+use crate::color::Rgba;
+use crate::path::Point;
+use crate::render::Cell;
+use crate::transform::Transform;
+
+/// How filled shapes (`draw_rect`/`draw_circle` with `filled`, `fill_path`,
+/// `flood_fill`) paint their cells. Outlines and text are unaffected.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Fill {
+    /// Every cell gets the current symbol and colours.
+    #[default]
+    Solid,
+    Gradient(Gradient),
+    Pattern(Pattern),
+}
+
+/// The geometry of a gradient, in the same coordinates as the shapes it fills.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientShape {
+    /// Varies along the line from `start` to `end`, constant across it.
+    Linear { start: Point, end: Point },
+    /// Varies with distance from `center`, reaching the last stop at `radius`.
+    Radial { center: Point, radius: f32 },
+}
+
+/// What a gradient interpolates. Stops are spread evenly from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientStops {
+    /// Colours blended channel by channel, alpha included. They paint the
+    /// background when the current symbol is a space and the glyph otherwise.
+    Colors(Vec<Rgba>),
+    /// Glyphs picked by position, e.g. a density ramp like `" .:-=+*#%@"`,
+    /// drawn in the current colours.
+    Glyphs(Vec<char>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    pub stops: GradientStops,
+}
+
+/// The paint a gradient produces for one cell.
+pub(crate) enum GradientSample {
+    Color(Rgba),
+    Glyph(char),
+}
+
+impl Gradient {
+    pub fn linear(start: Point, end: Point, stops: GradientStops) -> Self {
+        Self {
+            shape: GradientShape::Linear { start, end },
+            stops,
+        }
+    }
+
+    pub fn radial(center: Point, radius: f32, stops: GradientStops) -> Self {
+        Self {
+            shape: GradientShape::Radial { center, radius },
+            stops,
+        }
+    }
+
+    /// Position (0–1, clamped) of the device-space point `p` along the
+    /// gradient once its geometry has been mapped through `transform`.
+    pub(crate) fn position(&self, p: Point, transform: &Transform) -> f32 {
+        let t = match self.shape {
+            GradientShape::Linear { start, end } => {
+                let (start, end) = (transform.apply(start), transform.apply(end));
+                let (dx, dy) = (end.x - start.x, end.y - start.y);
+                let length_squared = dx * dx + dy * dy;
+                if length_squared <= f32::EPSILON {
+                    return 0.0;
+                }
+                ((p.x - start.x) * dx + (p.y - start.y) * dy) / length_squared
+            }
+            GradientShape::Radial { center, radius } => {
+                let radius = radius * transform.max_scale();
+                if radius <= f32::EPSILON {
+                    return 1.0;
+                }
+                p.distance(transform.apply(center)) / radius
+            }
+        };
+        t.clamp(0.0, 1.0)
+    }
+
+    pub(crate) fn sample(&self, t: f32) -> Option<GradientSample> {
+        match &self.stops {
+            GradientStops::Colors(colors) => mix_stops(colors, t).map(GradientSample::Color),
+            GradientStops::Glyphs(glyphs) => pick_stop(glyphs, t).map(GradientSample::Glyph),
+        }
+    }
+}
+
+/// The colour at `t` (0–1) along evenly spread `colors`, blended channel by
+/// channel. `None` when there are no colours.
+pub(crate) fn mix_stops(colors: &[Rgba], t: f32) -> Option<Rgba> {
+    let last = colors.len().checked_sub(1)?;
+    let scaled = t.clamp(0.0, 1.0) * last as f32;
+    let index = (scaled.floor() as usize).min(last);
+    let next = (index + 1).min(last);
+    let f = scaled - index as f32;
+    let (a, b) = (colors[index], colors[next]);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+    Some(Rgba::new(
+        mix(a.r, b.r),
+        mix(a.g, b.g),
+        mix(a.b, b.b),
+        mix(a.a, b.a),
+    ))
+}
+
+/// The glyph nearest `t` (0–1) along evenly spread `glyphs`.
+pub(crate) fn pick_stop(glyphs: &[char], t: f32) -> Option<char> {
+    let last = glyphs.len().checked_sub(1)?;
+    let index = (t.clamp(0.0, 1.0) * last as f32).round() as usize;
+    Some(glyphs[index.min(last)])
+}
+
+/// Line direction for `Pattern::Hatch`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HatchDirection {
+    #[default]
+    Horizontal,
+    Vertical,
+    /// Rising lines, like `/`.
+    Diagonal,
+    /// Falling lines, like `\`.
+    BackDiagonal,
+    /// Horizontal and vertical lines together.
+    Cross,
+}
+
+/// A tiling of "on" cells, aligned to the device grid so neighbouring shapes
+/// line up. On cells get the current symbol and colours; off cells are left
+/// untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    /// Alternating `size` x `size` squares.
+    Checker { size: u16 },
+    /// One-cell lines every `spacing` cells.
+    Hatch {
+        spacing: u16,
+        direction: HatchDirection,
+    },
+    /// Single cells every `spacing` cells, staggered on alternate dot rows.
+    Dots { spacing: u16 },
+}
+
+impl Pattern {
+    pub(crate) fn covers(&self, x: i32, y: i32) -> bool {
+        match *self {
+            Pattern::Checker { size } => {
+                let size = size.max(1) as i32;
+                (x.div_euclid(size) + y.div_euclid(size)) % 2 == 0
+            }
+            Pattern::Hatch { spacing, direction } => {
+                let spacing = spacing.max(1) as i32;
+                let on = |value: i32| value.rem_euclid(spacing) == 0;
+                match direction {
+                    HatchDirection::Horizontal => on(y),
+                    HatchDirection::Vertical => on(x),
+                    HatchDirection::Diagonal => on(x + y),
+                    HatchDirection::BackDiagonal => on(x - y),
+                    HatchDirection::Cross => on(x) || on(y),
+                }
+            }
+            Pattern::Dots { spacing } => {
+                let spacing = spacing.max(1) as i32;
+                let row = y.div_euclid(spacing);
+                let shift = if row % 2 == 0 { 0 } else { spacing / 2 };
+                y.rem_euclid(spacing) == 0 && (x - shift).rem_euclid(spacing) == 0
+            }
+        }
+    }
+}
+
+/// Which cells `Canvas::flood_fill` treats as part of the seed's region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FloodMode {
+    /// Cells showing the same symbol as the seed, whatever their colour.
+    #[default]
+    Symbol,
+    /// Cells with the same background colour as the seed.
+    Color,
+    /// Cells identical to the seed in symbol and colours.
+    Cell,
+}
+
+impl FloodMode {
+    pub(crate) fn matches(self, seed: &Cell, cell: &Cell) -> bool {
+        match self {
+            FloodMode::Symbol => cell.symbol == seed.symbol,
+            FloodMode::Color => cell.bg == seed.bg,
+            FloodMode::Cell => {
+                cell.symbol == seed.symbol && cell.fg == seed.fg && cell.bg == seed.bg
+            }
+        }
+    }
+}
+
+/// Neighbourhood used by `Canvas::flood_fill`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Spreads up, down, left and right only, so diagonal gaps stop it.
+    #[default]
+    Four,
+    /// Also spreads diagonally.
+    Eight,
+}
+
+impl Connectivity {
+    pub(crate) fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            Connectivity::Four => &[(1, 0), (-1, 0), (0, 1), (0, -1)],
+            Connectivity::Eight => &[
+                (1, 0),
+                (-1, 0),
+                (0, 1),
+                (0, -1),
+                (1, 1),
+                (1, -1),
+                (-1, 1),
+                (-1, -1),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLEAR: Rgba = Rgba::new(0, 0, 0, 0);
+    const WHITE: Rgba = Rgba::new(255, 255, 255, 255);
+
+    #[test]
+    fn colour_stops_blend_between_neighbours() {
+        let stops = [CLEAR, WHITE];
+        assert_eq!(mix_stops(&stops, 0.0), Some(CLEAR));
+        assert_eq!(mix_stops(&stops, 1.0), Some(WHITE));
+        assert_eq!(mix_stops(&stops, 0.5), Some(Rgba::new(128, 128, 128, 128)));
+
+        let red = Rgba::rgb(255, 0, 0);
+        assert_eq!(mix_stops(&[CLEAR, red, WHITE], 0.5), Some(red));
+        assert_eq!(
+            mix_stops(&[CLEAR, red, WHITE], 0.75),
+            Some(Rgba::new(255, 128, 128, 255))
+        );
+    }
+
+    #[test]
+    fn colour_stops_clamp_beyond_the_ends() {
+        let stops = [CLEAR, WHITE];
+        assert_eq!(mix_stops(&stops, -1.0), Some(CLEAR));
+        assert_eq!(mix_stops(&stops, 2.0), Some(WHITE));
+        assert_eq!(mix_stops(&[WHITE], 0.3), Some(WHITE));
+        assert_eq!(mix_stops(&[], 0.5), None);
+    }
+
+    #[test]
+    fn glyph_stops_pick_the_nearest() {
+        let ramp = ['a', 'b', 'c'];
+        assert_eq!(pick_stop(&ramp, 0.2), Some('a'));
+        assert_eq!(pick_stop(&ramp, 0.3), Some('b'));
+        assert_eq!(pick_stop(&ramp, 1.5), Some('c'));
+        assert_eq!(pick_stop(&ramp, -1.0), Some('a'));
+        assert_eq!(pick_stop(&[], 0.5), None);
+    }
+
+    #[test]
+    fn linear_gradients_project_onto_their_axis() {
+        let gradient = Gradient::linear(
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            GradientStops::Glyphs(vec![]),
+        );
+        let at = |x, y, transform: &Transform| gradient.position(Point::new(x, y), transform);
+        let identity = Transform::IDENTITY;
+        assert_eq!(at(5.0, 3.0, &identity), 0.5);
+        assert_eq!(at(-5.0, 0.0, &identity), 0.0);
+        assert_eq!(at(20.0, 0.0, &identity), 1.0);
+        // The geometry follows the canvas transform.
+        assert_eq!(at(15.0, 0.0, &Transform::translation(10.0, 0.0)), 0.5);
+
+        let degenerate = Gradient::linear(
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 1.0),
+            GradientStops::Glyphs(vec![]),
+        );
+        assert_eq!(degenerate.position(Point::new(4.0, 4.0), &identity), 0.0);
+    }
+
+    #[test]
+    fn radial_gradients_measure_from_the_centre() {
+        let gradient = Gradient::radial(Point::new(0.0, 0.0), 4.0, GradientStops::Glyphs(vec![]));
+        let at = |x, y, transform: &Transform| gradient.position(Point::new(x, y), transform);
+        assert_eq!(at(2.0, 0.0, &Transform::IDENTITY), 0.5);
+        assert_eq!(at(0.0, 9.0, &Transform::IDENTITY), 1.0);
+        assert_eq!(at(4.0, 0.0, &Transform::scaling(2.0, 2.0)), 0.5);
+    }
+
+    #[test]
+    fn patterns_tile_the_device_grid() {
+        let checker = Pattern::Checker { size: 2 };
+        assert!(checker.covers(0, 0) && checker.covers(1, 1) && checker.covers(2, 2));
+        assert!(!checker.covers(2, 0) && !checker.covers(-1, 0));
+        assert!(checker.covers(-1, -1));
+
+        let hatch = |direction| Pattern::Hatch {
+            spacing: 3,
+            direction,
+        };
+        assert!(hatch(HatchDirection::Horizontal).covers(7, 3));
+        assert!(!hatch(HatchDirection::Horizontal).covers(3, 7));
+        assert!(hatch(HatchDirection::Vertical).covers(3, 7));
+        assert!(hatch(HatchDirection::Diagonal).covers(1, 2));
+        assert!(hatch(HatchDirection::BackDiagonal).covers(4, 1));
+        assert!(!hatch(HatchDirection::BackDiagonal).covers(1, 2));
+        assert!(
+            hatch(HatchDirection::Cross).covers(3, 1) && hatch(HatchDirection::Cross).covers(1, 3)
+        );
+
+        // Alternate dot rows are staggered by half the spacing.
+        let dots = Pattern::Dots { spacing: 4 };
+        assert!(dots.covers(0, 0) && dots.covers(4, 0));
+        assert!(!dots.covers(0, 4) && dots.covers(2, 4));
+        assert!(!dots.covers(0, 1));
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+use rand::Rng;
+
+use crate::color::Rgba;
+use crate::draw::{Canvas, PixelMode};
+use crate::fill::{mix_stops, pick_stop};
+use crate::noise::Noise3;
+use crate::path::Point;
+use crate::plot::PlotPoint;
+
+/// Step used for the finite differences of `CurlField`, in noise units.
+const CURL_EPSILON: f32 = 0.01;
+
+/// A time-varying 2D vector field.
+///
+/// Fields work in square units: `x` in cells and `y` in cells divided by
+/// the tracers' aspect, so a field looks the same across and down the
+/// screen. Any `Fn(Point, f32) -> Point` taking a position and time is a
+/// field.
+pub trait VectorField {
+    fn velocity(&self, position: Point, time: f32) -> Point;
+}
+
+impl<F: Fn(Point, f32) -> Point> VectorField for F {
+    fn velocity(&self, position: Point, time: f32) -> Point {
+        self(position, time)
+    }
+}
+
+/// Flow in the direction given by a noise value: -1 to 1 maps to one full
+/// turn either way.
+#[derive(Debug, Clone)]
+pub struct NoiseField<N> {
+    pub noise: N,
+    /// Feature size in units; larger values give broader currents.
+    pub scale: f32,
+    /// Speed in units per second.
+    pub strength: f32,
+    /// How fast the field changes over time.
+    pub evolution: f32,
+}
+
+impl<N: Noise3> NoiseField<N> {
+    pub fn new(noise: N) -> Self {
+        Self {
+            noise,
+            scale: 20.0,
+            strength: 8.0,
+            evolution: 0.1,
+        }
+    }
+
+    pub fn with_scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    pub fn with_strength(self, strength: f32) -> Self {
+        Self { strength, ..self }
+    }
+
+    pub fn with_evolution(self, evolution: f32) -> Self {
+        Self { evolution, ..self }
+    }
+}
+
+impl<N: Noise3> VectorField for NoiseField<N> {
+    fn velocity(&self, position: Point, time: f32) -> Point {
+        let value = self.noise.noise3(
+            position.x / self.scale,
+            position.y / self.scale,
+            time * self.evolution,
+        );
+        let angle = value * TAU;
+        Point::new(angle.cos() * self.strength, angle.sin() * self.strength)
+    }
+}
+
+/// The curl of a noise potential: a swirling, divergence-free flow, so
+/// tracers neither bunch up nor thin out.
+#[derive(Debug, Clone)]
+pub struct CurlField<N> {
+    pub noise: N,
+    /// Feature size in units; larger values give broader eddies.
+    pub scale: f32,
+    /// Typical speed in units per second.
+    pub strength: f32,
+    /// How fast the field changes over time.
+    pub evolution: f32,
+}
+
+impl<N: Noise3> CurlField<N> {
+    pub fn new(noise: N) -> Self {
+        Self {
+            noise,
+            scale: 20.0,
+            strength: 8.0,
+            evolution: 0.1,
+        }
+    }
+
+    pub fn with_scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    pub fn with_strength(self, strength: f32) -> Self {
+        Self { strength, ..self }
+    }
+
+    pub fn with_evolution(self, evolution: f32) -> Self {
+        Self { evolution, ..self }
+    }
+}
+
+impl<N: Noise3> VectorField for CurlField<N> {
+    fn velocity(&self, position: Point, time: f32) -> Point {
+        let (x, y, z) = (
+            position.x / self.scale,
+            position.y / self.scale,
+            time * self.evolution,
+        );
+        let potential = |x: f32, y: f32| self.noise.noise3(x, y, z);
+        let dx = potential(x + CURL_EPSILON, y) - potential(x - CURL_EPSILON, y);
+        let dy = potential(x, y + CURL_EPSILON) - potential(x, y - CURL_EPSILON);
+        // Noise gradients are roughly 1-2 per unit, so this keeps speeds
+        // near `strength`.
+        let factor = self.strength / (2.0 * CURL_EPSILON) * 0.5;
+        Point::new(dy * factor, -dx * factor)
+    }
+}
+
+/// A point carried along by a field, remembering where it has been.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tracer {
+    /// Position in canvas cells.
+    pub position: Point,
+    /// Past positions, newest first.
+    pub trail: VecDeque<Point>,
+    /// Seconds until the tracer respawns somewhere else.
+    pub life: f32,
+}
+
+/// Tracers advected through a `VectorField` inside a `width` x `height`
+/// cell area, leaving fading trails. Draw with `Tracers::draw`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tracers {
+    pub tracers: Vec<Tracer>,
+    /// Positions remembered per tracer, head included.
+    pub trail_length: usize,
+    /// Mean seconds before a tracer respawns at random, which keeps them
+    /// from collecting in sinks; 0 keeps them forever.
+    pub lifetime: f32,
+    /// Whether tracers leaving the area come back on the opposite side
+    /// rather than respawning at random.
+    pub wrap: bool,
+    /// Scale from field units to cells vertically; 0.5 for cells twice as
+    /// tall as they are wide.
+    pub aspect: f32,
+    /// Colour from head to tail; empty uses the canvas' foreground colour.
+    pub colors: Vec<Rgba>,
+    /// ASCII glyph from head to tail; empty draws `.`.
+    pub glyphs: Vec<char>,
+    width: f32,
+    height: f32,
+}
+
+impl Tracers {
+    /// `count` tracers spread at random over a `width` x `height` area.
+    pub fn new(count: usize, width: f32, height: f32, rng: &mut impl Rng) -> Self {
+        let mut tracers = Self {
+            tracers: Vec::with_capacity(count),
+            trail_length: 12,
+            lifetime: 6.0,
+            wrap: true,
+            aspect: 0.5,
+            colors: Vec::new(),
+            glyphs: Vec::new(),
+            width,
+            height,
+        };
+        for _ in 0..count {
+            let tracer = tracers.spawn(rng);
+            tracers.tracers.push(tracer);
+        }
+        tracers
+    }
+
+    pub fn with_trail_length(self, trail_length: usize) -> Self {
+        Self {
+            trail_length,
+            ..self
+        }
+    }
+
+    pub fn with_colors(self, colors: Vec<Rgba>) -> Self {
+        Self { colors, ..self }
+    }
+
+    pub fn with_glyphs(self, glyphs: impl Into<String>) -> Self {
+        Self {
+            glyphs: glyphs.into().chars().collect(),
+            ..self
+        }
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
+    /// Changes the area, respawning tracers that are now outside it.
+    pub fn resize(&mut self, width: f32, height: f32, rng: &mut impl Rng) {
+        self.width = width;
+        self.height = height;
+        for index in 0..self.tracers.len() {
+            if !self.contains(self.tracers[index].position) {
+                self.tracers[index] = self.spawn(rng);
+            }
+        }
+    }
+
+    /// Moves every tracer along `field` at `time` for `delta_time` seconds,
+    /// using a midpoint step.
+    pub fn advect(
+        &mut self,
+        field: &(impl VectorField + ?Sized),
+        time: f32,
+        delta_time: f32,
+        rng: &mut impl Rng,
+    ) {
+        let aspect = self.aspect.max(f32::EPSILON);
+        let velocity = |p: Point, t: f32| {
+            let v = field.velocity(Point::new(p.x, p.y / aspect), t);
+            Point::new(v.x, v.y * aspect)
+        };
+        for index in 0..self.tracers.len() {
+            let tracer = &mut self.tracers[index];
+            let p = tracer.position;
+            let half = delta_time * 0.5;
+            let v1 = velocity(p, time);
+            let mid = Point::new(p.x + v1.x * half, p.y + v1.y * half);
+            let v2 = velocity(mid, time + half);
+            let mut next = Point::new(p.x + v2.x * delta_time, p.y + v2.y * delta_time);
+            tracer.life -= delta_time;
+
+            let mut respawn = self.lifetime > 0.0 && tracer.life <= 0.0;
+            if !(0.0..self.width).contains(&next.x) || !(0.0..self.height).contains(&next.y) {
+                if self.wrap {
+                    next = Point::new(
+                        next.x.rem_euclid(self.width),
+                        next.y.rem_euclid(self.height),
+                    );
+                    // Don't draw a trail across the whole area.
+                    tracer.trail.clear();
+                } else {
+                    respawn = true;
+                }
+            }
+            if respawn {
+                self.tracers[index] = self.spawn(rng);
+                continue;
+            }
+            tracer.position = next;
+            tracer.trail.push_front(next);
+            tracer.trail.truncate(self.trail_length.max(1));
+        }
+    }
+
+    /// Draws the trails as lines of logical pixels in the canvas' pixel
+    /// mode through `Canvas::plot_points`, fading along `colors` from head
+    /// to tail. Positions follow the transform and the clip applies.
+    pub fn draw(&self, canvas: &mut Canvas<'_>) {
+        let mode = canvas.current_pixel_mode;
+        canvas.plot_points(tracer_points(self, mode, canvas.foreground_rgba()));
+    }
+
+    fn contains(&self, p: Point) -> bool {
+        (0.0..self.width).contains(&p.x) && (0.0..self.height).contains(&p.y)
+    }
+
+    fn spawn(&self, rng: &mut impl Rng) -> Tracer {
+        let position = Point::new(
+            rng.gen::<f32>() * self.width,
+            rng.gen::<f32>() * self.height,
+        );
+        Tracer {
+            position,
+            trail: VecDeque::from([position]),
+            life: self.lifetime * rng.gen_range(0.5..1.5),
+        }
+    }
+
+    /// Colour and glyph at `t` along a trail, 0 at the head.
+    pub(crate) fn paint(&self, t: f32, default_color: Rgba) -> (Rgba, char) {
+        (
+            mix_stops(&self.colors, t).unwrap_or(default_color),
+            pick_stop(&self.glyphs, t).unwrap_or('.'),
+        )
+    }
+}
+
+/// Logical pixels along every trail, tails first so heads paint on top.
+fn tracer_points(tracers: &Tracers, mode: PixelMode, default_color: Rgba) -> Vec<PlotPoint> {
+    let mut points = Vec::new();
+    for tracer in &tracers.tracers {
+        trail_points(
+            tracer.trail.iter().rev().copied(),
+            |t| tracers.paint(t, default_color),
+            mode,
+            &mut points,
+        );
+    }
+    points
+}
+
+/// Appends logical pixels along `trail`, given oldest first. Gaps between
+/// positions are filled at the resolution of `mode` so fast movers still
+/// leave solid lines; `paint` gives the colour and glyph at `t` along the
+/// trail, 0 at the newest position and 1 at the oldest.
+pub(crate) fn trail_points(
+    trail: impl ExactSizeIterator<Item = Point>,
+    paint: impl Fn(f32) -> (Rgba, char),
+    mode: PixelMode,
+    points: &mut Vec<PlotPoint>,
+) {
+    let (sx, sy) = mode.cell_resolution();
+    let last = trail.len().saturating_sub(1).max(1) as f32;
+    let mut previous: Option<Point> = None;
+    for (index, to) in trail.enumerate() {
+        let (color, glyph) = paint(1.0 - index as f32 / last);
+        let Some(from) = previous.replace(to) else {
+            points.push(PlotPoint {
+                position: to,
+                color,
+                glyph,
+            });
+            continue;
+        };
+        let steps = ((to.x - from.x).abs() * sx as f32)
+            .max((to.y - from.y).abs() * sy as f32)
+            .ceil()
+            .max(1.0) as usize;
+        points.extend((1..=steps).map(|step| PlotPoint {
+            position: from.lerp(to, step as f32 / steps as f32),
+            color,
+            glyph,
+        }));
+    }
+}
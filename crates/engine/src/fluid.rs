@@ -0,0 +1,322 @@
+use crate::color::Rgba;
+use crate::draw::Canvas;
+
+/// Which field `set_boundary` is fixing up at the walls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Boundary {
+    /// Densities and pressures, copied from the cell inside.
+    Scalar,
+    /// Horizontal velocity, reflected at the left and right walls.
+    Horizontal,
+    /// Vertical velocity, reflected at the top and bottom walls.
+    Vertical,
+}
+
+/// Jos Stam's "stable fluids": an incompressible velocity field that
+/// diffuses and carries itself along, pushing a dye density around a
+/// walled box. Implicit diffusion and semi-Lagrangian advection keep it
+/// stable at any time step.
+///
+/// The grid usually matches the canvas' logical resolution, one cell per
+/// logical pixel, and velocities are in cells per second. Add dye with
+/// `add_density`, stir with `add_velocity` and draw with `Fluid::draw`.
+#[derive(Debug, Clone)]
+pub struct Fluid {
+    width: usize,
+    height: usize,
+    density: Vec<f32>,
+    velocity: (Vec<f32>, Vec<f32>),
+    scratch: Vec<f32>,
+    scratch_velocity: (Vec<f32>, Vec<f32>),
+    /// Kinematic viscosity in cells² per second; higher is more syrupy.
+    pub viscosity: f32,
+    /// How fast dye spreads by itself, in cells² per second.
+    pub diffusion: f32,
+    /// Share of the dye that fades away each second, 0–1.
+    pub dissipation: f32,
+    /// Gauss–Seidel sweeps per solve; more is more accurate and slower.
+    pub iterations: usize,
+    /// Colour ramp from no dye to full density; empty draws in the
+    /// canvas' foreground colour, leaving the glyph ramp and dot density to
+    /// show the dye.
+    pub colors: Vec<Rgba>,
+}
+
+impl Fluid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let cells = (width + 2) * (height + 2);
+        Self {
+            width,
+            height,
+            density: vec![0.0; cells],
+            velocity: (vec![0.0; cells], vec![0.0; cells]),
+            scratch: vec![0.0; cells],
+            scratch_velocity: (vec![0.0; cells], vec![0.0; cells]),
+            viscosity: 0.0,
+            diffusion: 0.0,
+            dissipation: 0.1,
+            iterations: 20,
+            colors: Vec::new(),
+        }
+    }
+
+    pub fn with_viscosity(self, viscosity: f32) -> Self {
+        Self {
+            viscosity: viscosity.max(0.0),
+            ..self
+        }
+    }
+
+    pub fn with_diffusion(self, diffusion: f32) -> Self {
+        Self {
+            diffusion: diffusion.max(0.0),
+            ..self
+        }
+    }
+
+    pub fn with_colors(self, colors: impl IntoIterator<Item = Rgba>) -> Self {
+        Self {
+            colors: colors.into_iter().collect(),
+            ..self
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Dye density at `(x, y)`, 0 outside the grid.
+    pub fn density(&self, x: usize, y: usize) -> f32 {
+        if x >= self.width || y >= self.height {
+            return 0.0;
+        }
+        self.density[self.index(x + 1, y + 1)]
+    }
+
+    /// Draws the dye density, one grid cell per logical pixel from the
+    /// top-left of the canvas, through `Canvas::shade_grid`.
+    pub fn draw(&self, canvas: &mut Canvas<'_>) {
+        canvas.shade_grid(&self.colors, |x, y| self.density(x, y));
+    }
+
+    /// Velocity at `(x, y)` in cells per second, zero outside the grid.
+    pub fn velocity(&self, x: usize, y: usize) -> (f32, f32) {
+        if x >= self.width || y >= self.height {
+            return (0.0, 0.0);
+        }
+        let index = self.index(x + 1, y + 1);
+        (self.velocity.0[index], self.velocity.1[index])
+    }
+
+    /// Removes all dye and motion.
+    pub fn clear(&mut self) {
+        self.density.fill(0.0);
+        self.velocity.0.fill(0.0);
+        self.velocity.1.fill(0.0);
+    }
+
+    /// Resizes the grid, keeping the overlapping top-left region.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+        let mut resized = Self {
+            viscosity: self.viscosity,
+            diffusion: self.diffusion,
+            dissipation: self.dissipation,
+            iterations: self.iterations,
+            colors: std::mem::take(&mut self.colors),
+            ..Self::new(width, height)
+        };
+        for y in 1..=height.min(self.height) {
+            for x in 1..=width.min(self.width) {
+                let (from, to) = (self.index(x, y), resized.index(x, y));
+                resized.density[to] = self.density[from];
+                resized.velocity.0[to] = self.velocity.0[from];
+                resized.velocity.1[to] = self.velocity.1[from];
+            }
+        }
+        *self = resized;
+    }
+
+    /// Adds `amount` of dye to the disc of `radius` cells around `(x, y)`,
+    /// capping the density at 1.
+    pub fn add_density(&mut self, x: f32, y: f32, radius: f32, amount: f32) {
+        for index in self.disc(x, y, radius) {
+            self.density[index] = (self.density[index] + amount).min(1.0);
+        }
+    }
+
+    /// Adds `(vx, vy)` cells per second to the velocity in the disc of
+    /// `radius` cells around `(x, y)`.
+    pub fn add_velocity(&mut self, x: f32, y: f32, radius: f32, (vx, vy): (f32, f32)) {
+        for index in self.disc(x, y, radius) {
+            self.velocity.0[index] += vx;
+            self.velocity.1[index] += vy;
+        }
+    }
+
+    /// Advances the simulation by `dt` seconds: the velocity diffuses and
+    /// advects itself, is made divergence free, then carries the dye.
+    pub fn step(&mut self, dt: f32) {
+        if self.width == 0 || self.height == 0 || dt <= 0.0 {
+            return;
+        }
+        let (mut u, mut v) = std::mem::take(&mut self.velocity);
+        let (mut u0, mut v0) = std::mem::take(&mut self.scratch_velocity);
+
+        std::mem::swap(&mut u, &mut u0);
+        self.diffuse(Boundary::Horizontal, &mut u, &u0, self.viscosity, dt);
+        std::mem::swap(&mut v, &mut v0);
+        self.diffuse(Boundary::Vertical, &mut v, &v0, self.viscosity, dt);
+        self.project(&mut u, &mut v, &mut u0, &mut v0);
+
+        u0.copy_from_slice(&u);
+        v0.copy_from_slice(&v);
+        self.advect(Boundary::Horizontal, &mut u, &u0, (&u0, &v0), dt);
+        self.advect(Boundary::Vertical, &mut v, &v0, (&u0, &v0), dt);
+        self.project(&mut u, &mut v, &mut u0, &mut v0);
+
+        let mut density = std::mem::take(&mut self.density);
+        let mut previous = std::mem::take(&mut self.scratch);
+        std::mem::swap(&mut density, &mut previous);
+        self.diffuse(
+            Boundary::Scalar,
+            &mut density,
+            &previous,
+            self.diffusion,
+            dt,
+        );
+        std::mem::swap(&mut density, &mut previous);
+        self.advect(Boundary::Scalar, &mut density, &previous, (&u, &v), dt);
+        let fade = (1.0 - self.dissipation.clamp(0.0, 1.0)).powf(dt);
+        density.iter_mut().for_each(|d| *d *= fade);
+
+        self.density = density;
+        self.scratch = previous;
+        self.velocity = (u, v);
+        self.scratch_velocity = (u0, v0);
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        y * (self.width + 2) + x
+    }
+
+    /// Indices of the interior cells within `radius` of `(x, y)`.
+    fn disc(&self, x: f32, y: f32, radius: f32) -> Vec<usize> {
+        let reach = radius.max(0.5).ceil() as i32;
+        let (cx, cy) = (x.floor() as i32, y.floor() as i32);
+        let mut indices = Vec::new();
+        for dy in -reach..=reach {
+            for dx in -reach..=reach {
+                let (column, row) = (cx + dx, cy + dy);
+                let inside = (0..self.width as i32).contains(&column)
+                    && (0..self.height as i32).contains(&row);
+                if inside && (dx * dx + dy * dy) as f32 <= radius * radius {
+                    indices.push(self.index(column as usize + 1, row as usize + 1));
+                }
+            }
+        }
+        indices
+    }
+
+    /// Implicit diffusion: solves `x - rate * dt * laplacian(x) = x0`.
+    fn diffuse(&self, boundary: Boundary, x: &mut [f32], x0: &[f32], rate: f32, dt: f32) {
+        let a = dt * rate;
+        if a <= 0.0 {
+            x.copy_from_slice(x0);
+            return;
+        }
+        x.copy_from_slice(x0);
+        self.solve(boundary, x, x0, a, 1.0 + 4.0 * a);
+    }
+
+    /// Gauss–Seidel relaxation of `x = (x0 + a * neighbours(x)) / c`.
+    fn solve(&self, boundary: Boundary, x: &mut [f32], x0: &[f32], a: f32, c: f32) {
+        for _ in 0..self.iterations.max(1) {
+            for y in 1..=self.height {
+                for column in 1..=self.width {
+                    let i = self.index(column, y);
+                    let stride = self.width + 2;
+                    let neighbours = x[i - 1] + x[i + 1] + x[i - stride] + x[i + stride];
+                    x[i] = (x0[i] + a * neighbours) / c;
+                }
+            }
+            self.set_boundary(boundary, x);
+        }
+    }
+
+    /// Moves `field` along `velocity` by tracing each cell back in time and
+    /// sampling `previous` there.
+    fn advect(
+        &self,
+        boundary: Boundary,
+        field: &mut [f32],
+        previous: &[f32],
+        (u, v): (&[f32], &[f32]),
+        dt: f32,
+    ) {
+        let max_x = self.width as f32 + 0.5;
+        let max_y = self.height as f32 + 0.5;
+        for y in 1..=self.height {
+            for x in 1..=self.width {
+                let i = self.index(x, y);
+                let px = (x as f32 - dt * u[i]).clamp(0.5, max_x);
+                let py = (y as f32 - dt * v[i]).clamp(0.5, max_y);
+                let (x0, y0) = (px.floor() as usize, py.floor() as usize);
+                let (sx, sy) = (px - x0 as f32, py - y0 as f32);
+                let at = |x: usize, y: usize| previous[self.index(x, y)];
+                field[i] = (1.0 - sx) * ((1.0 - sy) * at(x0, y0) + sy * at(x0, y0 + 1))
+                    + sx * ((1.0 - sy) * at(x0 + 1, y0) + sy * at(x0 + 1, y0 + 1));
+            }
+        }
+        self.set_boundary(boundary, field);
+    }
+
+    /// Removes the divergent part of the velocity so the fluid neither
+    /// gathers nor thins out, leaving the swirls.
+    fn project(&self, u: &mut [f32], v: &mut [f32], pressure: &mut [f32], divergence: &mut [f32]) {
+        let stride = self.width + 2;
+        for y in 1..=self.height {
+            for x in 1..=self.width {
+                let i = self.index(x, y);
+                divergence[i] = -0.5 * (u[i + 1] - u[i - 1] + v[i + stride] - v[i - stride]);
+                pressure[i] = 0.0;
+            }
+        }
+        self.set_boundary(Boundary::Scalar, divergence);
+        self.set_boundary(Boundary::Scalar, pressure);
+        self.solve(Boundary::Scalar, pressure, divergence, 1.0, 4.0);
+        for y in 1..=self.height {
+            for x in 1..=self.width {
+                let i = self.index(x, y);
+                u[i] -= 0.5 * (pressure[i + 1] - pressure[i - 1]);
+                v[i] -= 0.5 * (pressure[i + stride] - pressure[i - stride]);
+            }
+        }
+        self.set_boundary(Boundary::Horizontal, u);
+        self.set_boundary(Boundary::Vertical, v);
+    }
+
+    /// Fills the ring of wall cells so nothing flows through the walls.
+    fn set_boundary(&self, boundary: Boundary, field: &mut [f32]) {
+        let (w, h) = (self.width, self.height);
+        let sign = |reflect: bool| if reflect { -1.0 } else { 1.0 };
+        for y in 1..=h {
+            let flip = sign(boundary == Boundary::Horizontal);
+            field[self.index(0, y)] = flip * field[self.index(1, y)];
+            field[self.index(w + 1, y)] = flip * field[self.index(w, y)];
+        }
+        for x in 1..=w {
+            let flip = sign(boundary == Boundary::Vertical);
+            field[self.index(x, 0)] = flip * field[self.index(x, 1)];
+            field[self.index(x, h + 1)] = flip * field[self.index(x, h)];
+        }
+        let corner = |a: f32, b: f32| 0.5 * (a + b);
+        field[self.index(0, 0)] = corner(field[self.index(1, 0)], field[self.index(0, 1)]);
+        field[self.index(0, h + 1)] = corner(field[self.index(1, h + 1)], field[self.index(0, h)]);
+        field[self.index(w + 1, 0)] = corner(field[self.index(w, 0)], field[self.index(w + 1, 1)]);
+        field[self.index(w + 1, h + 1)] =
+            corner(field[self.index(w, h + 1)], field[self.index(w + 1, h)]);
+    }
+}
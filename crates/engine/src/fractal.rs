@@ -0,0 +1,219 @@
+use crate::color::Rgba;
+use crate::draw::Canvas;
+use crate::fill::mix_stops;
+use crate::shader::Shade;
+
+/// Escape radius squared. A radius well past 2 keeps the smooth iteration
+/// count free of banding.
+const BAILOUT: f64 = 256.0 * 256.0;
+
+/// Height of the view in plane units at zoom 1.
+const BASE_SPAN: f64 = 3.0;
+
+/// Which escape-time set a `FractalView` iterates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FractalSet {
+    /// `z = z² + c` from `z = 0`, with `c` the plotted point.
+    Mandelbrot,
+    /// `z = z² + c` from the plotted point, for a fixed `c`.
+    Julia { re: f64, im: f64 },
+}
+
+/// A window onto the Mandelbrot or a Julia set, coloured by smooth
+/// iteration count. Draw with `FractalView::draw`, which evaluates rows
+/// in parallel with the `parallel` feature.
+///
+/// Coordinates are `f64` throughout, so zooms hold up to around `1e13`
+/// before neighbouring pixels collapse onto the same plane point; raise
+/// `max_iterations` as the zoom deepens to keep the boundary detailed.
+#[derive(Debug, Clone)]
+pub struct FractalView {
+    pub set: FractalSet,
+    /// Plane point at the middle of the canvas, `(re, im)`.
+    pub center: (f64, f64),
+    /// Magnification; at 1 the canvas is 3 plane units tall.
+    pub zoom: f64,
+    pub max_iterations: u32,
+    /// Palette cycled along the smooth iteration count. Empty draws in the
+    /// canvas' foreground colour, leaving the glyph ramp and dot density to
+    /// show the gradient.
+    pub colors: Vec<Rgba>,
+    /// Iterations per trip round the palette.
+    pub color_period: f32,
+    /// Shift along the palette, 0–1; animate it to cycle the colours.
+    pub color_offset: f32,
+    /// Cell width over cell height, so the set is not stretched.
+    pub aspect: f32,
+}
+
+impl FractalView {
+    pub fn new(set: FractalSet) -> Self {
+        let center = match set {
+            FractalSet::Mandelbrot => (-0.5, 0.0),
+            FractalSet::Julia { .. } => (0.0, 0.0),
+        };
+        Self {
+            set,
+            center,
+            zoom: 1.0,
+            max_iterations: 128,
+            colors: Vec::new(),
+            color_period: 32.0,
+            color_offset: 0.0,
+            aspect: 0.5,
+        }
+    }
+
+    pub fn mandelbrot() -> Self {
+        Self::new(FractalSet::Mandelbrot)
+    }
+
+    pub fn julia(re: f64, im: f64) -> Self {
+        Self::new(FractalSet::Julia { re, im })
+    }
+
+    pub fn with_center(self, re: f64, im: f64) -> Self {
+        Self {
+            center: (re, im),
+            ..self
+        }
+    }
+
+    pub fn with_zoom(self, zoom: f64) -> Self {
+        Self {
+            zoom: zoom.max(f64::MIN_POSITIVE),
+            ..self
+        }
+    }
+
+    pub fn with_iterations(self, max_iterations: u32) -> Self {
+        Self {
+            max_iterations: max_iterations.max(1),
+            ..self
+        }
+    }
+
+    pub fn with_colors(self, colors: impl IntoIterator<Item = Rgba>) -> Self {
+        Self {
+            colors: colors.into_iter().collect(),
+            ..self
+        }
+    }
+
+    pub fn with_color_period(self, color_period: f32) -> Self {
+        Self {
+            color_period: color_period.max(f32::EPSILON),
+            ..self
+        }
+    }
+
+    /// Height of the view in plane units.
+    pub fn span(&self) -> f64 {
+        BASE_SPAN / self.zoom
+    }
+
+    /// Moves the centre by fractions of the view height, `dy` growing
+    /// downwards like screen rows.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        let span = self.span();
+        self.center.0 += dx * span;
+        self.center.1 -= dy * span;
+    }
+
+    /// Multiplies the zoom by `factor`, keeping the centre fixed.
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).max(f64::MIN_POSITIVE);
+    }
+
+    /// The smooth iteration count at which the orbit of plane point
+    /// `(re, im)` escapes, or `None` if it stays bounded for
+    /// `max_iterations`.
+    pub fn escape(&self, re: f64, im: f64) -> Option<f64> {
+        let ((mut x, mut y), (cx, cy)) = match self.set {
+            FractalSet::Mandelbrot => {
+                if in_main_bulbs(re, im) {
+                    return None;
+                }
+                ((0.0, 0.0), (re, im))
+            }
+            FractalSet::Julia { re: cx, im: cy } => ((re, im), (cx, cy)),
+        };
+        for n in 0..self.max_iterations {
+            let (xx, yy) = (x * x, y * y);
+            if xx + yy > BAILOUT {
+                // n + 1 - log2(log|z|) cancels the stepwise growth of the
+                // escaping orbit, giving a continuous count.
+                let log_modulus = (xx + yy).ln() * 0.5;
+                let nu = (log_modulus / std::f64::consts::LN_2).log2();
+                return Some((n as f64 + 1.0 - nu).max(0.0));
+            }
+            y = 2.0 * x * y + cy;
+            x = xx - yy + cx;
+        }
+        None
+    }
+
+    /// How `FractalView::draw` paints a point escaping after `count`
+    /// smooth iterations, or the interior for `None`.
+    pub fn shade(&self, count: Option<f64>) -> Shade {
+        let Some(count) = count else {
+            return Shade::new(0.0);
+        };
+        let max = self.max_iterations as f64;
+        let intensity = ((count + 1.0).ln() / (max + 1.0).ln()).clamp(0.0, 1.0) as f32;
+        let shade = Shade::new(intensity);
+        if self.colors.is_empty() {
+            return shade;
+        }
+        let position = (count as f32 / self.color_period + self.color_offset).rem_euclid(1.0);
+        let scaled = position * self.colors.len() as f32;
+        let index = (scaled as usize).min(self.colors.len() - 1);
+        let next = (index + 1) % self.colors.len();
+        let pair = [self.colors[index], self.colors[next]];
+        match mix_stops(&pair, scaled - index as f32) {
+            Some(color) => shade.with_color(color),
+            None => shade,
+        }
+    }
+
+    /// Maps logical pixel `(x, y)` of a canvas `size` logical pixels wide
+    /// and tall, with `resolution` pixels per cell, to its plane point.
+    pub fn plane_point(
+        &self,
+        (x, y): (f32, f32),
+        size: (usize, usize),
+        resolution: (usize, usize),
+    ) -> (f64, f64) {
+        let (sx, sy) = (resolution.0.max(1) as f64, resolution.1.max(1) as f64);
+        let aspect = self.aspect.max(f32::EPSILON) as f64;
+        // Work in cell widths, in which the canvas is square-pixelled.
+        let height = size.1 as f64 / sy / aspect;
+        let unit = self.span() / height.max(f64::MIN_POSITIVE);
+        let u = (x as f64 + 0.5) / sx - size.0 as f64 / sx * 0.5;
+        let v = ((y as f64 + 0.5) / sy - size.1 as f64 / sy * 0.5) / aspect;
+        (self.center.0 + u * unit, self.center.1 - v * unit)
+    }
+
+    /// Fills the canvas through `Canvas::shade`: every logical pixel is
+    /// mapped to its plane point, iterated and painted with `shade`. Points
+    /// inside the set stay blank.
+    pub fn draw(&self, canvas: &mut Canvas<'_>) {
+        let size = canvas.logical_size();
+        let resolution = canvas.current_pixel_mode.cell_resolution();
+        canvas.shade(0.0, |x, y, _| {
+            let (re, im) = self.plane_point((x, y), size, resolution);
+            self.shade(self.escape(re, im))
+        });
+    }
+}
+
+/// Whether `(re, im)` lies in the Mandelbrot set's main cardioid or
+/// period-2 bulb, which would otherwise run to `max_iterations`.
+fn in_main_bulbs(re: f64, im: f64) -> bool {
+    let x = re - 0.25;
+    let q = x * x + im * im;
+    if q * (q + x) <= 0.25 * im * im {
+        return true;
+    }
+    (re + 1.0) * (re + 1.0) + im * im <= 1.0 / 16.0
+}
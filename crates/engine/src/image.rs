@@ -0,0 +1,731 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use crossterm::style::Color;
+use serde::Deserialize;
+
+use crate::draw::PixelMode;
+
+/// Luminance-ordered glyphs used by ASCII image rendering, darkest first.
+pub const DEFAULT_RAMP: &str = " .:-=+*#%@";
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const OPAQUE_ALPHA: u8 = 128;
+
+/// An RGBA raster loaded from a PPM/PGM/PBM or PNG file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 4]>,
+}
+
+/// How `Canvas::draw_image_with_options` turns pixels into cells.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ImageOptions {
+    /// Rendering mode; `None` follows the canvas' current pixel mode.
+    pub mode: Option<PixelMode>,
+    /// Spread quantisation error with Floyd–Steinberg (braille and ASCII).
+    pub dither: bool,
+    /// Luminance (0–1) at which a braille dot lights up, or a half block is
+    /// drawn when `color` is off.
+    pub threshold: f32,
+    /// Light dots for dark pixels instead, for light terminal backgrounds.
+    pub invert: bool,
+    /// Glyphs for ASCII mode, darkest first.
+    pub ramp: String,
+    /// Colour cells from the image; otherwise the current foreground is used.
+    pub color: bool,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            mode: None,
+            dither: false,
+            threshold: 0.5,
+            invert: false,
+            ramp: DEFAULT_RAMP.to_string(),
+            color: true,
+        }
+    }
+}
+
+/// A prerendered cell for `Canvas::draw_cells`, as produced for images,
+/// viewports and automata; `None` colours keep the canvas' current colours.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageCell {
+    pub symbol: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+impl Image {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read image at {}", path.display()))?;
+        Self::decode(&bytes)
+            .with_context(|| format!("Failed to decode image at {}", path.display()))
+    }
+
+    /// Decodes PNG or Netpbm (`P1`–`P6`) data, detected from its magic bytes.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.starts_with(PNG_SIGNATURE) {
+            decode_png(bytes)
+        } else if bytes.first() == Some(&b'P') {
+            decode_netpbm(bytes)
+        } else {
+            bail!("Unsupported image format (expected PNG, PPM, PGM or PBM)")
+        }
+    }
+
+    pub fn from_rgba(width: usize, height: usize, pixels: Vec<[u8; 4]>) -> Result<Self> {
+        if pixels.len() != width * height {
+            bail!(
+                "Expected {} pixels for a {width}x{height} image, got {}",
+                width * height,
+                pixels.len()
+            );
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// RGBA value at `(x, y)`; out-of-range coordinates are transparent.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x]
+        } else {
+            [0; 4]
+        }
+    }
+
+    /// Rescales to `width` x `height` by averaging the source pixels each
+    /// target pixel covers (nearest neighbour when enlarging).
+    pub fn resize(&self, width: usize, height: usize) -> Image {
+        let mut pixels = Vec::with_capacity(width * height);
+        if self.is_empty() {
+            pixels.resize(width * height, [0; 4]);
+        } else {
+            for ty in 0..height {
+                let (y0, y1) = source_span(ty, height, self.height);
+                for tx in 0..width {
+                    let (x0, x1) = source_span(tx, width, self.width);
+                    pixels.push(self.average(x0..x1, y0..y1));
+                }
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Largest cell size within `max_width` x `max_height` that keeps the
+    /// image's aspect ratio, assuming cells are twice as tall as they are wide.
+    pub fn fit(&self, max_width: u16, max_height: u16) -> (u16, u16) {
+        if self.is_empty() || max_width == 0 || max_height == 0 {
+            return (0, 0);
+        }
+        let aspect = self.width as f32 / self.height as f32;
+        let height = (max_width as f32 / (2.0 * aspect)).round();
+        if height <= max_height as f32 {
+            (max_width, height.max(1.0) as u16)
+        } else {
+            let width = (max_height as f32 * 2.0 * aspect).round();
+            (width.clamp(1.0, max_width as f32) as u16, max_height)
+        }
+    }
+
+    /// Alpha-weighted mean of a block of pixels.
+    fn average(&self, xs: std::ops::Range<usize>, ys: std::ops::Range<usize>) -> [u8; 4] {
+        let mut sum = [0u32; 4];
+        let mut count = 0u32;
+        for y in ys {
+            for x in xs.clone() {
+                let [r, g, b, a] = self.pixels[y * self.width + x];
+                let a = a as u32;
+                sum[0] += r as u32 * a;
+                sum[1] += g as u32 * a;
+                sum[2] += b as u32 * a;
+                sum[3] += a;
+                count += 1;
+            }
+        }
+        if sum[3] == 0 {
+            return [0; 4];
+        }
+        [
+            (sum[0] / sum[3]) as u8,
+            (sum[1] / sum[3]) as u8,
+            (sum[2] / sum[3]) as u8,
+            (sum[3] / count.max(1)) as u8,
+        ]
+    }
+}
+
+/// Source pixel range covered by target pixel `index` when scaling `source`
+/// pixels to `target`; never empty.
+fn source_span(index: usize, target: usize, source: usize) -> (usize, usize) {
+    let start = (index * source / target).min(source - 1);
+    let end = ((index + 1) * source / target).clamp(start + 1, source);
+    (start, end)
+}
+
+/// Rec. 709 luma in 0–1.
+pub(crate) fn luminance([r, g, b, _]: [u8; 4]) -> f32 {
+    (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0
+}
+
+/// Converts `image` into `columns` x `rows` cells for `mode`, row by row.
+/// Fully transparent cells come back as `None`.
+pub(crate) fn render_cells(
+    image: &Image,
+    columns: usize,
+    rows: usize,
+    mode: PixelMode,
+    options: &ImageOptions,
+) -> Vec<Option<ImageCell>> {
+    match mode {
+        PixelMode::HalfBlock => halfblock_cells(image, columns, rows, options),
+        PixelMode::Braille => braille_cells(image, columns, rows, options),
+        PixelMode::Ascii => ascii_cells(image, columns, rows, options),
+    }
+}
+
+fn rgb([r, g, b, _]: [u8; 4]) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+fn is_opaque(pixel: [u8; 4]) -> bool {
+    pixel[3] >= OPAQUE_ALPHA
+}
+
+/// Luminance of each opaque pixel (flipped by `invert`); transparent pixels are `None`.
+fn luminance_grid(image: &Image, invert: bool) -> Vec<Option<f32>> {
+    image
+        .pixels
+        .iter()
+        .map(|&pixel| {
+            is_opaque(pixel).then(|| {
+                let value = luminance(pixel);
+                if invert {
+                    1.0 - value
+                } else {
+                    value
+                }
+            })
+        })
+        .collect()
+}
+
+/// Quantises `values` to `levels` evenly spaced steps in place, returning the
+/// chosen level of each pixel. With `dither`, Floyd–Steinberg error diffusion
+/// pushes each pixel's rounding error onto its unvisited neighbours;
+/// transparent pixels neither receive nor spread error.
+fn quantize(
+    values: &mut [Option<f32>],
+    width: usize,
+    levels: usize,
+    threshold: f32,
+    dither: bool,
+) -> Vec<usize> {
+    let max_level = levels.saturating_sub(1).max(1);
+    let height = values.len().checked_div(width).unwrap_or(0);
+    let mut chosen = vec![0; values.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let index = y * width + x;
+            let Some(value) = values[index] else {
+                continue;
+            };
+            let level = if max_level == 1 {
+                usize::from(value >= threshold)
+            } else {
+                (value.clamp(0.0, 1.0) * max_level as f32).round() as usize
+            };
+            chosen[index] = level;
+            if !dither {
+                continue;
+            }
+            let error = value - level as f32 / max_level as f32;
+            let mut spread = |dx: isize, dy: usize, weight: f32| {
+                let nx = x as isize + dx;
+                let ny = y + dy;
+                if nx < 0 || nx as usize >= width || ny >= height {
+                    return;
+                }
+                if let Some(neighbour) = values[ny * width + nx as usize].as_mut() {
+                    *neighbour += error * weight;
+                }
+            };
+            spread(1, 0, 7.0 / 16.0);
+            spread(-1, 1, 3.0 / 16.0);
+            spread(0, 1, 5.0 / 16.0);
+            spread(1, 1, 1.0 / 16.0);
+        }
+    }
+    chosen
+}
+
+/// `▀` cells with the upper pixel in the foreground and the lower one in the
+/// background, doubling the vertical resolution.
+fn halfblock_cells(
+    image: &Image,
+    columns: usize,
+    rows: usize,
+    options: &ImageOptions,
+) -> Vec<Option<ImageCell>> {
+    let scaled = image.resize(columns, rows * 2);
+    let lit = |pixel: [u8; 4]| {
+        is_opaque(pixel) && ((luminance(pixel) >= options.threshold) != options.invert)
+    };
+    let mut cells = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        for column in 0..columns {
+            let top = scaled.pixel(column, row * 2);
+            let bottom = scaled.pixel(column, row * 2 + 1);
+            let cell = if options.color {
+                match (is_opaque(top), is_opaque(bottom)) {
+                    (true, true) => Some(('▀', Some(rgb(top)), Some(rgb(bottom)))),
+                    (true, false) => Some(('▀', Some(rgb(top)), None)),
+                    (false, true) => Some(('▄', Some(rgb(bottom)), None)),
+                    (false, false) => None,
+                }
+            } else {
+                match (lit(top), lit(bottom)) {
+                    (true, true) => Some(('█', None, None)),
+                    (true, false) => Some(('▀', None, None)),
+                    (false, true) => Some(('▄', None, None)),
+                    (false, false) => None,
+                }
+            };
+            cells.push(cell.map(|(symbol, fg, bg)| ImageCell { symbol, fg, bg }));
+        }
+    }
+    cells
+}
+
+/// Braille dot bits for the 2x4 sub-pixels of a cell, indexed `[y][x]`.
+pub(crate) const BRAILLE_DOTS: [[u32; 2]; 4] =
+    [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Braille cells with one dot per 2x4 sub-pixel, lit above `threshold`.
+/// Coloured cells take the mean colour of their lit dots.
+fn braille_cells(
+    image: &Image,
+    columns: usize,
+    rows: usize,
+    options: &ImageOptions,
+) -> Vec<Option<ImageCell>> {
+    let (width, height) = (columns * 2, rows * 4);
+    let scaled = image.resize(width, height);
+    let mut values = luminance_grid(&scaled, options.invert);
+    let lit = quantize(&mut values, width, 2, options.threshold, options.dither);
+
+    let mut cells = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        for column in 0..columns {
+            let mut bits = 0;
+            let mut sum = [0u32; 3];
+            for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                for (dx, bit) in dots.iter().enumerate() {
+                    let (x, y) = (column * 2 + dx, row * 4 + dy);
+                    let index = y * width + x;
+                    if values[index].is_some() && lit[index] == 1 {
+                        bits |= bit;
+                        let [r, g, b, _] = scaled.pixel(x, y);
+                        sum[0] += r as u32;
+                        sum[1] += g as u32;
+                        sum[2] += b as u32;
+                    }
+                }
+            }
+            if bits == 0 {
+                cells.push(None);
+                continue;
+            }
+            let count = bits.count_ones();
+            let fg = options.color.then(|| Color::Rgb {
+                r: (sum[0] / count) as u8,
+                g: (sum[1] / count) as u8,
+                b: (sum[2] / count) as u8,
+            });
+            let symbol = char::from_u32(0x2800 + bits).unwrap_or(' ');
+            cells.push(Some(ImageCell {
+                symbol,
+                fg,
+                bg: None,
+            }));
+        }
+    }
+    cells
+}
+
+/// One glyph per pixel, picked from the ramp by luminance.
+fn ascii_cells(
+    image: &Image,
+    columns: usize,
+    rows: usize,
+    options: &ImageOptions,
+) -> Vec<Option<ImageCell>> {
+    let ramp: Vec<char> = if options.ramp.is_empty() {
+        DEFAULT_RAMP.chars().collect()
+    } else {
+        options.ramp.chars().collect()
+    };
+    let scaled = image.resize(columns, rows);
+    let mut values = luminance_grid(&scaled, options.invert);
+    let levels = quantize(&mut values, columns, ramp.len(), 0.5, options.dither);
+
+    values
+        .iter()
+        .zip(levels)
+        .zip(&scaled.pixels)
+        .map(|((value, level), &pixel)| {
+            value.map(|_| ImageCell {
+                symbol: ramp[level.min(ramp.len() - 1)],
+                fg: options.color.then(|| rgb(pixel)),
+                bg: None,
+            })
+        })
+        .collect()
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Image> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer)?;
+    let data = &buffer[..info.buffer_size()];
+    let pixels = match info.color_type {
+        png::ColorType::Grayscale => data.iter().map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::GrayscaleAlpha => data
+            .chunks_exact(2)
+            .map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Rgb => data
+            .chunks_exact(3)
+            .map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::Rgba => data
+            .chunks_exact(4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+            .collect(),
+        other => bail!("Unsupported PNG colour type {other:?}"),
+    };
+    Image::from_rgba(info.width as usize, info.height as usize, pixels)
+}
+
+/// Reads whitespace-separated header fields and plain-format samples,
+/// skipping `#` comments.
+struct NetpbmReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl NetpbmReader<'_> {
+    fn skip_whitespace(&mut self) {
+        while let Some(&byte) = self.bytes.get(self.position) {
+            if byte == b'#' {
+                while self.bytes.get(self.position).is_some_and(|&b| b != b'\n') {
+                    self.position += 1;
+                }
+            } else if byte.is_ascii_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<u32> {
+        self.skip_whitespace();
+        let start = self.position;
+        while self
+            .bytes
+            .get(self.position)
+            .is_some_and(u8::is_ascii_digit)
+        {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.position])?
+            .parse()
+            .map_err(|_| anyhow!("Expected a number at byte {start}"))
+    }
+
+    /// A single `0`/`1` digit; plain PBM allows bits without separators.
+    fn bit(&mut self) -> Result<bool> {
+        self.skip_whitespace();
+        match self.bytes.get(self.position) {
+            Some(b'0') => {
+                self.position += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.position += 1;
+                Ok(true)
+            }
+            _ => bail!("Expected a bit at byte {}", self.position),
+        }
+    }
+}
+
+fn decode_netpbm(bytes: &[u8]) -> Result<Image> {
+    let kind = match bytes.get(1) {
+        Some(digit @ b'1'..=b'6') => digit - b'0',
+        _ => bail!("Unknown Netpbm magic number"),
+    };
+    let mut reader = NetpbmReader { bytes, position: 2 };
+    let width = reader.number()? as usize;
+    let height = reader.number()? as usize;
+    let max_value = if matches!(kind, 1 | 4) {
+        1
+    } else {
+        reader.number()?
+    };
+    if max_value == 0 || max_value > u16::MAX as u32 {
+        bail!("Invalid maximum sample value {max_value}");
+    }
+    // Header sizes are untrusted, so every size derived from them is checked.
+    let too_large = || anyhow!("Image size {width}x{height} is too large");
+    let count = width.checked_mul(height).ok_or_else(too_large)?;
+    let scale = |sample: u32| (sample.min(max_value) * 255 / max_value) as u8;
+    let gray = |v: u8| [v, v, v, 255];
+    // A PBM 1 is black.
+    let bit = |on: bool| gray(if on { 0 } else { 255 });
+
+    let pixels: Vec<[u8; 4]> = match kind {
+        1 => (0..count)
+            .map(|_| reader.bit().map(bit))
+            .collect::<Result<_>>()?,
+        2 => (0..count)
+            .map(|_| reader.number().map(|v| gray(scale(v))))
+            .collect::<Result<_>>()?,
+        3 => (0..count)
+            .map(|_| {
+                let r = scale(reader.number()?);
+                let g = scale(reader.number()?);
+                let b = scale(reader.number()?);
+                Ok([r, g, b, 255])
+            })
+            .collect::<Result<_>>()?,
+        _ => {
+            // Exactly one whitespace byte separates the header from binary data.
+            let data = bytes
+                .get(reader.position + 1..)
+                .ok_or_else(|| anyhow!("Missing pixel data"))?;
+            let sample_size = if max_value > 255 { 2 } else { 1 };
+            let channels = if kind == 6 { 3 } else { 1 };
+            if kind != 4 {
+                let needed = count
+                    .checked_mul(channels * sample_size)
+                    .ok_or_else(too_large)?;
+                if data.len() < needed {
+                    bail!("Pixel data is truncated");
+                }
+            }
+            let sample = |i: usize| -> Result<u32> {
+                let start = i * sample_size;
+                let raw = data
+                    .get(start..start + sample_size)
+                    .ok_or_else(|| anyhow!("Pixel data is truncated"))?;
+                Ok(raw.iter().fold(0, |acc, &b| (acc << 8) | b as u32))
+            };
+            match kind {
+                4 => {
+                    let stride = width.div_ceil(8);
+                    if data.len() < stride.checked_mul(height).ok_or_else(too_large)? {
+                        bail!("Pixel data is truncated");
+                    }
+                    (0..count)
+                        .map(|i| {
+                            let (x, y) = (i % width, i / width);
+                            bit(data[y * stride + x / 8] & (0x80 >> (x % 8)) != 0)
+                        })
+                        .collect()
+                }
+                5 => (0..count)
+                    .map(|i| sample(i).map(|v| gray(scale(v))))
+                    .collect::<Result<_>>()?,
+                _ => (0..count)
+                    .map(|i| {
+                        Ok([
+                            scale(sample(i * 3)?),
+                            scale(sample(i * 3 + 1)?),
+                            scale(sample(i * 3 + 2)?),
+                            255,
+                        ])
+                    })
+                    .collect::<Result<_>>()?,
+            }
+        }
+    };
+    Image::from_rgba(width, height, pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    fn pixels(image: &Image) -> Vec<[u8; 4]> {
+        (0..image.height())
+            .flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+            .map(|(x, y)| image.pixel(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn decodes_plain_pbm_with_comments() {
+        let image = Image::decode(b"P1\n# a comment\n3 2\n1 0 1\n0 1 0\n").unwrap();
+        assert_eq!((image.width(), image.height()), (3, 2));
+        assert_eq!(
+            pixels(&image),
+            vec![BLACK, WHITE, BLACK, WHITE, BLACK, WHITE]
+        );
+    }
+
+    #[test]
+    fn decodes_plain_pbm_without_separators() {
+        let image = Image::decode(b"P1 3 2 101010").unwrap();
+        assert_eq!(
+            pixels(&image),
+            vec![BLACK, WHITE, BLACK, WHITE, BLACK, WHITE]
+        );
+    }
+
+    #[test]
+    fn scales_plain_samples_to_the_maximum() {
+        let gray = Image::decode(b"P2 2 1 4 0 2").unwrap();
+        assert_eq!(pixels(&gray), vec![[0, 0, 0, 255], [127, 127, 127, 255]]);
+        let color = Image::decode(b"P3 1 1 255 10 20 30").unwrap();
+        assert_eq!(pixels(&color), vec![[10, 20, 30, 255]]);
+    }
+
+    #[test]
+    fn decodes_padded_binary_pbm_rows() {
+        // 10 pixels wide: each row takes two bytes.
+        let mut bytes = b"P4\n10 2\n".to_vec();
+        bytes.extend([0b1000_0000, 0b0100_0000, 0b0000_0000, 0b1100_0000]);
+        let image = Image::decode(&bytes).unwrap();
+        let row = |y| {
+            (0..10)
+                .map(|x| image.pixel(x, y)[0] == 0)
+                .collect::<Vec<_>>()
+        };
+        let mut first = vec![false; 10];
+        first[0] = true;
+        first[9] = true;
+        assert_eq!(row(0), first);
+        let mut second = vec![false; 10];
+        second[8] = true;
+        second[9] = true;
+        assert_eq!(row(1), second);
+    }
+
+    #[test]
+    fn decodes_binary_samples() {
+        let mut bytes = b"P5 2 1 65535\n".to_vec();
+        bytes.extend([0xff, 0xff, 0x00, 0x00]);
+        assert_eq!(pixels(&Image::decode(&bytes).unwrap()), vec![WHITE, BLACK]);
+
+        let mut bytes = b"P6 1 1 255\n".to_vec();
+        bytes.extend([1, 2, 3]);
+        assert_eq!(
+            pixels(&Image::decode(&bytes).unwrap()),
+            vec![[1, 2, 3, 255]]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_netpbm() {
+        for bytes in [
+            &b"P7 1 1 255\n"[..],
+            b"P2 2 1 0 0 0",
+            b"P1 2 2 101",
+            b"P1 1 1 2",
+            b"P6 2 1 255\n\x01\x02\x03",
+            b"P4 9 1\n\x00",
+            b"GIF89a",
+        ] {
+            assert!(Image::decode(bytes).is_err(), "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_sizes_that_overflow() {
+        let huge = u32::MAX;
+        for header in [
+            format!("P6 {huge} {huge} 65535\n"),
+            format!("P5 {huge} {huge} 255\n"),
+            format!("P4 {huge} {huge}\n"),
+            format!("P2 {huge} {huge} 255 0"),
+        ] {
+            let mut bytes = header.into_bytes();
+            bytes.extend([0; 8]);
+            assert!(Image::decode(&bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn decodes_png() {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer
+                .write_image_data(&[255, 0, 0, 255, 0, 0, 255, 0])
+                .unwrap();
+        }
+        let image = Image::decode(&bytes).unwrap();
+        assert_eq!(pixels(&image), vec![[255, 0, 0, 255], [0, 0, 255, 0]]);
+    }
+
+    #[test]
+    fn resizing_averages_by_alpha() {
+        let image = Image::from_rgba(2, 1, vec![[200, 0, 0, 255], [0, 200, 0, 0]]).unwrap();
+        assert_eq!(pixels(&image.resize(1, 1)), vec![[200, 0, 0, 127]]);
+        assert_eq!(
+            pixels(&image.resize(4, 1)),
+            vec![
+                [200, 0, 0, 255],
+                [200, 0, 0, 255],
+                [0, 0, 0, 0],
+                [0, 0, 0, 0]
+            ]
+        );
+        assert!(Image::from_rgba(2, 2, vec![WHITE]).is_err());
+    }
+
+    #[test]
+    fn fits_cells_twice_as_tall_as_wide() {
+        let square = Image::from_rgba(4, 4, vec![WHITE; 16]).unwrap();
+        assert_eq!(square.fit(40, 40), (40, 20));
+        assert_eq!(square.fit(40, 10), (20, 10));
+        assert_eq!(square.fit(0, 10), (0, 0));
+    }
+}
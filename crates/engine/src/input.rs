@@ -23,8 +23,8 @@ impl InputState {
         let mut events = Vec::new();
 
         while event::poll(Duration::from_millis(0))? {
-            match event::read()? {
-                Event::Key(key_event) => match key_event.kind {
+            if let Event::Key(key_event) = event::read()? {
+                match key_event.kind {
                     KeyEventKind::Press => {
                         self.set_key_pressed(key_event.code, true);
                         events.push(key_event);
@@ -35,8 +35,7 @@ impl InputState {
                     KeyEventKind::Release => {
                         self.set_key_pressed(key_event.code, false);
                     }
-                },
-                _ => {}
+                }
             }
         }
 
@@ -0,0 +1,337 @@
+use crate::draw::Canvas;
+use crate::render::Cell;
+
+/// A named drawing surface composited with the scene canvas.
+///
+/// Cells left at `Cell::default()` are transparent, so lower layers show
+/// through. Layers with a negative `z` sit beneath the scene canvas, the rest
+/// above it (the debug overlay always stays on top).
+pub struct Layer {
+    name: String,
+    width: u16,
+    height: u16,
+    buffer: Vec<Cell>,
+    z: i32,
+    visible: bool,
+    opacity: f32,
+    offset_x: i32,
+    offset_y: i32,
+    wrap: bool,
+    persistent: bool,
+}
+
+impl Layer {
+    fn new(name: String, z: i32, width: u16, height: u16) -> Self {
+        Self {
+            name,
+            width,
+            height,
+            buffer: vec![Cell::default(); width as usize * height as usize],
+            z,
+            visible: true,
+            opacity: 1.0,
+            offset_x: 0,
+            offset_y: 0,
+            wrap: false,
+            persistent: false,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    pub fn canvas(&mut self) -> Canvas<'_> {
+        Canvas::new(self.width, self.height, &mut self.buffer)
+    }
+
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+
+    /// Moves the layer in the stack; layers with equal `z` keep their creation order.
+    pub fn set_z(&mut self, z: i32) {
+        self.z = z;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Scales the coverage of every cell in the layer; 0 hides it, 1 draws it as painted.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    pub fn offset(&self) -> (i32, i32) {
+        (self.offset_x, self.offset_y)
+    }
+
+    /// Shifts the layer's content on screen, e.g. for parallax scrolling.
+    pub fn set_offset(&mut self, x: i32, y: i32) {
+        self.offset_x = x;
+        self.offset_y = y;
+    }
+
+    pub fn scroll(&mut self, dx: i32, dy: i32) {
+        self.offset_x += dx;
+        self.offset_y += dy;
+    }
+
+    /// Tiles the layer in both directions so scrolled content wraps around.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    /// Persistent layers keep their content across frames instead of being
+    /// cleared with the screen, so static backgrounds can be drawn once.
+    pub fn set_persistent(&mut self, persistent: bool) {
+        self.persistent = persistent;
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.fill(Cell::default());
+    }
+
+    /// The cell this layer contributes at screen position `(x, y)`, with the
+    /// layer opacity applied, or `None` where it is transparent.
+    fn sample(&self, x: i32, y: i32) -> Option<Cell> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let (mut lx, mut ly) = (x - self.offset_x, y - self.offset_y);
+        if self.wrap {
+            lx = lx.rem_euclid(width);
+            ly = ly.rem_euclid(height);
+        } else if lx < 0 || ly < 0 || lx >= width || ly >= height {
+            return None;
+        }
+        let cell = self.buffer[(ly * width + lx) as usize];
+        if cell == Cell::default() {
+            return None;
+        }
+        if self.opacity >= 1.0 {
+            return Some(cell);
+        }
+        let scale = |alpha: u8| (alpha as f32 * self.opacity).round() as u8;
+        Some(Cell {
+            fg_alpha: scale(cell.fg_alpha),
+            bg_alpha: scale(cell.bg_alpha),
+            ..cell
+        })
+    }
+}
+
+/// The named layers owned by a `TerminalRenderer`.
+pub struct LayerStack {
+    width: u16,
+    height: u16,
+    layers: Vec<Layer>,
+}
+
+impl LayerStack {
+    pub(crate) fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Adds a screen-sized layer, replacing any existing layer with the same name.
+    pub fn add(&mut self, name: impl Into<String>, z: i32) -> &mut Layer {
+        self.add_sized(name, z, self.width, self.height)
+    }
+
+    /// Adds a layer of any size, e.g. a wide background to scroll across.
+    pub fn add_sized(
+        &mut self,
+        name: impl Into<String>,
+        z: i32,
+        width: u16,
+        height: u16,
+    ) -> &mut Layer {
+        let layer = Layer::new(name.into(), z, width, height);
+        let index = match self.layers.iter().position(|l| l.name == layer.name) {
+            Some(index) => {
+                self.layers[index] = layer;
+                index
+            }
+            None => {
+                self.layers.push(layer);
+                self.layers.len() - 1
+            }
+        };
+        &mut self.layers[index]
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Layer> {
+        let index = self.layers.iter().position(|l| l.name == name)?;
+        Some(self.layers.remove(index))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|l| l.name == name)
+    }
+
+    /// Shorthand for drawing on a layer: `layers.canvas("ui")`.
+    pub fn canvas(&mut self, name: &str) -> Option<Canvas<'_>> {
+        self.get_mut(name).map(Layer::canvas)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(Layer::name)
+    }
+
+    /// Clears every layer that is not persistent.
+    pub(crate) fn clear_frame(&mut self) {
+        for layer in self.layers.iter_mut().filter(|l| !l.persistent) {
+            layer.clear();
+        }
+    }
+
+    /// Visible layers from bottom to top.
+    pub(crate) fn ordered(&self) -> Vec<&Layer> {
+        let mut ordered: Vec<&Layer> = self
+            .layers
+            .iter()
+            .filter(|l| l.visible && l.opacity > 0.0)
+            .collect();
+        ordered.sort_by_key(|l| l.z);
+        ordered
+    }
+}
+
+/// Paints each of `layers` that covers `(x, y)` over `cell`, bottom to top.
+pub(crate) fn composite_layers(mut cell: Cell, layers: &[&Layer], x: i32, y: i32) -> Cell {
+    for layer in layers {
+        if let Some(source) = layer.sample(x, y) {
+            cell = cell.composite(&source);
+        }
+    }
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4x2 stack with `text` written at the top left of a layer per entry.
+    fn stack(layers: &[(&str, i32, &str)]) -> LayerStack {
+        let mut stack = LayerStack::new(4, 2);
+        for &(name, z, text) in layers {
+            stack.add(name, z).canvas().draw_text(0, 0, text);
+        }
+        stack
+    }
+
+    fn symbol_at(stack: &LayerStack, x: i32, y: i32) -> char {
+        composite_layers(Cell::default(), &stack.ordered(), x, y).symbol
+    }
+
+    #[test]
+    fn layers_stack_by_z_then_creation_order() {
+        let mut stack = stack(&[("top", 2, "t"), ("bottom", -1, "b"), ("also_top", 2, "a")]);
+        assert_eq!(symbol_at(&stack, 0, 0), 'a');
+        stack.get_mut("also_top").unwrap().set_z(0);
+        assert_eq!(symbol_at(&stack, 0, 0), 't');
+        let order: Vec<&str> = stack.ordered().iter().map(|l| l.name()).collect();
+        assert_eq!(order, ["bottom", "also_top", "top"]);
+    }
+
+    #[test]
+    fn transparent_cells_show_lower_layers() {
+        let stack = stack(&[("bottom", 0, "ab"), ("top", 1, "c")]);
+        assert_eq!(symbol_at(&stack, 0, 0), 'c');
+        assert_eq!(symbol_at(&stack, 1, 0), 'b');
+        assert_eq!(symbol_at(&stack, 2, 0), ' ');
+    }
+
+    #[test]
+    fn hidden_and_fully_faded_layers_are_skipped() {
+        let mut stack = stack(&[("bottom", 0, "b"), ("top", 1, "t")]);
+        stack.get_mut("top").unwrap().set_visible(false);
+        assert_eq!(symbol_at(&stack, 0, 0), 'b');
+        stack.get_mut("top").unwrap().set_visible(true);
+        stack.get_mut("top").unwrap().set_opacity(0.0);
+        assert_eq!(stack.ordered().len(), 1);
+    }
+
+    #[test]
+    fn opacity_scales_coverage() {
+        let mut stack = LayerStack::new(1, 1);
+        let layer = stack.add("tint", 0);
+        let mut canvas = layer.canvas();
+        canvas.set_foreground_color(crossterm::style::Color::Red);
+        canvas.draw_text(0, 0, "x");
+        layer.set_opacity(0.5);
+        let sampled = layer.sample(0, 0).unwrap();
+        assert_eq!((sampled.fg_alpha, sampled.bg_alpha), (128, 128));
+        // Out of range opacities are clamped.
+        layer.set_opacity(3.0);
+        assert_eq!(layer.opacity(), 1.0);
+        assert_eq!(layer.sample(0, 0).unwrap().fg_alpha, 255);
+    }
+
+    #[test]
+    fn offsets_shift_the_layer_and_optionally_wrap() {
+        let mut stack = stack(&[("scroll", 0, "abcd")]);
+        let layer = stack.get_mut("scroll").unwrap();
+        layer.set_offset(1, 0);
+        layer.scroll(0, 1);
+        assert_eq!(layer.offset(), (1, 1));
+        assert!(layer.sample(0, 1).is_none());
+        assert_eq!(layer.sample(1, 1).unwrap().symbol, 'a');
+        assert!(layer.sample(1, 0).is_none());
+
+        layer.set_wrap(true);
+        assert_eq!(layer.sample(0, 1).unwrap().symbol, 'd');
+        assert_eq!(layer.sample(8, 3).unwrap().symbol, 'd');
+        assert_eq!(layer.sample(-3, -1).unwrap().symbol, 'a');
+    }
+
+    #[test]
+    fn only_persistent_layers_survive_a_new_frame() {
+        let mut stack = stack(&[("hud", 0, "h"), ("background", -1, "b")]);
+        stack.get_mut("background").unwrap().set_persistent(true);
+        stack.clear_frame();
+        assert!(stack.get("hud").unwrap().sample(0, 0).is_none());
+        assert_eq!(
+            stack
+                .get("background")
+                .unwrap()
+                .sample(0, 0)
+                .unwrap()
+                .symbol,
+            'b'
+        );
+    }
+
+    #[test]
+    fn adding_a_layer_again_replaces_it() {
+        let mut stack = stack(&[("ui", 0, "u")]);
+        stack.add("ui", 3);
+        assert_eq!(stack.names().collect::<Vec<_>>(), ["ui"]);
+        assert_eq!(stack.get("ui").unwrap().z(), 3);
+        assert!(stack.get("ui").unwrap().sample(0, 0).is_none());
+        assert!(stack.remove("ui").is_some());
+        assert!(stack.get("ui").is_none());
+    }
+}
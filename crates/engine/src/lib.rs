@@ -1,11 +1,79 @@
+pub mod agents;
+pub mod anim;
+pub mod automata;
+pub mod boids;
+pub mod border;
+pub mod color;
 pub mod config;
 pub mod draw;
+pub mod effects;
+pub mod figlet;
+pub mod fill;
+pub mod flow;
+pub mod fluid;
+pub mod fractal;
+pub mod image;
 pub mod input;
+pub mod layer;
+pub mod lsystem;
+pub mod math3d;
+pub mod mesh;
+pub mod noise;
+pub mod particles;
+pub mod path;
+pub mod plot;
+pub mod reaction;
 pub mod render;
+pub mod render3d;
 pub mod scene;
+pub mod shader;
+pub mod spatial;
+pub mod sprite;
+pub mod stroke;
+pub mod text;
 pub mod time;
+pub mod transform;
+pub mod turtle;
 
+pub use agents::{Agent, Agents, Edges, RandomWalk, StepRule};
+pub use anim::{
+    Animation, Delay, Easing, Hold, Keyframes, Lerp, Parallel, Param, Repeat, Sequence, Timeline,
+    Tween, Yoyo,
+};
+pub use automata::{Automaton, AutomatonRule, CellPattern, Topology};
+pub use boids::{Boid, Flock};
+pub use border::BorderStyle;
+pub use color::{parse_color, BlendMode, Rgba};
 pub use config::{load_config, EngineConfig, EngineSettings, SceneConfig};
-pub use draw::{Canvas, PixelMode};
+pub use draw::{Canvas, ClipRect, PixelMode};
+pub use effects::{Effect, EffectChain};
+pub use figlet::{FigletFont, FigletLayout};
+pub use fill::{
+    Connectivity, Fill, FloodMode, Gradient, GradientShape, GradientStops, HatchDirection, Pattern,
+};
+pub use flow::{CurlField, NoiseField, Tracer, Tracers, VectorField};
+pub use fluid::Fluid;
+pub use fractal::{FractalSet, FractalView};
+pub use image::{Image, ImageCell, ImageOptions};
 pub use input::InputState;
+pub use layer::{Layer, LayerStack};
+pub use lsystem::{LSystem, Module, Rule};
+pub use math3d::{Mat4, Vec3, Vec4};
+pub use mesh::Mesh;
+pub use noise::{
+    DomainWarp, Fractal, FractalKind, Noise2, Noise3, Noise4, OpenSimplex, Perlin, Worley,
+    WorleyDistance, WorleyOutput,
+};
+pub use particles::{Emitter, EmitterShape, Force, Particle, ParticleSystem};
+pub use path::{Path, PathCommand, Point, Polyline};
+pub use plot::PlotPoint;
+pub use reaction::GrayScott;
+pub use render3d::{Camera, Light, Material, MeshStyle, Shading, Viewport3d};
 pub use scene::{Context, Scene, SceneManager};
+pub use shader::Shade;
+pub use spatial::SpatialHash;
+pub use sprite::{Flip, Sprite, SpriteCell, SpriteFrame};
+pub use stroke::{LineCap, StrokeStyle};
+pub use text::TextAlign;
+pub use transform::Transform;
+pub use turtle::{Turtle, TurtleState};
@@ -0,0 +1,703 @@
+use std::fmt;
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use rand::Rng;
+
+use crate::path::Point;
+use crate::turtle::Turtle;
+
+/// Words never grow past this many modules; expansion cuts them off there.
+pub const MAX_MODULES: usize = 1_000_000;
+
+/// One symbol of an L-system word with its (possibly empty) parameters,
+/// such as `F` or `A(1.5, 2)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub symbol: char,
+    pub params: Vec<f32>,
+}
+
+impl Module {
+    pub fn new(symbol: char) -> Self {
+        Self {
+            symbol,
+            params: Vec::new(),
+        }
+    }
+
+    fn param(&self) -> Option<f32> {
+        self.params.first().copied()
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol)?;
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(f32::to_string).collect();
+            write!(f, "({})", params.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// An arithmetic expression over a rule's formal parameters. Comparisons
+/// and `&&`/`||` yield 1 for true and 0 for false.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f32),
+    /// Index into the matched module's parameters.
+    Param(usize),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, params: &[f32]) -> f32 {
+        let truth = |b: bool| if b { 1.0 } else { 0.0 };
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Param(index) => params[*index],
+            Expr::Neg(inner) => -inner.eval(params),
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (a.eval(params), b.eval(params));
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    '^' => a.powf(b),
+                    '<' => truth(a < b),
+                    '>' => truth(a > b),
+                    '≤' => truth(a <= b),
+                    '≥' => truth(a >= b),
+                    '=' => truth(a == b),
+                    '≠' => truth(a != b),
+                    '&' => truth(a != 0.0 && b != 0.0),
+                    _ => truth(a != 0.0 || b != 0.0),
+                }
+            }
+        }
+    }
+}
+
+/// A recursive-descent parser over one rule or word, skipping whitespace.
+struct Parser<'s> {
+    chars: Vec<char>,
+    pos: usize,
+    formals: &'s [String],
+}
+
+impl<'s> Parser<'s> {
+    fn new(text: &str, formals: &'s [String]) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            pos: 0,
+            formals,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.peek();
+        let matches = token
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c));
+        if matches {
+            self.pos += token.chars().count();
+        }
+        matches
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            bail!("Expected '{token}'")
+        }
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        self.peek();
+        let start = self.pos;
+        while self
+            .chars
+            .get(self.pos)
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| self.chars[start..self.pos].iter().collect())
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut left = self.and()?;
+        while self.eat("||") {
+            left = Expr::Binary('|', Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut left = self.comparison()?;
+        while self.eat("&&") {
+            left = Expr::Binary('&', Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let left = self.sum()?;
+        let ops = [
+            ("<=", '≤'),
+            (">=", '≥'),
+            ("==", '='),
+            ("!=", '≠'),
+            ("<", '<'),
+            (">", '>'),
+        ];
+        for (token, op) in ops {
+            if self.eat(token) {
+                return Ok(Expr::Binary(op, Box::new(left), Box::new(self.sum()?)));
+            }
+        }
+        Ok(left)
+    }
+
+    fn sum(&mut self) -> Result<Expr> {
+        let mut left = self.product()?;
+        loop {
+            let op = match self.peek() {
+                Some(op @ ('+' | '-')) => op,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(op @ ('*' | '/')) => op,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        let base = self.atom()?;
+        if self.eat("^") {
+            return Ok(Expr::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+                {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                text.parse()
+                    .map(Expr::Number)
+                    .map_err(|_| anyhow!("Invalid number '{text}'"))
+            }
+            Some(c) => {
+                let name = self
+                    .identifier()
+                    .ok_or_else(|| anyhow!("Expected a value, found '{c}'"))?;
+                let index = self
+                    .formals
+                    .iter()
+                    .position(|formal| *formal == name)
+                    .ok_or_else(|| anyhow!("Unknown parameter '{name}'"))?;
+                Ok(Expr::Param(index))
+            }
+            None => bail!("Unexpected end of expression"),
+        }
+    }
+
+    /// A sequence of symbols, each optionally followed by `(expr, ...)`.
+    fn successor(&mut self) -> Result<Vec<(char, Vec<Expr>)>> {
+        let mut modules = Vec::new();
+        while let Some(symbol) = self.peek() {
+            if symbol == '(' || symbol == ')' || symbol == ',' {
+                bail!("Unexpected '{symbol}'");
+            }
+            self.pos += 1;
+            let mut args = Vec::new();
+            if self.eat("(") {
+                loop {
+                    args.push(self.expr()?);
+                    if !self.eat(",") {
+                        break;
+                    }
+                }
+                self.expect(")")?;
+            }
+            modules.push((symbol, args));
+        }
+        Ok(modules)
+    }
+}
+
+/// Parses a word such as `"F+A(1, 2)"`; parameters must be constants.
+pub fn parse_word(text: &str) -> Result<Vec<Module>> {
+    let modules = Parser::new(text, &[])
+        .successor()
+        .with_context(|| format!("Invalid word '{text}'"))?;
+    Ok(modules
+        .into_iter()
+        .map(|(symbol, args)| Module {
+            symbol,
+            params: args.iter().map(|arg| arg.eval(&[])).collect(),
+        })
+        .collect())
+}
+
+/// A production such as `F -> F[+F]F`, written
+/// `[(weight)] predecessor[(params)] [: condition] -> successor`.
+///
+/// Rules sharing a predecessor are picked at random in proportion to their
+/// weight (1 by default), among those whose condition holds. Parametric
+/// rules like `A(x) : x > 1 -> F(x) [+A(x * 0.6)]` bind the module's
+/// parameters by position; a module only matches a rule with the same
+/// number of parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub predecessor: char,
+    pub weight: f32,
+    arity: usize,
+    condition: Option<Expr>,
+    successor: Vec<(char, Vec<Expr>)>,
+}
+
+impl Rule {
+    pub fn parse(text: &str) -> Result<Self> {
+        Self::parse_inner(text).with_context(|| format!("Invalid rule '{text}'"))
+    }
+
+    fn parse_inner(text: &str) -> Result<Self> {
+        let (left, right) = text
+            .split_once("->")
+            .ok_or_else(|| anyhow!("Expected '->'"))?;
+        let (head, condition) = match left.split_once(':') {
+            Some((head, condition)) => (head, Some(condition)),
+            None => (left, None),
+        };
+
+        let mut head = Parser::new(head, &[]);
+        let mut weight = 1.0;
+        if head.eat("(") {
+            weight = match head.expr()? {
+                Expr::Number(value) if value >= 0.0 => value,
+                _ => bail!("Weights must be non-negative numbers"),
+            };
+            head.expect(")")?;
+        }
+        let predecessor = head.peek().ok_or_else(|| anyhow!("Missing predecessor"))?;
+        head.pos += 1;
+        let mut formals = Vec::new();
+        if head.eat("(") {
+            loop {
+                formals.push(
+                    head.identifier()
+                        .ok_or_else(|| anyhow!("Expected a parameter name"))?,
+                );
+                if !head.eat(",") {
+                    break;
+                }
+            }
+            head.expect(")")?;
+        }
+        if let Some(extra) = head.peek() {
+            bail!("Unexpected '{extra}' after the predecessor");
+        }
+
+        let condition = match condition {
+            Some(text) => {
+                let mut parser = Parser::new(text, &formals);
+                let condition = parser.expr()?;
+                if let Some(extra) = parser.peek() {
+                    bail!("Unexpected '{extra}' in the condition");
+                }
+                Some(condition)
+            }
+            None => None,
+        };
+        let successor = Parser::new(right, &formals).successor()?;
+        Ok(Self {
+            predecessor,
+            weight,
+            arity: formals.len(),
+            condition,
+            successor,
+        })
+    }
+
+    fn matches(&self, module: &Module) -> bool {
+        module.symbol == self.predecessor
+            && module.params.len() == self.arity
+            && self
+                .condition
+                .as_ref()
+                .is_none_or(|condition| condition.eval(&module.params) != 0.0)
+    }
+
+    /// Appends the successor for `module`, stopping at `MAX_MODULES`.
+    fn apply(&self, module: &Module, out: &mut Vec<Module>) {
+        let room = MAX_MODULES.saturating_sub(out.len());
+        out.extend(
+            self.successor
+                .iter()
+                .take(room)
+                .map(|(symbol, args)| Module {
+                    symbol: *symbol,
+                    params: args.iter().map(|arg| arg.eval(&module.params)).collect(),
+                }),
+        );
+    }
+}
+
+/// An L-system: an axiom rewritten by production rules, then drawn with a
+/// `Turtle`.
+///
+/// Drawing commands, each taking an optional parameter:
+///
+/// ```text
+/// F, G   draw forward one step (or `step * param`)
+/// f      move forward one step without drawing
+/// + -    turn left / right by `angle` (or `param` degrees)
+/// |      turn around
+/// [ ]    save / restore the turtle state
+/// !      set the stroke width to `param` cells
+/// ```
+///
+/// Other symbols only take part in rewriting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LSystem {
+    pub axiom: Vec<Module>,
+    pub rules: Vec<Rule>,
+    /// Turning angle in degrees.
+    pub angle: f32,
+    /// Forward distance in cells.
+    pub step: f32,
+}
+
+impl LSystem {
+    pub fn new(axiom: &str, angle: f32) -> Result<Self> {
+        Ok(Self {
+            axiom: parse_word(axiom)?,
+            rules: Vec::new(),
+            angle,
+            step: 1.0,
+        })
+    }
+
+    pub fn with_rule(mut self, rule: &str) -> Result<Self> {
+        self.rules.push(Rule::parse(rule)?);
+        Ok(self)
+    }
+
+    pub fn with_step(self, step: f32) -> Self {
+        Self { step, ..self }
+    }
+
+    /// Rewrites every module of `word` once. Modules without a matching rule
+    /// are copied unchanged, and the result is cut off at `MAX_MODULES`.
+    pub fn expand(&self, word: &[Module], rng: &mut impl Rng) -> Vec<Module> {
+        self.rewrite(word, rng).0
+    }
+
+    /// `expand`, also reporting whether any module had a random choice
+    /// between rules.
+    fn rewrite(&self, word: &[Module], rng: &mut impl Rng) -> (Vec<Module>, bool) {
+        let mut out = Vec::with_capacity((word.len() * 2).min(MAX_MODULES));
+        let mut candidates = Vec::new();
+        let mut random = false;
+        for module in word {
+            if out.len() >= MAX_MODULES {
+                break;
+            }
+            candidates.clear();
+            candidates.extend(self.rules.iter().filter(|rule| rule.matches(module)));
+            let total: f32 = candidates.iter().map(|rule| rule.weight).sum();
+            let chosen = match candidates.len() {
+                0 => None,
+                1 => Some(candidates[0]),
+                _ if total <= 0.0 => Some(candidates[0]),
+                _ => {
+                    random = true;
+                    let mut pick = rng.gen_range(0.0..total);
+                    candidates
+                        .iter()
+                        .find(|rule| {
+                            pick -= rule.weight;
+                            pick < 0.0
+                        })
+                        .or(candidates.last())
+                        .copied()
+                }
+            };
+            match chosen {
+                Some(rule) => rule.apply(module, &mut out),
+                None => out.push(module.clone()),
+            }
+        }
+        (out, random)
+    }
+
+    /// The axiom expanded `iterations` times, stopping early once the word
+    /// reaches `MAX_MODULES` or stops changing.
+    pub fn generate(&self, iterations: u32, rng: &mut impl Rng) -> Vec<Module> {
+        let mut word = self.axiom.clone();
+        for _ in 0..iterations {
+            if word.len() >= MAX_MODULES {
+                break;
+            }
+            let (next, random) = self.rewrite(&word, rng);
+            // Without random choices, a word that came back unchanged will
+            // keep doing so.
+            let settled = !random && next == word;
+            word = next;
+            if settled {
+                break;
+            }
+        }
+        word
+    }
+
+    /// Draws `word` with `turtle` from its current state. `amount` (0–1) is
+    /// the share of the drawn length to trace, for animating growth; the
+    /// last segment is drawn partially.
+    pub fn draw(&self, turtle: &mut Turtle<'_, '_>, word: &[Module], amount: f32) {
+        let total = self.drawn_length(word);
+        let mut budget = total * amount.clamp(0.0, 1.0);
+        for module in word {
+            match module.symbol {
+                'F' | 'G' => {
+                    if budget <= 0.0 {
+                        break;
+                    }
+                    let distance = self.step * module.param().unwrap_or(1.0);
+                    let part = distance.abs().min(budget);
+                    budget -= part;
+                    turtle.forward(part.copysign(distance));
+                }
+                'f' => {
+                    let distance = self.step * module.param().unwrap_or(1.0);
+                    let was_down = turtle.is_pen_down();
+                    turtle.pen_up();
+                    turtle.forward(distance);
+                    if was_down {
+                        turtle.pen_down();
+                    }
+                }
+                '+' => turtle.left(module.param().unwrap_or(self.angle)),
+                '-' => turtle.right(module.param().unwrap_or(self.angle)),
+                '|' => turtle.left(180.0),
+                '[' => turtle.push(),
+                ']' => turtle.pop(),
+                '!' => {
+                    if let Some(width) = module.param() {
+                        turtle.set_stroke_width(width);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The bounding box, as `(min, max)` offsets from the start, of what
+    /// `draw` would trace for a turtle starting with `heading` degrees and
+    /// the turtle's default aspect, so a word can be scaled to fit.
+    pub fn extent(&self, word: &[Module], heading: f32) -> (Point, Point) {
+        let mut state = (Point::default(), heading);
+        let mut stack = Vec::new();
+        let (mut min, mut max) = (Point::default(), Point::default());
+        for module in word {
+            match module.symbol {
+                'F' | 'G' | 'f' => {
+                    let distance = self.step * module.param().unwrap_or(1.0);
+                    let radians = state.1.to_radians();
+                    state.0.x += radians.cos() * distance;
+                    state.0.y -= radians.sin() * distance * 0.5;
+                    min = Point::new(min.x.min(state.0.x), min.y.min(state.0.y));
+                    max = Point::new(max.x.max(state.0.x), max.y.max(state.0.y));
+                }
+                '+' => state.1 += module.param().unwrap_or(self.angle),
+                '-' => state.1 -= module.param().unwrap_or(self.angle),
+                '|' => state.1 += 180.0,
+                '[' => stack.push(state),
+                ']' => state = stack.pop().unwrap_or(state),
+                _ => {}
+            }
+        }
+        (min, max)
+    }
+
+    fn drawn_length(&self, word: &[Module]) -> f32 {
+        word.iter()
+            .filter(|module| matches!(module.symbol, 'F' | 'G'))
+            .map(|module| (self.step * module.param().unwrap_or(1.0)).abs())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn text(word: &[Module]) -> String {
+        word.iter().map(Module::to_string).collect()
+    }
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(1)
+    }
+
+    #[test]
+    fn parses_words_with_parameters() {
+        let word = parse_word("F + A(1, 2.5 * 2)[-B(-1)]").unwrap();
+        assert_eq!(text(&word), "F+A(1,5)[-B(-1)]");
+        assert!(parse_word("A(x)").is_err());
+        assert!(parse_word("A(1").is_err());
+        assert!(parse_word("A)").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        for rule in [
+            "F F+F",
+            "-> F",
+            "AB -> F",
+            "(-1) A -> F",
+            "(x) A -> F",
+            "A(x) -> F(y)",
+            "A(x) : x > -> F",
+            "A(x) : x 1 -> F",
+        ] {
+            assert!(Rule::parse(rule).is_err(), "{rule:?}");
+        }
+    }
+
+    #[test]
+    fn expands_deterministic_rules() {
+        // Lindenmayer's algae: word lengths follow the Fibonacci numbers.
+        let system = LSystem::new("A", 0.0)
+            .unwrap()
+            .with_rule("A -> AB")
+            .unwrap()
+            .with_rule("B -> A")
+            .unwrap();
+        let lengths: Vec<usize> = (0..7)
+            .map(|n| system.generate(n, &mut rng()).len())
+            .collect();
+        assert_eq!(lengths, vec![1, 2, 3, 5, 8, 13, 21]);
+        assert_eq!(text(&system.generate(3, &mut rng())), "ABAAB");
+    }
+
+    #[test]
+    fn evaluates_parametric_rules_and_conditions() {
+        let system = LSystem::new("A(4)", 0.0)
+            .unwrap()
+            .with_rule("A(x) : x > 1 && x != 3 -> F(x) A(x / 2)")
+            .unwrap()
+            .with_rule("A(x) : x <= 1 -> B(x ^ 2, -x)")
+            .unwrap();
+        assert_eq!(text(&system.generate(5, &mut rng())), "F(4)F(2)B(1,-1)");
+    }
+
+    #[test]
+    fn modules_only_match_rules_of_their_arity() {
+        let system = LSystem::new("A A(1)", 0.0)
+            .unwrap()
+            .with_rule("A -> B")
+            .unwrap()
+            .with_rule("A(x) -> C(x)")
+            .unwrap();
+        assert_eq!(text(&system.generate(1, &mut rng())), "BC(1)");
+    }
+
+    #[test]
+    fn picks_stochastic_rules_by_weight() {
+        let system = LSystem::new("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA", 0.0)
+            .unwrap()
+            .with_rule("(3) A -> B")
+            .unwrap()
+            .with_rule("(1) A -> C")
+            .unwrap()
+            .with_rule("(0) A -> D")
+            .unwrap();
+        let word = text(&system.generate(1, &mut rng()));
+        let count = |symbol| word.chars().filter(|&c| c == symbol).count();
+        assert!(count('B') > count('C') && count('C') > 0);
+        assert_eq!(count('D'), 0);
+        assert_eq!(
+            system.generate(1, &mut rng()),
+            system.generate(1, &mut rng())
+        );
+    }
+
+    #[test]
+    fn stops_once_the_word_settles() {
+        let system = LSystem::new("A", 0.0)
+            .unwrap()
+            .with_rule("(1) A -> A")
+            .unwrap()
+            .with_rule("(1) A -> B")
+            .unwrap();
+        assert_eq!(text(&system.generate(u32::MAX, &mut rng())), "B");
+
+        let fixed = LSystem::new("F", 0.0).unwrap().with_rule("F -> F").unwrap();
+        assert_eq!(text(&fixed.generate(u32::MAX, &mut rng())), "F");
+    }
+
+    #[test]
+    fn caps_the_word_length() {
+        let system = LSystem::new("A", 0.0)
+            .unwrap()
+            .with_rule(&format!("A -> {}", "A".repeat(300)))
+            .unwrap();
+        let word = system.generate(10, &mut rng());
+        assert_eq!(word.len(), MAX_MODULES);
+        assert_eq!(system.expand(&word, &mut rng()).len(), MAX_MODULES);
+    }
+
+    #[test]
+    fn measures_the_drawn_extent() {
+        let system = LSystem::new("F+F[-fF]", 90.0).unwrap().with_step(2.0);
+        let (min, max) = system.extent(&system.axiom, 0.0);
+        assert_eq!(min, Point::new(0.0, -1.0));
+        assert_eq!(max, Point::new(6.0, 0.0));
+        assert_eq!(system.drawn_length(&system.axiom), 6.0);
+    }
+}
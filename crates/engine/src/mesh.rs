@@ -0,0 +1,303 @@
+use std::{f32::consts::TAU, fs, path::Path};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+
+use crate::math3d::Vec3;
+
+/// An indexed triangle mesh. Triangles wind counter-clockwise when seen
+/// from the front.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    pub vertices: Vec<Vec3>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<Vec3>, triangles: Vec<[usize; 3]>) -> Result<Self> {
+        if let Some(index) = triangles.iter().flatten().find(|&&i| i >= vertices.len()) {
+            bail!(
+                "Triangle refers to vertex {index} but the mesh has {} vertices",
+                vertices.len()
+            );
+        }
+        Ok(Self {
+            vertices,
+            triangles,
+        })
+    }
+
+    /// Loads the vertices and faces of a Wavefront OBJ file.
+    pub fn load_obj(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read model at {}", path.display()))?;
+        Self::parse_obj(&source)
+            .with_context(|| format!("Failed to parse model at {}", path.display()))
+    }
+
+    /// Parses OBJ source. Only `v` and `f` statements are used; polygons are
+    /// split into triangle fans and texture/normal indices are ignored, as
+    /// are groups, materials and other statements.
+    pub fn parse_obj(source: &str) -> Result<Self> {
+        let mut vertices = Vec::new();
+        let mut triangles = Vec::new();
+        for (number, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("v") => {
+                    let mut coordinate = || -> Result<f32> {
+                        let field = fields.next().ok_or_else(|| {
+                            anyhow!("Line {}: vertex needs three coordinates", number + 1)
+                        })?;
+                        field.parse().map_err(|_| {
+                            anyhow!("Line {}: invalid coordinate '{field}'", number + 1)
+                        })
+                    };
+                    vertices.push(Vec3::new(coordinate()?, coordinate()?, coordinate()?));
+                }
+                Some("f") => {
+                    let indices = fields
+                        .map(|field| obj_index(field, vertices.len()))
+                        .collect::<Option<Vec<usize>>>()
+                        .ok_or_else(|| anyhow!("Line {}: invalid face index", number + 1))?;
+                    if indices.len() < 3 {
+                        bail!("Line {}: face needs at least three vertices", number + 1);
+                    }
+                    for i in 1..indices.len() - 1 {
+                        triangles.push([indices[0], indices[i], indices[i + 1]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if triangles.is_empty() {
+            bail!("Model has no faces");
+        }
+        Ok(Self {
+            vertices,
+            triangles,
+        })
+    }
+
+    /// An axis-aligned cube centred on the origin.
+    pub fn cube(size: f32) -> Self {
+        let h = size * 0.5;
+        let vertices = [
+            (-h, -h, -h),
+            (h, -h, -h),
+            (h, h, -h),
+            (-h, h, -h),
+            (-h, -h, h),
+            (h, -h, h),
+            (h, h, h),
+            (-h, h, h),
+        ]
+        .map(|(x, y, z)| Vec3::new(x, y, z))
+        .to_vec();
+        let quads = [
+            [4, 5, 6, 7],
+            [1, 0, 3, 2],
+            [5, 1, 2, 6],
+            [0, 4, 7, 3],
+            [7, 6, 2, 3],
+            [0, 1, 5, 4],
+        ];
+        let triangles = quads
+            .iter()
+            .flat_map(|&[a, b, c, d]| [[a, b, c], [a, c, d]])
+            .collect();
+        Self {
+            vertices,
+            triangles,
+        }
+    }
+
+    /// A torus around the y axis: `major_radius` to the centre of the tube,
+    /// `minor_radius` across it, with `rings` segments around the axis and
+    /// `sides` around the tube.
+    pub fn torus(major_radius: f32, minor_radius: f32, rings: usize, sides: usize) -> Self {
+        let (rings, sides) = (rings.max(3), sides.max(3));
+        let mut vertices = Vec::with_capacity(rings * sides);
+        for ring in 0..rings {
+            let (sin_u, cos_u) = (ring as f32 / rings as f32 * TAU).sin_cos();
+            for side in 0..sides {
+                let (sin_v, cos_v) = (side as f32 / sides as f32 * TAU).sin_cos();
+                let distance = major_radius + minor_radius * cos_v;
+                vertices.push(Vec3::new(
+                    distance * cos_u,
+                    minor_radius * sin_v,
+                    -distance * sin_u,
+                ));
+            }
+        }
+        let index = |ring: usize, side: usize| (ring % rings) * sides + side % sides;
+        let mut triangles = Vec::with_capacity(rings * sides * 2);
+        for ring in 0..rings {
+            for side in 0..sides {
+                let (a, b) = (index(ring, side), index(ring + 1, side));
+                let (c, d) = (index(ring + 1, side + 1), index(ring, side + 1));
+                triangles.push([a, b, c]);
+                triangles.push([a, c, d]);
+            }
+        }
+        Self {
+            vertices,
+            triangles,
+        }
+    }
+
+    /// The smallest and largest coordinates of the vertices.
+    pub fn bounds(&self) -> Option<(Vec3, Vec3)> {
+        let first = *self.vertices.first()?;
+        Some(
+            self.vertices
+                .iter()
+                .fold((first, first), |(min, max), &v| (min.min(v), max.max(v))),
+        )
+    }
+
+    /// A copy centred on the origin and scaled so its largest dimension is
+    /// `size`, handy for models authored at arbitrary scales.
+    pub fn fitted(&self, size: f32) -> Mesh {
+        let Some((min, max)) = self.bounds() else {
+            return self.clone();
+        };
+        let center = (min + max) * 0.5;
+        let extent = max - min;
+        let largest = extent.x.max(extent.y).max(extent.z);
+        let scale = if largest > f32::EPSILON {
+            size / largest
+        } else {
+            1.0
+        };
+        Mesh {
+            vertices: self
+                .vertices
+                .iter()
+                .map(|&v| (v - center) * scale)
+                .collect(),
+            triangles: self.triangles.clone(),
+        }
+    }
+
+    /// Unit normals of every triangle, facing the side it winds
+    /// counter-clockwise towards.
+    pub fn face_normals(&self) -> Vec<Vec3> {
+        self.triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                let (a, b, c) = (self.vertices[a], self.vertices[b], self.vertices[c]);
+                (b - a).cross(c - a).normalized()
+            })
+            .collect()
+    }
+
+    /// Unit normals of every vertex, averaged from the triangles sharing it
+    /// weighted by their area, for smooth shading.
+    pub fn vertex_normals(&self) -> Vec<Vec3> {
+        let mut normals = vec![Vec3::ZERO; self.vertices.len()];
+        for &[a, b, c] in &self.triangles {
+            let (pa, pb, pc) = (self.vertices[a], self.vertices[b], self.vertices[c]);
+            let weighted = (pb - pa).cross(pc - pa);
+            for index in [a, b, c] {
+                normals[index] += weighted;
+            }
+        }
+        normals.into_iter().map(Vec3::normalized).collect()
+    }
+}
+
+/// Resolves a face field (`7`, `7/2`, `7//3`, `-1`...) to a zero-based
+/// vertex index. Negative indices count back from the latest vertex.
+fn obj_index(field: &str, vertex_count: usize) -> Option<usize> {
+    let index: i64 = field.split('/').next()?.parse().ok()?;
+    let resolved = match index {
+        0 => return None,
+        i if i > 0 => i - 1,
+        i => vertex_count as i64 + i,
+    };
+    (0..vertex_count as i64)
+        .contains(&resolved)
+        .then_some(resolved as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vertices_and_fans_polygons() {
+        let mesh = Mesh::parse_obj(
+            "# a unit square\n\
+             o square\n\
+             v 0 0 0\n\
+             v 1 0 0 # trailing comment\n\
+             v 1 1 0\n\
+             v 0 1.5 0\n\
+             vn 0 0 1\n\
+             usemtl plain\n\
+             f 1/1/1 2//1 3 4\n",
+        )
+        .unwrap();
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.vertices[3], Vec3::new(0.0, 1.5, 0.0));
+        assert_eq!(mesh.triangles, vec![[0, 1, 2], [0, 2, 3]]);
+    }
+
+    #[test]
+    fn resolves_negative_indices() {
+        let mesh = Mesh::parse_obj("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n").unwrap();
+        assert_eq!(mesh.triangles, vec![[0, 1, 2]]);
+        assert_eq!(obj_index("-1", 3), Some(2));
+        assert_eq!(obj_index("-4", 3), None);
+        assert_eq!(obj_index("0", 3), None);
+        assert_eq!(obj_index("4", 3), None);
+        assert_eq!(obj_index("2/5/7", 3), Some(1));
+    }
+
+    #[test]
+    fn rejects_malformed_obj() {
+        for source in [
+            "",
+            "v 0 0 0\n",
+            "v 0 0\nf 1 1 1\n",
+            "v 0 0 x\n",
+            "v 0 0 0\nv 1 0 0\nf 1 2\n",
+            "v 0 0 0\nv 1 0 0\nf 1 2 3\n",
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 a\n",
+        ] {
+            assert!(Mesh::parse_obj(source).is_err(), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn new_checks_indices() {
+        let vertices = vec![Vec3::ZERO; 3];
+        assert!(Mesh::new(vertices.clone(), vec![[0, 1, 2]]).is_ok());
+        assert!(Mesh::new(vertices, vec![[0, 1, 3]]).is_err());
+    }
+
+    #[test]
+    fn cube_faces_point_outwards() {
+        let cube = Mesh::cube(2.0);
+        assert_eq!(cube.triangles.len(), 12);
+        for (normal, &[a, b, c]) in cube.face_normals().iter().zip(&cube.triangles) {
+            let centroid = (cube.vertices[a] + cube.vertices[b] + cube.vertices[c]) * (1.0 / 3.0);
+            assert!(normal.dot(centroid) > 0.0);
+        }
+    }
+
+    #[test]
+    fn fitted_centres_and_scales() {
+        let mesh = Mesh::new(
+            vec![Vec3::new(2.0, 2.0, 2.0), Vec3::new(6.0, 4.0, 2.0)],
+            Vec::new(),
+        )
+        .unwrap()
+        .fitted(2.0);
+        let (min, max) = mesh.bounds().unwrap();
+        assert_eq!(min, Vec3::new(-1.0, -0.5, 0.0));
+        assert_eq!(max, Vec3::new(1.0, 0.5, 0.0));
+    }
+}
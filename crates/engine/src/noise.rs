@@ -0,0 +1,642 @@
+//! Seedable coherent noise for procedural scenes.
+//!
+//! Sources (`Perlin`, `OpenSimplex`, `Worley`) and the `Fractal` and
+//! `DomainWarp` combinators implement `Noise2`, `Noise3` and `Noise4`, so they
+//! nest freely. Animate a 2D field by feeding time as the third coordinate,
+//! and seed sources from `Context::seed` to make a scene reproducible.
+
+use std::f32::consts::FRAC_1_SQRT_2;
+
+/// Noise sampled in two dimensions.
+pub trait Noise2 {
+    fn noise2(&self, x: f32, y: f32) -> f32;
+}
+
+/// Noise sampled in three dimensions.
+pub trait Noise3 {
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32;
+}
+
+/// Noise sampled in four dimensions, e.g. a looping 3D field.
+pub trait Noise4 {
+    fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32;
+}
+
+/// Scales bringing each source's output close to -1..1, indexed by dimension.
+const PERLIN_SCALE: [f32; 5] = [0.0, 0.0, 1.41, 1.0, 0.9];
+const SIMPLEX_SCALE: [f32; 5] = [0.0, 0.0, 98.0, 76.0, 62.0];
+
+/// Unit gradients for 2D lattice points.
+const GRADIENTS_2D: [[f32; 2]; 8] = [
+    [1.0, 0.0],
+    [-1.0, 0.0],
+    [0.0, 1.0],
+    [0.0, -1.0],
+    [FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+    [-FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+    [FRAC_1_SQRT_2, -FRAC_1_SQRT_2],
+    [-FRAC_1_SQRT_2, -FRAC_1_SQRT_2],
+];
+
+/// Mixes integer lattice coordinates with the seed into a well-distributed hash.
+fn hash<const N: usize>(seed: u64, cell: [i32; N]) -> u64 {
+    let mut h = seed ^ 0x9E37_79B9_7F4A_7C15;
+    for c in cell {
+        h = mix(h ^ (c as u32 as u64).wrapping_mul(0xD6E8_FEB8_6659_FD93));
+    }
+    h
+}
+
+/// SplitMix64's finaliser.
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Dot product of the lattice point's pseudo-random gradient with `offset`.
+/// 2D uses eight compass directions; higher dimensions use the midpoints of
+/// the hypercube's edges (12 in 3D, 32 in 4D), as in improved Perlin noise.
+fn gradient_dot<const N: usize>(h: u64, offset: [f32; N]) -> f32 {
+    if N == 2 {
+        let g = GRADIENTS_2D[(h % 8) as usize];
+        return g[0] * offset[0] + g[1] * offset[1];
+    }
+    let zero_axis = (h % N as u64) as usize;
+    let mut signs = h >> 8;
+    let mut total = 0.0;
+    for (axis, &value) in offset.iter().enumerate() {
+        if axis == zero_axis {
+            continue;
+        }
+        total += if signs & 1 == 0 { value } else { -value };
+        signs >>= 1;
+    }
+    total
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Classic gradient noise on a square lattice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Perlin {
+    seed: u64,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    fn sample<const N: usize>(&self, p: [f32; N]) -> f32 {
+        (self.raw(p) * PERLIN_SCALE[N]).clamp(-1.0, 1.0)
+    }
+
+    fn raw<const N: usize>(&self, p: [f32; N]) -> f32 {
+        let base = p.map(f32::floor);
+        let mut frac = [0.0; N];
+        let mut cell = [0; N];
+        for axis in 0..N {
+            frac[axis] = p[axis] - base[axis];
+            cell[axis] = base[axis] as i32;
+        }
+        let faded = frac.map(fade);
+        let mut total = 0.0;
+        for corner in 0..1usize << N {
+            let mut weight = 1.0;
+            let mut lattice = cell;
+            let mut offset = frac;
+            for axis in 0..N {
+                if corner >> axis & 1 == 1 {
+                    lattice[axis] += 1;
+                    offset[axis] -= 1.0;
+                    weight *= faded[axis];
+                } else {
+                    weight *= 1.0 - faded[axis];
+                }
+            }
+            total += weight * gradient_dot(hash(self.seed, lattice), offset);
+        }
+        total
+    }
+}
+
+/// Gradient noise on a simplex lattice in the manner of OpenSimplex2: the
+/// N+1 corners of the simplex holding the point each contribute a gradient
+/// weighted by `(r² - d²)⁴`. With `r² = 0.5` every kernel fades out before
+/// the simplex's far side, so no other lattice point can reach it. Smoother
+/// and less grid-aligned than `Perlin`, and cheaper in higher dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenSimplex {
+    seed: u64,
+}
+
+impl OpenSimplex {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    fn sample<const N: usize>(&self, p: [f32; N]) -> f32 {
+        (self.raw(p) * SIMPLEX_SCALE[N]).clamp(-1.0, 1.0)
+    }
+
+    fn raw<const N: usize>(&self, p: [f32; N]) -> f32 {
+        let n = N as f32;
+        let skew = ((n + 1.0).sqrt() - 1.0) / n;
+        let unskew = (1.0 - 1.0 / (n + 1.0).sqrt()) / n;
+
+        let stretch = p.iter().sum::<f32>() * skew;
+        let skewed = p.map(|v| v + stretch);
+        let mut vertex = skewed.map(|v| v.floor() as i32);
+        // The simplex's corners are reached by stepping along the axes in
+        // order of decreasing offset within the skewed cell.
+        let mut order: [usize; N] = std::array::from_fn(|axis| axis);
+        order.sort_unstable_by(|&a, &b| {
+            let offset = |axis: usize| skewed[axis] - vertex[axis] as f32;
+            offset(b).total_cmp(&offset(a))
+        });
+        let mut total = 0.0;
+        for step in 0..=N {
+            if step > 0 {
+                vertex[order[step - 1]] += 1;
+            }
+            let squish = vertex.iter().map(|&v| v as f32).sum::<f32>() * unskew;
+            let mut offset = [0.0; N];
+            let mut distance_squared = 0.0;
+            for axis in 0..N {
+                offset[axis] = p[axis] - (vertex[axis] as f32 - squish);
+                distance_squared += offset[axis] * offset[axis];
+            }
+            let falloff = 0.5 - distance_squared;
+            if falloff <= 0.0 {
+                continue;
+            }
+            let falloff = falloff * falloff;
+            total += falloff * falloff * gradient_dot(hash(self.seed, vertex), offset);
+        }
+        total
+    }
+}
+
+/// How `Worley` measures the distance to feature points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WorleyDistance {
+    #[default]
+    Euclidean,
+    /// Sum of axis distances, giving diamond-shaped cells.
+    Manhattan,
+    /// Largest axis distance, giving square cells.
+    Chebyshev,
+}
+
+/// Which distance `Worley` returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WorleyOutput {
+    /// Distance to the nearest feature point: round cells, dark at their centres.
+    #[default]
+    F1,
+    /// Distance to the second nearest point.
+    F2,
+    /// `F2 - F1`, which is zero along cell borders: a network of cracks.
+    F2MinusF1,
+}
+
+/// Cellular noise: one jittered feature point per lattice cell, returning
+/// distances in cell units (0 to about 1 for `F1`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Worley {
+    seed: u64,
+    pub distance: WorleyDistance,
+    pub output: WorleyOutput,
+    /// How far feature points stray from their cell centres, 0–1.
+    pub jitter: f32,
+}
+
+impl Worley {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            distance: WorleyDistance::default(),
+            output: WorleyOutput::default(),
+            jitter: 1.0,
+        }
+    }
+
+    pub fn with_distance(self, distance: WorleyDistance) -> Self {
+        Self { distance, ..self }
+    }
+
+    pub fn with_output(self, output: WorleyOutput) -> Self {
+        Self { output, ..self }
+    }
+
+    fn sample<const N: usize>(&self, p: [f32; N]) -> f32 {
+        let cell = p.map(|v| v.floor() as i32);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let (mut f1, mut f2) = (f32::INFINITY, f32::INFINITY);
+        for neighbour in 0..3usize.pow(N as u32) {
+            let mut lattice = cell;
+            let mut code = neighbour;
+            for value in lattice.iter_mut() {
+                *value += (code % 3) as i32 - 1;
+                code /= 3;
+            }
+            let mut h = hash(self.seed, lattice);
+            let mut distance = 0.0f32;
+            for axis in 0..N {
+                h = mix(h);
+                let random = (h >> 40) as f32 / (1u64 << 24) as f32;
+                let feature = lattice[axis] as f32 + 0.5 + (random - 0.5) * jitter;
+                let delta = (feature - p[axis]).abs();
+                distance = match self.distance {
+                    WorleyDistance::Euclidean => distance + delta * delta,
+                    WorleyDistance::Manhattan => distance + delta,
+                    WorleyDistance::Chebyshev => distance.max(delta),
+                };
+            }
+            if self.distance == WorleyDistance::Euclidean {
+                distance = distance.sqrt();
+            }
+            if distance < f1 {
+                f2 = f1;
+                f1 = distance;
+            } else if distance < f2 {
+                f2 = distance;
+            }
+        }
+        match self.output {
+            WorleyOutput::F1 => f1,
+            WorleyOutput::F2 => f2,
+            WorleyOutput::F2MinusF1 => f2 - f1,
+        }
+    }
+}
+
+/// How `Fractal` combines its octaves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FractalKind {
+    /// Fractal Brownian motion: a plain weighted sum, in -1..1.
+    #[default]
+    Fbm,
+    /// Sum of absolute values, billowy like smoke, in 0..1.
+    Turbulence,
+    /// Sum of inverted absolute values squared, sharp crests like
+    /// mountain ridges, in 0..1.
+    Ridged,
+}
+
+/// Layers `octaves` copies of `source`, each `lacunarity` times the
+/// frequency and `gain` times the amplitude of the one before.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fractal<N> {
+    pub source: N,
+    pub kind: FractalKind,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl<N> Fractal<N> {
+    pub fn new(source: N, kind: FractalKind) -> Self {
+        Self {
+            source,
+            kind,
+            octaves: 5,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    pub fn fbm(source: N) -> Self {
+        Self::new(source, FractalKind::Fbm)
+    }
+
+    pub fn turbulence(source: N) -> Self {
+        Self::new(source, FractalKind::Turbulence)
+    }
+
+    pub fn ridged(source: N) -> Self {
+        Self::new(source, FractalKind::Ridged)
+    }
+
+    pub fn with_octaves(self, octaves: u32) -> Self {
+        Self { octaves, ..self }
+    }
+
+    pub fn with_lacunarity(self, lacunarity: f32) -> Self {
+        Self { lacunarity, ..self }
+    }
+
+    pub fn with_gain(self, gain: f32) -> Self {
+        Self { gain, ..self }
+    }
+
+    /// Sums `sample(frequency, shift)` over the octaves. `shift` moves each
+    /// octave so lattice points of different octaves do not line up.
+    fn accumulate(&self, sample: impl Fn(f32, f32) -> f32) -> f32 {
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        let (mut total, mut norm) = (0.0, 0.0);
+        for octave in 0..self.octaves.max(1) {
+            let value = sample(frequency, octave as f32 * 19.19);
+            total += amplitude
+                * match self.kind {
+                    FractalKind::Fbm => value,
+                    FractalKind::Turbulence => value.abs(),
+                    FractalKind::Ridged => (1.0 - value.abs()).powi(2),
+                };
+            norm += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        if norm > 0.0 {
+            total / norm
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Displaces the coordinates fed to `source` by `amount` times `warp`,
+/// sampled at offsets per axis, giving swirling, marbled fields.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DomainWarp<N, W> {
+    pub source: N,
+    pub warp: W,
+    pub amount: f32,
+}
+
+impl<N, W> DomainWarp<N, W> {
+    pub fn new(source: N, warp: W, amount: f32) -> Self {
+        Self {
+            source,
+            warp,
+            amount,
+        }
+    }
+}
+
+/// Offsets decorrelating the warp applied to each axis.
+const WARP_OFFSETS: [f32; 4] = [0.0, 5.2, 9.7, 13.3];
+
+macro_rules! impl_noise {
+    ($type:ident) => {
+        impl Noise2 for $type {
+            fn noise2(&self, x: f32, y: f32) -> f32 {
+                self.sample([x, y])
+            }
+        }
+
+        impl Noise3 for $type {
+            fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+                self.sample([x, y, z])
+            }
+        }
+
+        impl Noise4 for $type {
+            fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+                self.sample([x, y, z, w])
+            }
+        }
+    };
+}
+
+/// Forwards to boxed or borrowed noise, so sources chosen at runtime
+/// (`Box<dyn Noise3 + Sync>`) can still be combined.
+macro_rules! impl_forward {
+    ($trait:ident, $method:ident, $($arg:ident),+) => {
+        impl<T: $trait + ?Sized> $trait for Box<T> {
+            fn $method(&self, $($arg: f32),+) -> f32 {
+                (**self).$method($($arg),+)
+            }
+        }
+
+        impl<T: $trait + ?Sized> $trait for &T {
+            fn $method(&self, $($arg: f32),+) -> f32 {
+                (**self).$method($($arg),+)
+            }
+        }
+    };
+}
+
+impl_forward!(Noise2, noise2, x, y);
+impl_forward!(Noise3, noise3, x, y, z);
+impl_forward!(Noise4, noise4, x, y, z, w);
+
+impl_noise!(Perlin);
+impl_noise!(OpenSimplex);
+impl_noise!(Worley);
+
+impl<N: Noise2> Noise2 for Fractal<N> {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        self.accumulate(|f, s| self.source.noise2(x * f + s, y * f + s))
+    }
+}
+
+impl<N: Noise3> Noise3 for Fractal<N> {
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        self.accumulate(|f, s| self.source.noise3(x * f + s, y * f + s, z * f + s))
+    }
+}
+
+impl<N: Noise4> Noise4 for Fractal<N> {
+    fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        self.accumulate(|f, s| {
+            self.source
+                .noise4(x * f + s, y * f + s, z * f + s, w * f + s)
+        })
+    }
+}
+
+impl<N: Noise2, W: Noise2> Noise2 for DomainWarp<N, W> {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        let [a, b, ..] = WARP_OFFSETS;
+        let dx = self.warp.noise2(x + a, y + a);
+        let dy = self.warp.noise2(x + b, y + b);
+        self.source
+            .noise2(x + dx * self.amount, y + dy * self.amount)
+    }
+}
+
+impl<N: Noise3, W: Noise3> Noise3 for DomainWarp<N, W> {
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let [a, b, c, _] = WARP_OFFSETS;
+        let dx = self.warp.noise3(x + a, y + a, z + a);
+        let dy = self.warp.noise3(x + b, y + b, z + b);
+        let dz = self.warp.noise3(x + c, y + c, z + c);
+        self.source.noise3(
+            x + dx * self.amount,
+            y + dy * self.amount,
+            z + dz * self.amount,
+        )
+    }
+}
+
+impl<N: Noise4, W: Noise4> Noise4 for DomainWarp<N, W> {
+    fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        let [a, b, c, d] = WARP_OFFSETS;
+        let dx = self.warp.noise4(x + a, y + a, z + a, w + a);
+        let dy = self.warp.noise4(x + b, y + b, z + b, w + b);
+        let dz = self.warp.noise4(x + c, y + c, z + c, w + c);
+        let dw = self.warp.noise4(x + d, y + d, z + d, w + d);
+        self.source.noise4(
+            x + dx * self.amount,
+            y + dy * self.amount,
+            z + dz * self.amount,
+            w + dw * self.amount,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic points spread over -20..20 in each axis.
+    fn points(count: usize) -> Vec<[f32; 4]> {
+        let mut state = 1;
+        let mut next = || {
+            state = mix(state);
+            (state >> 40) as f32 / (1 << 24) as f32 * 40.0 - 20.0
+        };
+        (0..count)
+            .map(|_| [next(), next(), next(), next()])
+            .collect()
+    }
+
+    /// Smallest and largest of `values`.
+    fn extremes(values: impl Iterator<Item = f32>) -> (f32, f32) {
+        values.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+            (min.min(v), max.max(v))
+        })
+    }
+
+    /// The scaled, unclamped output should nearly fill -1..1 without
+    /// spilling far past it.
+    fn assert_normalised(name: &str, (min, max): (f32, f32)) {
+        assert!(
+            min >= -1.05 && max <= 1.05,
+            "{name} exceeds -1..1: {min}..{max}"
+        );
+        assert!(
+            min <= -0.7 && max >= 0.7,
+            "{name} is too flat: {min}..{max}"
+        );
+    }
+
+    #[test]
+    fn perlin_scales_fill_the_unit_range() {
+        let noise = Perlin::new(7);
+        let points = points(50_000);
+        let scaled =
+            |values: Vec<f32>, n: usize| extremes(values.into_iter().map(|v| v * PERLIN_SCALE[n]));
+        assert_normalised(
+            "2D",
+            scaled(points.iter().map(|p| noise.raw([p[0], p[1]])).collect(), 2),
+        );
+        assert_normalised(
+            "3D",
+            scaled(
+                points
+                    .iter()
+                    .map(|p| noise.raw([p[0], p[1], p[2]]))
+                    .collect(),
+                3,
+            ),
+        );
+        assert_normalised(
+            "4D",
+            scaled(points.iter().map(|&p| noise.raw(p)).collect(), 4),
+        );
+    }
+
+    #[test]
+    fn simplex_scales_fill_the_unit_range() {
+        let noise = OpenSimplex::new(7);
+        let points = points(50_000);
+        let scaled =
+            |values: Vec<f32>, n: usize| extremes(values.into_iter().map(|v| v * SIMPLEX_SCALE[n]));
+        assert_normalised(
+            "2D",
+            scaled(points.iter().map(|p| noise.raw([p[0], p[1]])).collect(), 2),
+        );
+        assert_normalised(
+            "3D",
+            scaled(
+                points
+                    .iter()
+                    .map(|p| noise.raw([p[0], p[1], p[2]]))
+                    .collect(),
+                3,
+            ),
+        );
+        assert_normalised(
+            "4D",
+            scaled(points.iter().map(|&p| noise.raw(p)).collect(), 4),
+        );
+    }
+
+    /// Sums the kernels of every lattice point within a step of the skewed
+    /// cell, which the simplex walk must match.
+    fn simplex_scan<const N: usize>(seed: u64, p: [f32; N]) -> f32 {
+        let n = N as f32;
+        let skew = ((n + 1.0).sqrt() - 1.0) / n;
+        let unskew = (1.0 - 1.0 / (n + 1.0).sqrt()) / n;
+        let stretch = p.iter().sum::<f32>() * skew;
+        let base = p.map(|v| (v + stretch).floor() as i32);
+        let mut total = 0.0;
+        for neighbour in 0..4usize.pow(N as u32) {
+            let mut vertex = base;
+            let mut code = neighbour;
+            for value in vertex.iter_mut() {
+                *value += (code % 4) as i32 - 1;
+                code /= 4;
+            }
+            let squish = vertex.iter().map(|&v| v as f32).sum::<f32>() * unskew;
+            let mut offset = [0.0; N];
+            let mut distance_squared = 0.0;
+            for axis in 0..N {
+                offset[axis] = p[axis] - (vertex[axis] as f32 - squish);
+                distance_squared += offset[axis] * offset[axis];
+            }
+            let falloff = (0.5 - distance_squared).max(0.0);
+            total += falloff.powi(4) * gradient_dot(hash(seed, vertex), offset);
+        }
+        total
+    }
+
+    #[test]
+    fn simplex_walk_reaches_every_contributing_vertex() {
+        let noise = OpenSimplex::new(3);
+        for p in points(2_000) {
+            let close = |a: f32, b: f32| (a - b).abs() < 1e-6;
+            assert!(close(
+                noise.raw([p[0], p[1]]),
+                simplex_scan(3, [p[0], p[1]])
+            ));
+            assert!(close(
+                noise.raw([p[0], p[1], p[2]]),
+                simplex_scan(3, [p[0], p[1], p[2]])
+            ));
+            assert!(close(noise.raw(p), simplex_scan(3, p)));
+        }
+    }
+
+    #[test]
+    fn sources_are_seeded() {
+        let p = [1.3, 2.7, 0.4];
+        assert_eq!(
+            Perlin::new(1).noise3(p[0], p[1], p[2]),
+            Perlin::new(1).noise3(p[0], p[1], p[2])
+        );
+        assert_ne!(
+            Perlin::new(1).noise3(p[0], p[1], p[2]),
+            Perlin::new(2).noise3(p[0], p[1], p[2])
+        );
+        assert_ne!(
+            OpenSimplex::new(1).noise3(p[0], p[1], p[2]),
+            OpenSimplex::new(2).noise3(p[0], p[1], p[2])
+        );
+    }
+}
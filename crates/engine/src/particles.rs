@@ -0,0 +1,344 @@
+use std::ops::Range;
+
+use rand::Rng;
+
+use crate::color::Rgba;
+use crate::draw::Canvas;
+use crate::fill::{mix_stops, pick_stop};
+use crate::path::Point;
+use crate::plot::PlotPoint;
+
+/// Glyph drawn in ASCII mode when a system has no glyph ramp.
+const DEFAULT_PARTICLE_GLYPH: char = '*';
+
+/// A single live particle. Positions and velocities are in the canvas'
+/// local cell coordinates, velocities per second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub position: Point,
+    pub velocity: Point,
+    /// Seconds since the particle was spawned.
+    pub age: f32,
+    /// Seconds the particle lives for.
+    pub lifetime: f32,
+}
+
+impl Particle {
+    /// How far through its life the particle is, 0 at birth and 1 at death.
+    pub fn life_fraction(&self) -> f32 {
+        if self.lifetime <= 0.0 {
+            1.0
+        } else {
+            (self.age / self.lifetime).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Where an emitter spawns particles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmitterShape {
+    Point(Point),
+    /// Anywhere along the segment from `start` to `end`.
+    Line {
+        start: Point,
+        end: Point,
+    },
+    /// Anywhere on the circle's outline.
+    Circle {
+        center: Point,
+        radius: f32,
+    },
+    /// Anywhere inside the rectangle with top-left corner `(x, y)`.
+    Area {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+}
+
+impl EmitterShape {
+    fn sample(&self, rng: &mut impl Rng) -> Point {
+        match *self {
+            EmitterShape::Point(point) => point,
+            EmitterShape::Line { start, end } => start.lerp(end, rng.gen()),
+            EmitterShape::Circle { center, radius } => {
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                // Halved vertically so the outline looks round in tall cells.
+                Point::new(
+                    center.x + angle.cos() * radius,
+                    center.y + angle.sin() * radius * 0.5,
+                )
+            }
+            EmitterShape::Area {
+                x,
+                y,
+                width,
+                height,
+            } => Point::new(x + rng.gen::<f32>() * width, y + rng.gen::<f32>() * height),
+        }
+    }
+}
+
+/// Spawns particles continuously at `rate` and in one-off bursts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Emitter {
+    pub shape: EmitterShape,
+    /// Particles per second; 0 only emits bursts.
+    pub rate: f32,
+    /// Launch direction in degrees counter-clockwise from east, so 90 is up.
+    pub direction: f32,
+    /// Total width in degrees of the cone particles launch into; 360 sends
+    /// them every way.
+    pub spread: f32,
+    /// Launch speed in cells per second, picked uniformly.
+    pub speed: Range<f32>,
+    /// Lifetime in seconds, picked uniformly.
+    pub lifetime: Range<f32>,
+    /// Scale applied to vertical launch speed, 0.5 by default so bursts are
+    /// not stretched by tall cells.
+    pub aspect: f32,
+    /// Whether the continuous `rate` is running; bursts fire regardless.
+    pub active: bool,
+    accumulator: f32,
+    pending: usize,
+}
+
+impl Emitter {
+    pub fn new(shape: EmitterShape) -> Self {
+        Self {
+            shape,
+            rate: 10.0,
+            direction: 90.0,
+            spread: 30.0,
+            speed: 5.0..10.0,
+            lifetime: 1.0..2.0,
+            aspect: 0.5,
+            active: true,
+            accumulator: 0.0,
+            pending: 0,
+        }
+    }
+
+    pub fn with_rate(self, rate: f32) -> Self {
+        Self { rate, ..self }
+    }
+
+    pub fn with_direction(self, direction: f32, spread: f32) -> Self {
+        Self {
+            direction,
+            spread,
+            ..self
+        }
+    }
+
+    pub fn with_speed(self, speed: Range<f32>) -> Self {
+        Self { speed, ..self }
+    }
+
+    pub fn with_lifetime(self, lifetime: Range<f32>) -> Self {
+        Self { lifetime, ..self }
+    }
+
+    /// Queues `count` particles to spawn on the next update.
+    pub fn burst(&mut self, count: usize) {
+        self.pending += count;
+    }
+
+    /// How many particles to spawn for `delta_time` seconds.
+    fn due(&mut self, delta_time: f32) -> usize {
+        let mut count = std::mem::take(&mut self.pending);
+        if self.active && self.rate > 0.0 {
+            self.accumulator += self.rate * delta_time;
+            let whole = self.accumulator.floor();
+            self.accumulator -= whole;
+            count += whole as usize;
+        }
+        count
+    }
+
+    fn spawn(&self, rng: &mut impl Rng) -> Particle {
+        let half_spread = self.spread.abs() * 0.5;
+        let offset = if half_spread > 0.0 {
+            rng.gen_range(-half_spread..=half_spread)
+        } else {
+            0.0
+        };
+        let radians = (self.direction + offset).to_radians();
+        let speed = sample_range(&self.speed, rng);
+        Particle {
+            position: self.shape.sample(rng),
+            velocity: Point::new(radians.cos() * speed, -radians.sin() * speed * self.aspect),
+            age: 0.0,
+            lifetime: sample_range(&self.lifetime, rng),
+        }
+    }
+}
+
+fn sample_range(range: &Range<f32>, rng: &mut impl Rng) -> f32 {
+    if range.start < range.end {
+        rng.gen_range(range.clone())
+    } else {
+        range.start
+    }
+}
+
+/// Accelerations applied to every particle on each update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Force {
+    /// Constant acceleration in cells per second squared; positive `y`
+    /// pulls down the screen.
+    Gravity(Point),
+    /// Slows particles by this fraction of their speed per second.
+    Drag(f32),
+    /// Pulls particles towards `position` with `strength` over distance
+    /// squared; a negative strength repels.
+    Attractor { position: Point, strength: f32 },
+}
+
+/// Emitters, forces and the particles they produce.
+///
+/// Call `update` with a fixed step (see `time::FixedStep`) and draw with
+/// `ParticleSystem::draw`. Particles age through `colors` and, in ASCII
+/// mode, `glyphs`; both ramps are spread evenly over a particle's life.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleSystem {
+    pub emitters: Vec<Emitter>,
+    pub forces: Vec<Force>,
+    /// Colour over life; empty uses the canvas' foreground colour.
+    pub colors: Vec<Rgba>,
+    /// ASCII glyph over life; empty draws `*`.
+    pub glyphs: Vec<char>,
+    /// Spawning stops while this many particles are alive.
+    pub max_particles: usize,
+    particles: Vec<Particle>,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Self {
+            emitters: Vec::new(),
+            forces: Vec::new(),
+            colors: Vec::new(),
+            glyphs: Vec::new(),
+            max_particles: 10_000,
+            particles: Vec::new(),
+        }
+    }
+
+    pub fn with_emitter(mut self, emitter: Emitter) -> Self {
+        self.emitters.push(emitter);
+        self
+    }
+
+    pub fn with_force(mut self, force: Force) -> Self {
+        self.forces.push(force);
+        self
+    }
+
+    pub fn with_colors(self, colors: Vec<Rgba>) -> Self {
+        Self { colors, ..self }
+    }
+
+    pub fn with_glyphs(self, glyphs: impl Into<String>) -> Self {
+        Self {
+            glyphs: glyphs.into().chars().collect(),
+            ..self
+        }
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// Adds a particle directly, ignoring `max_particles`.
+    pub fn push(&mut self, particle: Particle) {
+        self.particles.push(particle);
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// Advances the simulation by `delta_time` seconds: ages and removes
+    /// particles, applies forces, moves them and spawns new ones.
+    pub fn update(&mut self, delta_time: f32, rng: &mut impl Rng) {
+        let forces = &self.forces;
+        self.particles.retain_mut(|particle| {
+            particle.age += delta_time;
+            if particle.age >= particle.lifetime {
+                return false;
+            }
+            let (mut ax, mut ay) = (0.0, 0.0);
+            for force in forces {
+                match *force {
+                    Force::Gravity(g) => {
+                        ax += g.x;
+                        ay += g.y;
+                    }
+                    Force::Drag(_) => {}
+                    Force::Attractor { position, strength } => {
+                        let (dx, dy) = (
+                            position.x - particle.position.x,
+                            position.y - particle.position.y,
+                        );
+                        let distance_squared = (dx * dx + dy * dy).max(1.0);
+                        let scale = strength / (distance_squared * distance_squared.sqrt());
+                        ax += dx * scale;
+                        ay += dy * scale;
+                    }
+                }
+            }
+            particle.velocity.x += ax * delta_time;
+            particle.velocity.y += ay * delta_time;
+            for force in forces {
+                if let Force::Drag(amount) = *force {
+                    let keep = (-amount * delta_time).exp();
+                    particle.velocity.x *= keep;
+                    particle.velocity.y *= keep;
+                }
+            }
+            particle.position.x += particle.velocity.x * delta_time;
+            particle.position.y += particle.velocity.y * delta_time;
+            true
+        });
+
+        for emitter in &mut self.emitters {
+            let count = emitter.due(delta_time);
+            let room = self.max_particles.saturating_sub(self.particles.len());
+            for _ in 0..count.min(room) {
+                self.particles.push(emitter.spawn(rng));
+            }
+        }
+    }
+
+    /// Plots the live particles at their transformed positions through
+    /// `Canvas::plot_points`. Cells without particles are left untouched and
+    /// the clip rectangle applies.
+    pub fn draw(&self, canvas: &mut Canvas<'_>) {
+        canvas.plot_points(particle_points(self, canvas.foreground_rgba()));
+    }
+}
+
+/// One logical pixel per live particle, painted for its age. Particles
+/// spawned later come later, so they paint over earlier ones.
+fn particle_points(system: &ParticleSystem, default_color: Rgba) -> Vec<PlotPoint> {
+    system
+        .particles
+        .iter()
+        .map(|particle| {
+            let t = particle.life_fraction();
+            PlotPoint {
+                position: particle.position,
+                color: mix_stops(&system.colors, t).unwrap_or(default_color),
+                glyph: pick_stop(&system.glyphs, t).unwrap_or(DEFAULT_PARTICLE_GLYPH),
+            }
+        })
+        .collect()
+}
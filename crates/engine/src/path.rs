@@ -0,0 +1,398 @@
+use std::f32::consts::PI;
+
+/// Maximum distance (in cells) a flattened segment may deviate from the true curve.
+pub const DEFAULT_TOLERANCE: f32 = 0.25;
+
+/// Recursion cap for curve subdivision so degenerate input cannot blow the stack.
+const MAX_SUBDIVISION_DEPTH: u32 = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn lerp(self, other: Point, t: f32) -> Point {
+        Point::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+        )
+    }
+
+    pub fn distance(self, other: Point) -> f32 {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Point),
+    LineTo(Point),
+    /// Quadratic Bézier: control point, end point.
+    QuadTo(Point, Point),
+    /// Cubic Bézier: first control point, second control point, end point.
+    CubicTo(Point, Point, Point),
+    /// Tangent arc between the current point, a corner and an end direction,
+    /// with the semantics of the HTML canvas `arcTo`.
+    ArcTo(Point, Point, f32),
+    Close,
+}
+
+/// A flattened sub-path made of straight segments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Point>,
+    pub closed: bool,
+}
+
+/// A sequence of drawing commands that can be stroked or filled on a `Canvas`.
+///
+/// Paths are built by chaining commands, e.g.
+/// `Path::new().move_to(2.0, 10.0).quad_to(10.0, 0.0, 18.0, 10.0).close()`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::MoveTo(Point::new(x, y)));
+        self
+    }
+
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::LineTo(Point::new(x, y)));
+        self
+    }
+
+    pub fn quad_to(mut self, cx: f32, cy: f32, x: f32, y: f32) -> Self {
+        self.commands
+            .push(PathCommand::QuadTo(Point::new(cx, cy), Point::new(x, y)));
+        self
+    }
+
+    pub fn cubic_to(mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::CubicTo(
+            Point::new(c1x, c1y),
+            Point::new(c2x, c2y),
+            Point::new(x, y),
+        ));
+        self
+    }
+
+    pub fn arc_to(mut self, x1: f32, y1: f32, x2: f32, y2: f32, radius: f32) -> Self {
+        self.commands.push(PathCommand::ArcTo(
+            Point::new(x1, y1),
+            Point::new(x2, y2),
+            radius,
+        ));
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Converts the path into polylines whose segments stay within `tolerance`
+    /// cells of the original curves. Curves are subdivided adaptively, so
+    /// tight bends get more segments than gentle ones.
+    pub fn flatten(&self, tolerance: f32) -> Vec<Polyline> {
+        let tolerance = tolerance.max(0.01);
+        let mut polylines = Vec::new();
+        let mut points: Vec<Point> = Vec::new();
+        let mut current = Point::default();
+        let mut start = Point::default();
+
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(p) => {
+                    finish_polyline(&mut polylines, &mut points, false);
+                    points.push(p);
+                    current = p;
+                    start = p;
+                }
+                PathCommand::LineTo(p) => {
+                    begin_segment(&mut points, current);
+                    points.push(p);
+                    current = p;
+                }
+                PathCommand::QuadTo(c, p) => {
+                    begin_segment(&mut points, current);
+                    flatten_quad(current, c, p, tolerance, 0, &mut points);
+                    current = p;
+                }
+                PathCommand::CubicTo(c1, c2, p) => {
+                    begin_segment(&mut points, current);
+                    flatten_cubic(current, c1, c2, p, tolerance, 0, &mut points);
+                    current = p;
+                }
+                PathCommand::ArcTo(corner, end, radius) => {
+                    begin_segment(&mut points, current);
+                    current = flatten_arc_to(current, corner, end, radius, tolerance, &mut points);
+                }
+                PathCommand::Close => {
+                    finish_polyline(&mut polylines, &mut points, true);
+                    current = start;
+                }
+            }
+        }
+        finish_polyline(&mut polylines, &mut points, false);
+
+        polylines
+    }
+}
+
+fn begin_segment(points: &mut Vec<Point>, current: Point) {
+    if points.is_empty() {
+        points.push(current);
+    }
+}
+
+fn finish_polyline(polylines: &mut Vec<Polyline>, points: &mut Vec<Point>, closed: bool) {
+    if !points.is_empty() {
+        polylines.push(Polyline {
+            points: std::mem::take(points),
+            closed,
+        });
+    }
+}
+
+fn flatten_quad(p0: Point, c: Point, p1: Point, tolerance: f32, depth: u32, out: &mut Vec<Point>) {
+    // The maximum distance between a quadratic and its chord is |p0 - 2c + p1| / 4.
+    let dx = p0.x - 2.0 * c.x + p1.x;
+    let dy = p0.y - 2.0 * c.y + p1.y;
+    if depth >= MAX_SUBDIVISION_DEPTH || dx.hypot(dy) * 0.25 <= tolerance {
+        out.push(p1);
+        return;
+    }
+
+    let a = p0.lerp(c, 0.5);
+    let b = c.lerp(p1, 0.5);
+    let mid = a.lerp(b, 0.5);
+    flatten_quad(p0, a, mid, tolerance, depth + 1, out);
+    flatten_quad(mid, b, p1, tolerance, depth + 1, out);
+}
+
+fn flatten_cubic(
+    p0: Point,
+    c1: Point,
+    c2: Point,
+    p1: Point,
+    tolerance: f32,
+    depth: u32,
+    out: &mut Vec<Point>,
+) {
+    // Bound the deviation from the chord using the second differences of the control polygon.
+    let d1 = (p0.x - 2.0 * c1.x + c2.x).hypot(p0.y - 2.0 * c1.y + c2.y);
+    let d2 = (c1.x - 2.0 * c2.x + p1.x).hypot(c1.y - 2.0 * c2.y + p1.y);
+    if depth >= MAX_SUBDIVISION_DEPTH || d1.max(d2) * 0.75 <= tolerance {
+        out.push(p1);
+        return;
+    }
+
+    let ab = p0.lerp(c1, 0.5);
+    let bc = c1.lerp(c2, 0.5);
+    let cd = c2.lerp(p1, 0.5);
+    let abc = ab.lerp(bc, 0.5);
+    let bcd = bc.lerp(cd, 0.5);
+    let mid = abc.lerp(bcd, 0.5);
+    flatten_cubic(p0, ab, abc, mid, tolerance, depth + 1, out);
+    flatten_cubic(mid, bcd, cd, p1, tolerance, depth + 1, out);
+}
+
+/// Appends a tangent arc and returns the point where it ends.
+fn flatten_arc_to(
+    p0: Point,
+    corner: Point,
+    end: Point,
+    radius: f32,
+    tolerance: f32,
+    out: &mut Vec<Point>,
+) -> Point {
+    let (v1x, v1y) = (p0.x - corner.x, p0.y - corner.y);
+    let (v2x, v2y) = (end.x - corner.x, end.y - corner.y);
+    let len1 = v1x.hypot(v1y);
+    let len2 = v2x.hypot(v2y);
+    let cross = v1x * v2y - v1y * v2x;
+
+    // Degenerate cases fall back to a straight line to the corner, like the HTML canvas.
+    if radius <= 0.0 || len1 == 0.0 || len2 == 0.0 || cross.abs() < f32::EPSILON {
+        out.push(corner);
+        return corner;
+    }
+
+    let (u1x, u1y) = (v1x / len1, v1y / len1);
+    let (u2x, u2y) = (v2x / len2, v2y / len2);
+    let angle = (u1x * u2x + u1y * u2y).clamp(-1.0, 1.0).acos();
+    let tangent_distance = radius / (angle * 0.5).tan();
+    let center_distance = radius / (angle * 0.5).sin();
+
+    let t1 = Point::new(
+        corner.x + u1x * tangent_distance,
+        corner.y + u1y * tangent_distance,
+    );
+    let t2 = Point::new(
+        corner.x + u2x * tangent_distance,
+        corner.y + u2y * tangent_distance,
+    );
+    let (bx, by) = (u1x + u2x, u1y + u2y);
+    let bisector = bx.hypot(by);
+    let center = Point::new(
+        corner.x + bx / bisector * center_distance,
+        corner.y + by / bisector * center_distance,
+    );
+
+    out.push(t1);
+
+    let start_angle = (t1.y - center.y).atan2(t1.x - center.x);
+    let end_angle = (t2.y - center.y).atan2(t2.x - center.x);
+    let mut sweep = end_angle - start_angle;
+    if sweep > PI {
+        sweep -= 2.0 * PI;
+    } else if sweep < -PI {
+        sweep += 2.0 * PI;
+    }
+
+    let steps = arc_segment_count(radius, sweep, tolerance);
+    for i in 1..=steps {
+        let a = start_angle + sweep * (i as f32 / steps as f32);
+        out.push(Point::new(
+            center.x + radius * a.cos(),
+            center.y + radius * a.sin(),
+        ));
+    }
+
+    t2
+}
+
+/// Number of chords needed so an arc of `radius` never strays more than `tolerance` from the chord.
+pub(crate) fn arc_segment_count(radius: f32, sweep: f32, tolerance: f32) -> usize {
+    if radius <= tolerance {
+        return 4;
+    }
+    let max_step = 2.0 * (1.0 - tolerance / radius).clamp(-1.0, 1.0).acos();
+    ((sweep.abs() / max_step).ceil() as usize).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_flatten_to_their_points() {
+        let polylines = Path::new()
+            .move_to(0.0, 0.0)
+            .line_to(4.0, 0.0)
+            .line_to(4.0, 3.0)
+            .close()
+            .flatten(DEFAULT_TOLERANCE);
+        assert_eq!(
+            polylines,
+            vec![Polyline {
+                points: vec![
+                    Point::new(0.0, 0.0),
+                    Point::new(4.0, 0.0),
+                    Point::new(4.0, 3.0)
+                ],
+                closed: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn move_to_starts_a_new_sub_path() {
+        let polylines = Path::new()
+            .move_to(0.0, 0.0)
+            .line_to(1.0, 0.0)
+            .move_to(5.0, 5.0)
+            .line_to(6.0, 5.0)
+            .flatten(DEFAULT_TOLERANCE);
+        assert_eq!(polylines.len(), 2);
+        assert!(polylines.iter().all(|polyline| !polyline.closed));
+        assert_eq!(polylines[1].points[0], Point::new(5.0, 5.0));
+    }
+
+    #[test]
+    fn quad_stays_within_tolerance() {
+        let (p0, c, p1) = (
+            Point::new(0.0, 10.0),
+            Point::new(10.0, -10.0),
+            Point::new(20.0, 10.0),
+        );
+        let tolerance = 0.1;
+        let points = &Path::new()
+            .move_to(p0.x, p0.y)
+            .quad_to(c.x, c.y, p1.x, p1.y)
+            .flatten(tolerance)[0]
+            .points;
+        assert_eq!(points.first(), Some(&p0));
+        assert_eq!(points.last(), Some(&p1));
+        assert!(points.len() > 4);
+        // Every point of the curve lies near some flattened segment.
+        for i in 0..=100 {
+            let t = i as f32 / 100.0;
+            let on_curve = p0.lerp(c, t).lerp(c.lerp(p1, t), t);
+            let nearest = points
+                .windows(2)
+                .map(|w| distance_to_segment(on_curve, w[0], w[1]))
+                .fold(f32::INFINITY, f32::min);
+            assert!(nearest <= tolerance + 1e-3, "t = {t}: {nearest}");
+        }
+    }
+
+    #[test]
+    fn degenerate_arc_falls_back_to_the_corner() {
+        let points = &Path::new()
+            .move_to(0.0, 0.0)
+            .arc_to(5.0, 0.0, 10.0, 0.0, 2.0)
+            .flatten(DEFAULT_TOLERANCE)[0]
+            .points;
+        assert_eq!(points, &vec![Point::new(0.0, 0.0), Point::new(5.0, 0.0)]);
+    }
+
+    #[test]
+    fn arc_to_rounds_the_corner() {
+        let radius = 3.0;
+        let points = &Path::new()
+            .move_to(0.0, 0.0)
+            .arc_to(10.0, 0.0, 10.0, 10.0, radius)
+            .flatten(DEFAULT_TOLERANCE)[0]
+            .points;
+        assert_eq!(points[1], Point::new(7.0, 0.0));
+        let end = *points.last().unwrap();
+        assert!(end.distance(Point::new(10.0, 3.0)) < 1e-4);
+        let center = Point::new(7.0, 3.0);
+        for point in &points[1..] {
+            assert!((point.distance(center) - radius).abs() < 1e-4);
+        }
+    }
+
+    fn distance_to_segment(p: Point, a: Point, b: Point) -> f32 {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let length_squared = dx * dx + dy * dy;
+        let t = if length_squared == 0.0 {
+            0.0
+        } else {
+            (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_squared).clamp(0.0, 1.0)
+        };
+        p.distance(a.lerp(b, t))
+    }
+}
@@ -0,0 +1,91 @@
+//! Plotting scattered points as logical pixels, shared by particles,
+//! tracers, boids and agents.
+
+use std::collections::BTreeMap;
+
+use crate::color::Rgba;
+use crate::draw::PixelMode;
+use crate::image::{ImageCell, BRAILLE_DOTS};
+use crate::path::Point;
+use crate::transform::Transform;
+
+/// One logical pixel to plot with `Canvas::plot_points`, in local canvas
+/// coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotPoint {
+    pub position: Point,
+    pub color: Rgba,
+    /// Glyph used in ASCII mode.
+    pub glyph: char,
+}
+
+/// Lit logical pixels of one cell, with the newest point's paint.
+#[derive(Default)]
+struct CellPaint {
+    bits: u32,
+    /// Colours of the upper and lower half-block pixels.
+    halves: [Option<Rgba>; 2],
+    color: Option<Rgba>,
+    glyph: Option<char>,
+}
+
+/// Device cells covered by `points` once mapped through `transform`, each
+/// point lighting one logical pixel of `mode`. Later points paint over
+/// earlier ones in the same pixel; cells without points are left out.
+pub(crate) fn plot_cells(
+    points: impl IntoIterator<Item = PlotPoint>,
+    mode: PixelMode,
+    transform: &Transform,
+) -> Vec<((i32, i32), ImageCell)> {
+    let (sx, sy) = mode.cell_resolution();
+    let (sx, sy) = (sx as i32, sy as i32);
+    let mut cells: BTreeMap<(i32, i32), CellPaint> = BTreeMap::new();
+    for point in points {
+        let p = transform.apply(point.position);
+        // Cells are centred on integer coordinates, like everything else.
+        let px = ((p.x + 0.5) * sx as f32).floor() as i32;
+        let py = ((p.y + 0.5) * sy as f32).floor() as i32;
+        let paint = cells
+            .entry((py.div_euclid(sy), px.div_euclid(sx)))
+            .or_default();
+        let (dx, dy) = (px.rem_euclid(sx) as usize, py.rem_euclid(sy) as usize);
+        match mode {
+            PixelMode::Ascii => paint.glyph = Some(point.glyph),
+            PixelMode::HalfBlock => paint.halves[dy] = Some(point.color),
+            PixelMode::Braille => paint.bits |= BRAILLE_DOTS[dy][dx],
+        }
+        paint.color = Some(point.color);
+    }
+
+    cells
+        .into_iter()
+        .map(|((row, column), paint)| {
+            let fg = paint.color.map(Rgba::to_color);
+            let cell = match mode {
+                PixelMode::Ascii => ImageCell {
+                    symbol: paint.glyph.unwrap_or(' '),
+                    fg,
+                    bg: None,
+                },
+                PixelMode::HalfBlock => match paint.halves {
+                    [Some(top), bottom] => ImageCell {
+                        symbol: '▀',
+                        fg: Some(top.to_color()),
+                        bg: bottom.map(Rgba::to_color),
+                    },
+                    [None, bottom] => ImageCell {
+                        symbol: '▄',
+                        fg: bottom.map(Rgba::to_color),
+                        bg: None,
+                    },
+                },
+                PixelMode::Braille => ImageCell {
+                    symbol: char::from_u32(0x2800 + paint.bits).unwrap_or(' '),
+                    fg,
+                    bg: None,
+                },
+            };
+            ((column, row), cell)
+        })
+        .collect()
+}
@@ -0,0 +1,187 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::color::Rgba;
+use crate::draw::Canvas;
+
+/// Gray–Scott reaction-diffusion: chemical `u` is fed into a toroidal grid
+/// and converted into `v` where they meet (`u + 2v -> 3v`), while `v` is
+/// killed off. Depending on `feed` and `kill` the `v` concentration
+/// settles into spots, stripes, mazes or endlessly dividing cells.
+///
+/// The grid usually matches the canvas' logical resolution, one cell per
+/// logical pixel; draw it with `GrayScott::draw`. With the `parallel`
+/// feature rows are updated on rayon's thread pool.
+#[derive(Debug, Clone)]
+pub struct GrayScott {
+    width: usize,
+    height: usize,
+    u: Vec<f32>,
+    v: Vec<f32>,
+    scratch: (Vec<f32>, Vec<f32>),
+    /// Rate at which `u` is replenished.
+    pub feed: f32,
+    /// Rate at which `v` is removed, on top of the feed rate.
+    pub kill: f32,
+    /// Diffusion rates of `u` and `v`; `v` should diffuse more slowly.
+    pub diffusion: (f32, f32),
+    /// Colour ramp from no `v` to the most; empty draws in the canvas'
+    /// foreground colour, leaving the glyph ramp and dot density to show
+    /// the concentration.
+    pub colors: Vec<Rgba>,
+}
+
+impl GrayScott {
+    /// A `width` x `height` grid full of `u` and empty of `v`, with the
+    /// "coral" feed and kill rates. Nothing happens until `v` is added with
+    /// `inject`.
+    pub fn new(width: usize, height: usize) -> Self {
+        let cells = width * height;
+        Self {
+            width,
+            height,
+            u: vec![1.0; cells],
+            v: vec![0.0; cells],
+            scratch: (vec![0.0; cells], vec![0.0; cells]),
+            feed: 0.0545,
+            kill: 0.062,
+            diffusion: (1.0, 0.5),
+            colors: Vec::new(),
+        }
+    }
+
+    pub fn with_rates(self, feed: f32, kill: f32) -> Self {
+        Self { feed, kill, ..self }
+    }
+
+    pub fn with_colors(self, colors: impl IntoIterator<Item = Rgba>) -> Self {
+        Self {
+            colors: colors.into_iter().collect(),
+            ..self
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Concentration of `v` at `(x, y)`, 0 outside the grid.
+    pub fn get(&self, x: usize, y: usize) -> f32 {
+        if x >= self.width || y >= self.height {
+            return 0.0;
+        }
+        self.v[y * self.width + x]
+    }
+
+    /// Draws the `v` concentration, one grid cell per logical pixel from the
+    /// top-left of the canvas, through `Canvas::shade_grid`. Higher
+    /// concentrations pick denser glyphs and later `colors`.
+    pub fn draw(&self, canvas: &mut Canvas<'_>) {
+        // `v` rarely rises much above a third, so stretch it to fill the ramp.
+        canvas.shade_grid(&self.colors, |x, y| self.get(x, y) * 3.0);
+    }
+
+    /// Fills the grid with `u` again, removing all `v`.
+    pub fn clear(&mut self) {
+        self.u.fill(1.0);
+        self.v.fill(0.0);
+    }
+
+    /// Resizes the grid, keeping the overlapping top-left region; new
+    /// cells start empty of `v`.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+        let mut resized = Self {
+            feed: self.feed,
+            kill: self.kill,
+            diffusion: self.diffusion,
+            colors: std::mem::take(&mut self.colors),
+            ..Self::new(width, height)
+        };
+        for y in 0..height.min(self.height) {
+            for x in 0..width.min(self.width) {
+                resized.u[y * width + x] = self.u[y * self.width + x];
+                resized.v[y * width + x] = self.v[y * self.width + x];
+            }
+        }
+        *self = resized;
+    }
+
+    /// Drops `v` into the disc of `radius` cells around `(x, y)`, wrapping
+    /// around the edges.
+    pub fn inject(&mut self, x: f32, y: f32, radius: f32) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let reach = radius.max(0.5).ceil() as i32;
+        let (cx, cy) = (x.floor() as i32, y.floor() as i32);
+        for dy in -reach..=reach {
+            for dx in -reach..=reach {
+                if (dx * dx + dy * dy) as f32 > radius * radius {
+                    continue;
+                }
+                let column = (cx + dx).rem_euclid(self.width as i32) as usize;
+                let row = (cy + dy).rem_euclid(self.height as i32) as usize;
+                let index = row * self.width + column;
+                self.u[index] = 0.5;
+                self.v[index] = 0.25;
+            }
+        }
+    }
+
+    /// Advances the reaction by one unit time step. Steps above 1 make the
+    /// explicit integration unstable, so run several per frame instead.
+    pub fn step(&mut self) {
+        let (width, height) = (self.width, self.height);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let (u, v) = (&self.u, &self.v);
+        let (feed, kill) = (self.feed, self.kill);
+        let (du, dv) = self.diffusion;
+        let update_row = |(y, (next_u, next_v)): (usize, (&mut [f32], &mut [f32]))| {
+            let up = (y + height - 1) % height * width;
+            let down = (y + 1) % height * width;
+            let here = y * width;
+            for x in 0..width {
+                let left = (x + width - 1) % width;
+                let right = (x + 1) % width;
+                // 3x3 Laplacian: weight 0.2 for edge neighbours, 0.05 for
+                // corners and -1 for the cell itself.
+                let laplacian = |field: &[f32]| {
+                    0.2 * (field[up + x]
+                        + field[down + x]
+                        + field[here + left]
+                        + field[here + right])
+                        + 0.05
+                            * (field[up + left]
+                                + field[up + right]
+                                + field[down + left]
+                                + field[down + right])
+                        - field[here + x]
+                };
+                let (a, b) = (u[here + x], v[here + x]);
+                let reaction = a * b * b;
+                next_u[x] = (a + du * laplacian(u) - reaction + feed * (1.0 - a)).clamp(0.0, 1.0);
+                next_v[x] = (b + dv * laplacian(v) + reaction - (kill + feed) * b).clamp(0.0, 1.0);
+            }
+        };
+        let (next_u, next_v) = &mut self.scratch;
+        #[cfg(feature = "parallel")]
+        next_u
+            .par_chunks_mut(width)
+            .zip(next_v.par_chunks_mut(width))
+            .enumerate()
+            .for_each(update_row);
+        #[cfg(not(feature = "parallel"))]
+        next_u
+            .chunks_mut(width)
+            .zip(next_v.chunks_mut(width))
+            .enumerate()
+            .for_each(update_row);
+        std::mem::swap(&mut self.u, next_u);
+        std::mem::swap(&mut self.v, next_v);
+    }
+}
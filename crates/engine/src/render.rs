@@ -1,4 +1,7 @@
+use crate::color::{BlendMode, Rgba, DEFAULT_BACKGROUND, DEFAULT_FOREGROUND};
 use crate::draw::Canvas;
+use crate::effects::EffectChain;
+use crate::layer::{composite_layers, LayerStack};
 use anyhow::Result;
 use crossterm::{
     cursor,
@@ -14,16 +17,146 @@ pub struct Cell {
     pub symbol: char,
     pub fg: Color,
     pub bg: Color,
+    /// A zero-width mark (accent, variation selector) printed after `symbol`.
+    pub combining: Option<char>,
+    /// Coverage of `fg`; below 255 the glyph colour still has to be blended
+    /// with whatever ends up beneath the cell.
+    pub fg_alpha: u8,
+    /// Coverage of `bg`, like `fg_alpha`.
+    pub bg_alpha: u8,
+    /// How translucent or non-normal paint in this cell combines with what is beneath.
+    pub blend: BlendMode,
 }
 
 impl Default for Cell {
     fn default() -> Self {
+        Self::new(' ', Color::Reset, Color::Reset)
+    }
+}
+
+impl Cell {
+    /// Placeholder stored in the cell to the right of a double-width glyph.
+    /// The terminal fills it when printing the glyph, so it is never printed itself.
+    pub const CONTINUATION: char = '\0';
+
+    pub fn new(symbol: char, fg: Color, bg: Color) -> Self {
         Self {
-            symbol: ' ',
-            fg: Color::Reset,
-            bg: Color::Reset,
+            symbol,
+            fg,
+            bg,
+            combining: None,
+            fg_alpha: 255,
+            bg_alpha: 255,
+            blend: BlendMode::Normal,
         }
     }
+
+    pub fn is_continuation(&self) -> bool {
+        self.symbol == Self::CONTINUATION
+    }
+
+    /// True when the cell simply replaces whatever is beneath it.
+    pub fn is_opaque(&self) -> bool {
+        self.fg_alpha == 255 && self.bg_alpha == 255 && self.blend == BlendMode::Normal
+    }
+
+    pub(crate) fn has_glyph(&self) -> bool {
+        !self.is_continuation() && (self.symbol != ' ' || self.combining.is_some())
+    }
+
+    /// Paints `source` over this cell.
+    ///
+    /// Opaque sources replace the cell outright. Otherwise backgrounds blend
+    /// with the background beneath; a glyph replaces the glyph beneath and
+    /// blends its colour with it (or with the background when there was no
+    /// glyph); and a blank source only tints the existing glyph with its
+    /// background. Parts with nothing beneath (a `Color::Reset` background)
+    /// stay translucent until the cell is composited onto a lower layer or
+    /// `resolved`.
+    pub fn composite(&self, source: &Cell) -> Cell {
+        if source.is_opaque() {
+            return *source;
+        }
+        let mut out = *self;
+        let mode = source.blend;
+
+        if let Some(paint) = Rgba::from_color(source.bg) {
+            let paint = paint.with_alpha(source.bg_alpha);
+            match Rgba::from_color(self.bg) {
+                Some(beneath) => {
+                    let result = mode.composite(beneath.with_alpha(self.bg_alpha), paint);
+                    out.bg = result.to_color();
+                    out.bg_alpha = result.a;
+                }
+                None => {
+                    out.bg = source.bg;
+                    out.bg_alpha = source.bg_alpha;
+                    out.blend = mode;
+                }
+            }
+            if !source.has_glyph() && self.has_glyph() {
+                let result = mode.composite(self.foreground(), paint);
+                out.fg = result.to_color();
+                out.fg_alpha = result.a;
+            }
+        }
+
+        if source.has_glyph() {
+            out.symbol = source.symbol;
+            out.combining = source.combining;
+            let backdrop = if self.has_glyph() {
+                Some(self.foreground())
+            } else {
+                Rgba::from_color(out.bg).map(|bg| bg.with_alpha(out.bg_alpha))
+            };
+            match backdrop {
+                Some(backdrop) => {
+                    let result = mode.composite(backdrop, source.foreground());
+                    out.fg = result.to_color();
+                    out.fg_alpha = result.a;
+                }
+                None => {
+                    out.fg = source.fg;
+                    out.fg_alpha = source.fg_alpha;
+                    out.blend = mode;
+                }
+            }
+        }
+
+        if out.fg_alpha == 255 && out.bg_alpha == 255 && self.is_opaque() {
+            out.blend = BlendMode::Normal;
+        }
+        out
+    }
+
+    /// Flattens any remaining translucency onto the terminal's default
+    /// colours so the cell can be printed.
+    pub fn resolved(&self) -> Cell {
+        if self.is_opaque() {
+            return *self;
+        }
+        let background = Rgba::from_color(self.bg).map(|bg| {
+            self.blend
+                .composite(DEFAULT_BACKGROUND, bg.with_alpha(self.bg_alpha))
+        });
+        let fg = self
+            .blend
+            .composite(background.unwrap_or(DEFAULT_BACKGROUND), self.foreground());
+        Cell {
+            fg: fg.to_color(),
+            bg: background.map_or(Color::Reset, Rgba::to_color),
+            fg_alpha: 255,
+            bg_alpha: 255,
+            blend: BlendMode::Normal,
+            ..*self
+        }
+    }
+
+    fn foreground(&self) -> Rgba {
+        Rgba::from_color(self.fg)
+            .unwrap_or(DEFAULT_FOREGROUND)
+            .with_alpha(self.fg_alpha)
+    }
 }
 
 #[allow(dead_code)] // Fields will be used in future steps
@@ -33,6 +166,9 @@ pub struct TerminalRenderer {
     front_buffer: Vec<Cell>,
     back_buffer: Vec<Cell>,
     overlay_buffer: Vec<Cell>,
+    composed_buffer: Vec<Cell>,
+    composed: bool,
+    layers: LayerStack,
     stdout: std::io::Stdout,
     keyboard_enhancement_enabled: bool,
 }
@@ -46,6 +182,9 @@ impl TerminalRenderer {
             front_buffer: vec![Cell::default(); size],
             back_buffer: vec![Cell::default(); size],
             overlay_buffer: vec![Cell::default(); size],
+            composed_buffer: vec![Cell::default(); size],
+            composed: false,
+            layers: LayerStack::new(width, height),
             stdout: stdout(),
             keyboard_enhancement_enabled: false,
         })
@@ -79,6 +218,7 @@ impl TerminalRenderer {
         for cell in self.back_buffer.iter_mut() {
             *cell = Cell::default();
         }
+        self.layers.clear_frame();
         self.clear_overlay();
     }
 
@@ -86,6 +226,19 @@ impl TerminalRenderer {
         Canvas::new(self.width, self.height, &mut self.back_buffer)
     }
 
+    pub fn layers(&mut self) -> &mut LayerStack {
+        &mut self.layers
+    }
+
+    /// The scene canvas together with the named layers, for building a
+    /// `Context` that can draw on both.
+    pub fn canvas_with_layers(&mut self) -> (Canvas<'_>, &mut LayerStack) {
+        (
+            Canvas::new(self.width, self.height, &mut self.back_buffer),
+            &mut self.layers,
+        )
+    }
+
     pub fn overlay_canvas(&mut self) -> Canvas<'_> {
         Canvas::new(self.width, self.height, &mut self.overlay_buffer)
     }
@@ -96,46 +249,74 @@ impl TerminalRenderer {
         }
     }
 
-    pub fn flush(&mut self) -> Result<()> {
-        for (i, (front_cell, back_cell)) in self
-            .front_buffer
-            .iter()
-            .zip(self.back_buffer.iter())
-            .enumerate()
-        {
-            if front_cell != back_cell {
-                let x = (i % self.width as usize) as u16;
-                let y = (i / self.width as usize) as u16;
-                execute!(
-                    self.stdout,
-                    cursor::MoveTo(x, y),
-                    SetForegroundColor(back_cell.fg),
-                    SetBackgroundColor(back_cell.bg),
-                    Print(back_cell.symbol)
-                )?;
+    /// Composites layers below the scene (negative z), the scene canvas and
+    /// the remaining layers into a frame of concrete colours. `flush` does
+    /// this itself unless it already happened this frame.
+    pub fn compose(&mut self) {
+        let layers = self.layers.ordered();
+        let split = layers.partition_point(|layer| layer.z() < 0);
+        let (below, above) = layers.split_at(split);
+        for (i, back_cell) in self.back_buffer.iter().enumerate() {
+            let x = (i % self.width as usize) as i32;
+            let y = (i / self.width as usize) as i32;
+            let mut cell = composite_layers(Cell::default(), below, x, y);
+            if *back_cell != Cell::default() {
+                cell = cell.composite(back_cell);
             }
+            self.composed_buffer[i] = composite_layers(cell, above, x, y).resolved();
+        }
+        self.composed = true;
+    }
+
+    /// Runs post-processing `effects` over the composed frame. The overlay is
+    /// drawn on top afterwards, so debug text stays untouched.
+    pub fn apply_effects(&mut self, effects: &mut EffectChain, total_time: f32, delta_time: f32) {
+        if !self.composed {
+            self.compose();
         }
-        self.front_buffer.copy_from_slice(&self.back_buffer);
+        effects.apply(
+            &mut self.composed_buffer,
+            self.width,
+            self.height,
+            total_time,
+            delta_time,
+        );
+    }
 
+    /// Draws the overlay over the composed frame and prints every cell that
+    /// changed since the last flush.
+    pub fn flush(&mut self) -> Result<()> {
+        if !self.composed {
+            self.compose();
+        }
         for (i, overlay_cell) in self.overlay_buffer.iter().enumerate() {
-            if overlay_cell.symbol != ' '
-                || overlay_cell.fg != Color::Reset
-                || overlay_cell.bg != Color::Reset
-            {
-                let x = (i % self.width as usize) as u16;
-                let y = (i / self.width as usize) as u16;
-                execute!(
-                    self.stdout,
-                    cursor::MoveTo(x, y),
-                    SetForegroundColor(overlay_cell.fg),
-                    SetBackgroundColor(overlay_cell.bg),
-                    Print(overlay_cell.symbol)
-                )?;
-                self.front_buffer[i] = *overlay_cell;
+            let mut cell = self.composed_buffer[i];
+            if *overlay_cell != Cell::default() {
+                cell = cell.composite(overlay_cell).resolved();
+            }
+            if cell == self.front_buffer[i] {
+                continue;
+            }
+            self.front_buffer[i] = cell;
+            if cell.is_continuation() {
+                continue;
+            }
+            let x = (i % self.width as usize) as u16;
+            let y = (i / self.width as usize) as u16;
+            execute!(
+                self.stdout,
+                cursor::MoveTo(x, y),
+                SetForegroundColor(cell.fg),
+                SetBackgroundColor(cell.bg),
+                Print(cell.symbol)
+            )?;
+            if let Some(mark) = cell.combining {
+                execute!(self.stdout, Print(mark))?;
             }
         }
 
         self.stdout.flush()?;
+        self.composed = false;
         self.clear_overlay();
         Ok(())
     }
@@ -146,3 +327,115 @@ impl Drop for TerminalRenderer {
         self.shutdown().unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::Rgb { r: 255, g: 0, b: 0 };
+    const BLUE: Color = Color::Rgb { r: 0, g: 0, b: 255 };
+
+    fn paint(symbol: char, fg: Color, fg_alpha: u8, bg: Color, bg_alpha: u8) -> Cell {
+        Cell {
+            fg_alpha,
+            bg_alpha,
+            ..Cell::new(symbol, fg, bg)
+        }
+    }
+
+    #[test]
+    fn opaque_sources_replace_the_cell() {
+        let beneath = Cell::new('a', RED, BLUE);
+        let source = Cell::new('b', BLUE, RED);
+        assert_eq!(beneath.composite(&source), source);
+    }
+
+    #[test]
+    fn translucent_backgrounds_blend_with_the_background_beneath() {
+        let beneath = Cell::new(' ', Color::Reset, BLUE);
+        let out = beneath.composite(&paint(' ', Color::Reset, 255, RED, 128));
+        assert_eq!(
+            out.bg,
+            Color::Rgb {
+                r: 128,
+                g: 0,
+                b: 127
+            }
+        );
+        assert!(out.is_opaque());
+    }
+
+    #[test]
+    fn blank_translucent_paint_tints_the_glyph_beneath() {
+        let beneath = Cell::new('x', BLUE, Color::Reset);
+        let out = beneath.composite(&paint(' ', Color::Reset, 255, RED, 128));
+        assert_eq!(out.symbol, 'x');
+        assert_eq!(
+            out.fg,
+            Color::Rgb {
+                r: 128,
+                g: 0,
+                b: 127
+            }
+        );
+    }
+
+    #[test]
+    fn translucent_glyphs_blend_with_the_background_when_nothing_is_beneath() {
+        let beneath = Cell::new(' ', Color::Reset, BLUE);
+        let out = beneath.composite(&paint('o', RED, 128, Color::Reset, 255));
+        assert_eq!(out.symbol, 'o');
+        assert_eq!(
+            out.fg,
+            Color::Rgb {
+                r: 128,
+                g: 0,
+                b: 127
+            }
+        );
+        assert_eq!(out.bg, BLUE);
+    }
+
+    #[test]
+    fn blend_modes_apply_to_the_cell() {
+        let beneath = Cell::new(' ', Color::Reset, Color::Rgb { r: 100, g: 0, b: 0 });
+        let glow = Cell {
+            blend: BlendMode::Add,
+            ..Cell::new(' ', Color::Reset, Color::Rgb { r: 100, g: 0, b: 0 })
+        };
+        let out = beneath.composite(&glow);
+        assert_eq!(out.bg, Color::Rgb { r: 200, g: 0, b: 0 });
+        assert_eq!(out.blend, BlendMode::Normal);
+    }
+
+    #[test]
+    fn reset_backdrops_keep_paint_translucent_until_resolved() {
+        let source = Cell {
+            blend: BlendMode::Screen,
+            ..paint(' ', Color::Reset, 255, RED, 64)
+        };
+        let out = Cell::default().composite(&source);
+        assert_eq!(
+            (out.bg, out.bg_alpha, out.blend),
+            (RED, 64, BlendMode::Screen)
+        );
+
+        // Flattened onto the black default background.
+        let resolved = out.resolved();
+        assert_eq!(resolved.bg, Color::Rgb { r: 64, g: 0, b: 0 });
+        assert!(resolved.is_opaque());
+    }
+
+    #[test]
+    fn resolved_glyphs_blend_onto_their_background() {
+        let cell = paint('o', RED, 128, Color::Reset, 255);
+        let resolved = cell.resolved();
+        assert_eq!(resolved.symbol, 'o');
+        assert_eq!(resolved.fg, Color::Rgb { r: 128, g: 0, b: 0 });
+        assert_eq!(resolved.bg, Color::Reset);
+        assert!(resolved.is_opaque());
+
+        let opaque = Cell::new('o', RED, BLUE);
+        assert_eq!(opaque.resolved(), opaque);
+    }
+}
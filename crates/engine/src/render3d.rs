@@ -0,0 +1,489 @@
+use std::f32::consts::FRAC_PI_3;
+
+use crate::color::Rgba;
+use crate::draw::{Canvas, PixelMode};
+use crate::image::{render_cells, Image, ImageCell, ImageOptions};
+use crate::math3d::{Mat4, Vec3, Vec4};
+use crate::mesh::Mesh;
+
+/// Glyphs for ASCII shading, darkest first. Unlike `image::DEFAULT_RAMP` it
+/// has no space, so unlit surfaces still show.
+pub const SHADING_RAMP: &str = ".,-~:;=!*#$@";
+
+/// Fraction of its depth by which a wireframe edge may sit behind a surface
+/// and still be drawn, so edges of filled triangles are not hidden by them.
+const WIRE_DEPTH_BIAS: f32 = 0.01;
+
+/// A perspective camera looking from `position` at `target`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub position: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    /// Vertical field of view in radians.
+    pub fov_y: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(0.0, 0.0, 4.0),
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            fov_y: FRAC_PI_3,
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+}
+
+impl Camera {
+    pub fn new(position: Vec3, target: Vec3) -> Self {
+        Self {
+            position,
+            target,
+            ..Self::default()
+        }
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at(self.position, self.target, self.up)
+    }
+
+    pub fn projection_matrix(&self, aspect: f32) -> Mat4 {
+        Mat4::perspective(self.fov_y, aspect, self.near, self.far)
+    }
+}
+
+/// A directional light for Lambert shading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    /// The direction the light travels in, towards the scene.
+    pub direction: Vec3,
+    /// Brightness (0–1) of surfaces facing away from the light.
+    pub ambient: f32,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::new(1.0, -1.0, -1.0),
+            ambient: 0.15,
+        }
+    }
+}
+
+impl Light {
+    /// Brightness (0–1) of a surface with unit normal `normal`.
+    pub fn intensity(&self, normal: Vec3) -> f32 {
+        let ambient = self.ambient.clamp(0.0, 1.0);
+        let diffuse = normal.dot(-self.direction.normalized()).max(0.0);
+        ambient + (1.0 - ambient) * diffuse
+    }
+}
+
+/// What `Viewport3d::draw_mesh` draws for each triangle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MeshStyle {
+    #[default]
+    Filled,
+    Wireframe,
+    /// Shaded triangles with their edges drawn at full brightness on top.
+    FilledWireframe,
+}
+
+/// How lighting varies across a filled triangle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Shading {
+    /// One brightness per triangle, showing the facets.
+    #[default]
+    Flat,
+    /// Brightness interpolated from averaged vertex normals.
+    Smooth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Material {
+    /// Surface colour; `None` uses the canvas' foreground colour.
+    pub color: Option<Rgba>,
+    pub style: MeshStyle,
+    pub shading: Shading,
+    /// Skips triangles facing away from the camera. Turn it off for open
+    /// models, whose inside faces are then lit like their outsides.
+    pub cull_back_faces: bool,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            color: None,
+            style: MeshStyle::default(),
+            shading: Shading::default(),
+            cull_back_faces: true,
+        }
+    }
+}
+
+/// What a logical pixel shows once the nearest surface has been drawn.
+#[derive(Debug, Clone, Copy)]
+struct Fragment {
+    color: Option<Rgba>,
+    intensity: f32,
+}
+
+/// A projected vertex in logical pixel coordinates.
+#[derive(Debug, Clone, Copy)]
+struct ScreenVertex {
+    x: f32,
+    y: f32,
+    /// Reciprocal of the view depth, which varies linearly across the screen.
+    inv_w: f32,
+    intensity: f32,
+}
+
+/// A depth-buffered render target of `columns` x `rows` cells, holding one
+/// sample per logical pixel of its `PixelMode` (2x4 per cell in braille).
+/// Draw meshes into it each frame, then show it with `Viewport3d::draw`.
+pub struct Viewport3d {
+    columns: u16,
+    rows: u16,
+    mode: PixelMode,
+    width: usize,
+    height: usize,
+    depth: Vec<f32>,
+    fragments: Vec<Option<Fragment>>,
+    ramp: Vec<char>,
+    pub camera: Camera,
+    pub light: Light,
+}
+
+impl Viewport3d {
+    pub fn new(columns: u16, rows: u16, mode: PixelMode) -> Self {
+        let mut viewport = Self {
+            columns: 0,
+            rows: 0,
+            mode,
+            width: 0,
+            height: 0,
+            depth: Vec::new(),
+            fragments: Vec::new(),
+            ramp: SHADING_RAMP.chars().collect(),
+            camera: Camera::default(),
+            light: Light::default(),
+        };
+        viewport.allocate(columns, rows, mode);
+        viewport
+    }
+
+    /// Changes the cell size or pixel mode, clearing the viewport if either
+    /// differs. Cheap to call every frame with the canvas' current mode.
+    pub fn resize(&mut self, columns: u16, rows: u16, mode: PixelMode) {
+        if (columns, rows, mode) != (self.columns, self.rows, self.mode) {
+            self.allocate(columns, rows, mode);
+        }
+    }
+
+    fn allocate(&mut self, columns: u16, rows: u16, mode: PixelMode) {
+        let (sx, sy) = mode.cell_resolution();
+        self.columns = columns;
+        self.rows = rows;
+        self.mode = mode;
+        self.width = columns as usize * sx;
+        self.height = rows as usize * sy;
+        self.depth = vec![f32::INFINITY; self.width * self.height];
+        self.fragments = vec![None; self.width * self.height];
+    }
+
+    pub fn columns(&self) -> u16 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    pub fn mode(&self) -> PixelMode {
+        self.mode
+    }
+
+    /// Size in logical pixels.
+    pub fn pixel_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Physical width over height, taking cells as twice as tall as wide.
+    pub fn aspect(&self) -> f32 {
+        if self.rows == 0 {
+            return 1.0;
+        }
+        self.columns as f32 / (self.rows as f32 * 2.0)
+    }
+
+    /// Sets the ASCII shading glyphs, darkest first; an empty ramp restores
+    /// `SHADING_RAMP`.
+    pub fn set_ramp(&mut self, ramp: &str) {
+        let ramp = if ramp.is_empty() { SHADING_RAMP } else { ramp };
+        self.ramp = ramp.chars().collect();
+    }
+
+    /// Forgets everything drawn, ready for the next frame.
+    pub fn clear(&mut self) {
+        self.depth.fill(f32::INFINITY);
+        self.fragments.fill(None);
+    }
+
+    /// Renders `mesh`, placed in the world by `model`, as seen by `camera`.
+    /// Nearer surfaces hide farther ones regardless of drawing order.
+    pub fn draw_mesh(&mut self, mesh: &Mesh, model: &Mat4, material: &Material) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let view_projection =
+            self.camera.projection_matrix(self.aspect()) * self.camera.view_matrix();
+        let world: Vec<Vec3> = mesh
+            .vertices
+            .iter()
+            .map(|&v| model.transform_point(v))
+            .collect();
+        let clip: Vec<Vec4> = world
+            .iter()
+            .map(|&v| view_projection * v.extend(1.0))
+            .collect();
+        let vertex_normals: Vec<Vec3> = match material.shading {
+            Shading::Smooth => mesh
+                .vertex_normals()
+                .into_iter()
+                .map(|n| model.transform_vector(n).normalized())
+                .collect(),
+            Shading::Flat => Vec::new(),
+        };
+        let filled = material.style != MeshStyle::Wireframe;
+        let wired = material.style != MeshStyle::Filled;
+
+        for &[a, b, c] in &mesh.triangles {
+            let normal = (world[b] - world[a])
+                .cross(world[c] - world[a])
+                .normalized();
+            let facing = normal.dot(self.camera.position - world[a]) > 0.0;
+            if !facing && material.cull_back_faces {
+                continue;
+            }
+            if filled {
+                // Two-sided lighting: the visible side of a back face faces the other way.
+                let side = if facing { 1.0 } else { -1.0 };
+                let intensity = |index: usize| {
+                    let normal = vertex_normals.get(index).copied().unwrap_or(normal);
+                    self.light.intensity(normal * side)
+                };
+                let corners = [
+                    (clip[a], intensity(a)),
+                    (clip[b], intensity(b)),
+                    (clip[c], intensity(c)),
+                ];
+                let polygon = clip_near(&corners);
+                let screen: Vec<ScreenVertex> = polygon
+                    .iter()
+                    .map(|&(p, intensity)| self.to_screen(p, intensity))
+                    .collect();
+                for i in 1..screen.len().saturating_sub(1) {
+                    self.fill_triangle([screen[0], screen[i], screen[i + 1]], material.color);
+                }
+            }
+            if wired {
+                for (from, to) in [(a, b), (b, c), (c, a)] {
+                    self.draw_edge(clip[from], clip[to], material.color);
+                }
+            }
+        }
+    }
+
+    fn to_screen(&self, p: Vec4, intensity: f32) -> ScreenVertex {
+        let inv_w = 1.0 / p.w;
+        ScreenVertex {
+            x: (p.x * inv_w + 1.0) * 0.5 * self.width as f32,
+            y: (1.0 - p.y * inv_w) * 0.5 * self.height as f32,
+            inv_w,
+            intensity,
+        }
+    }
+
+    /// Writes a fragment at `(x, y)` if it is nearer than what is there.
+    fn write(&mut self, x: usize, y: usize, inv_w: f32, fragment: Fragment, biased: bool) {
+        let index = y * self.width + x;
+        let depth = 1.0 / inv_w;
+        let tested = if biased {
+            depth * (1.0 - WIRE_DEPTH_BIAS)
+        } else {
+            depth
+        };
+        if tested < self.depth[index] {
+            if !biased {
+                self.depth[index] = depth;
+            }
+            self.fragments[index] = Some(fragment);
+        }
+    }
+
+    /// Fills the pixels whose centres fall inside the triangle.
+    fn fill_triangle(&mut self, v: [ScreenVertex; 3], color: Option<Rgba>) {
+        let edge = |a: &ScreenVertex, b: &ScreenVertex, x: f32, y: f32| {
+            (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+        };
+        let area = edge(&v[0], &v[1], v[2].x, v[2].y);
+        if area.abs() <= f32::EPSILON {
+            return;
+        }
+        let min_x = v.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+        let max_x = v.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
+        let min_y = v.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+        let max_y = v.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+        let x_range = pixel_range(min_x, max_x, self.width);
+        let y_range = pixel_range(min_y, max_y, self.height);
+        for y in y_range {
+            let py = y as f32 + 0.5;
+            for x in x_range.clone() {
+                let px = x as f32 + 0.5;
+                let w0 = edge(&v[1], &v[2], px, py) / area;
+                let w1 = edge(&v[2], &v[0], px, py) / area;
+                let w2 = 1.0 - w0 - w1;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+                let inv_w = w0 * v[0].inv_w + w1 * v[1].inv_w + w2 * v[2].inv_w;
+                let intensity = w0 * v[0].intensity + w1 * v[1].intensity + w2 * v[2].intensity;
+                self.write(x, y, inv_w, Fragment { color, intensity }, false);
+            }
+        }
+    }
+
+    /// Draws a clip-space edge one pixel wide at full brightness.
+    fn draw_edge(&mut self, from: Vec4, to: Vec4, color: Option<Rgba>) {
+        let Some((from, to)) = clip_near_segment(from, to) else {
+            return;
+        };
+        let (from, to) = (self.to_screen(from, 1.0), self.to_screen(to, 1.0));
+        let steps = (to.x - from.x)
+            .abs()
+            .max((to.y - from.y).abs())
+            .ceil()
+            .max(1.0);
+        for step in 0..=steps as usize {
+            let t = step as f32 / steps;
+            let x = from.x + (to.x - from.x) * t;
+            let y = from.y + (to.y - from.y) * t;
+            if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+                continue;
+            }
+            let inv_w = from.inv_w + (to.inv_w - from.inv_w) * t;
+            let fragment = Fragment {
+                color,
+                intensity: 1.0,
+            };
+            self.write(x as usize, y as usize, inv_w, fragment, true);
+        }
+    }
+
+    /// Draws what was rendered with its top-left cell at `(x, y)`, in the
+    /// viewport's own pixel mode. Uncovered pixels leave the canvas
+    /// untouched, and materials without a colour use the current
+    /// foreground. Like images, the viewport stays aligned to the cell grid.
+    pub fn draw(&self, canvas: &mut Canvas<'_>, x: i32, y: i32) {
+        let cells = self.cells(canvas.foreground_rgba());
+        canvas.draw_cells(x, y, self.columns as usize, &cells);
+    }
+
+    /// The viewport as cells: ramp glyphs in ASCII, shaded colours in the
+    /// other modes. Uncovered cells are `None`.
+    fn cells(&self, default_color: Rgba) -> Vec<Option<ImageCell>> {
+        if self.mode == PixelMode::Ascii {
+            let last = self.ramp.len().saturating_sub(1);
+            return self
+                .fragments
+                .iter()
+                .map(|fragment| {
+                    fragment.map(|fragment| {
+                        let level = (fragment.intensity.clamp(0.0, 1.0) * last as f32).round();
+                        ImageCell {
+                            symbol: self.ramp.get(level as usize).copied().unwrap_or('#'),
+                            fg: fragment.color.map(Rgba::to_color),
+                            bg: None,
+                        }
+                    })
+                })
+                .collect();
+        }
+        let pixels = self
+            .fragments
+            .iter()
+            .map(|fragment| match fragment {
+                Some(fragment) => {
+                    let color = fragment.color.unwrap_or(default_color);
+                    let shade = |c: u8| (c as f32 * fragment.intensity.clamp(0.0, 1.0)) as u8;
+                    [shade(color.r), shade(color.g), shade(color.b), 255]
+                }
+                None => [0, 0, 0, 0],
+            })
+            .collect();
+        let (columns, rows) = (self.columns as usize, self.rows as usize);
+        let Ok(image) = Image::from_rgba(self.width, self.height, pixels) else {
+            return vec![None; columns * rows];
+        };
+        // Every covered pixel becomes a dot or half block; shading is in the colour.
+        let options = ImageOptions {
+            mode: Some(self.mode),
+            threshold: 0.0,
+            ..ImageOptions::default()
+        };
+        render_cells(&image, columns, rows, self.mode, &options)
+    }
+}
+
+/// Pixels whose centres may lie within `min..=max`, clamped to `0..size`.
+fn pixel_range(min: f32, max: f32, size: usize) -> std::ops::Range<usize> {
+    let start = (min - 0.5).ceil().max(0.0) as usize;
+    let end = ((max - 0.5).floor() + 1.0).clamp(0.0, size as f32) as usize;
+    start..end.max(start)
+}
+
+/// Whether a clip-space point lies in front of the near plane.
+fn in_front(p: &Vec4) -> bool {
+    p.z >= -p.w
+}
+
+/// Clips a triangle against the near plane, returning a polygon of up to
+/// four corners (empty when it lies entirely behind).
+fn clip_near(corners: &[(Vec4, f32); 3]) -> Vec<(Vec4, f32)> {
+    let mut polygon = Vec::with_capacity(4);
+    for i in 0..3 {
+        let (current, current_intensity) = corners[i];
+        let (next, next_intensity) = corners[(i + 1) % 3];
+        if in_front(&current) {
+            polygon.push((current, current_intensity));
+        }
+        if in_front(&current) != in_front(&next) {
+            let t = near_crossing(current, next);
+            polygon.push((
+                current.lerp(next, t),
+                current_intensity + (next_intensity - current_intensity) * t,
+            ));
+        }
+    }
+    polygon
+}
+
+fn clip_near_segment(from: Vec4, to: Vec4) -> Option<(Vec4, Vec4)> {
+    match (in_front(&from), in_front(&to)) {
+        (true, true) => Some((from, to)),
+        (false, false) => None,
+        (true, false) => Some((from, from.lerp(to, near_crossing(from, to)))),
+        (false, true) => Some((from.lerp(to, near_crossing(from, to)), to)),
+    }
+}
+
+/// Where along `a`→`b` the segment meets the near plane `z = -w`.
+fn near_crossing(a: Vec4, b: Vec4) -> f32 {
+    let (da, db) = (a.z + a.w, b.z + b.w);
+    da / (da - db)
+}
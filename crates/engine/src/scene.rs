@@ -1,16 +1,22 @@
 use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
+use rand::rngs::StdRng;
 
-use crate::{config::EngineSettings, draw::Canvas, input::InputState};
+use crate::{config::EngineSettings, draw::Canvas, input::InputState, layer::LayerStack};
 
 pub struct Context<'a> {
     pub canvas: Canvas<'a>,
     pub engine: EngineSettings,
     pub delta_time: f32,
     pub total_time: f32,
+    /// Seconds since the active scene started, not counting pauses. Keyframed
+    /// settings (`SceneConfig::param`) are evaluated against it.
+    pub scene_time: f32,
     pub frame: u64,
     input: Option<&'a InputState>,
+    layers: Option<&'a mut LayerStack>,
+    rng: Option<&'a mut StdRng>,
 }
 
 impl<'a> Context<'a> {
@@ -20,8 +26,11 @@ impl<'a> Context<'a> {
             engine,
             delta_time: 0.0,
             total_time: 0.0,
+            scene_time: 0.0,
             frame: 0,
             input: None,
+            layers: None,
+            rng: None,
         }
     }
 
@@ -33,6 +42,41 @@ impl<'a> Context<'a> {
         self.input
     }
 
+    pub fn set_layers(&mut self, layers: Option<&'a mut LayerStack>) {
+        self.layers = layers;
+    }
+
+    /// The renderer's named layers, for adding layers and changing their
+    /// visibility, opacity or offset.
+    pub fn layers(&mut self) -> Option<&mut LayerStack> {
+        self.layers.as_deref_mut()
+    }
+
+    /// A canvas for drawing on the layer called `name`, using the scene
+    /// canvas' pixel mode.
+    pub fn layer(&mut self, name: &str) -> Option<Canvas<'_>> {
+        let mode = self.canvas.current_pixel_mode;
+        let mut canvas = self.layers.as_deref_mut()?.canvas(name)?;
+        canvas.current_pixel_mode = mode;
+        Some(canvas)
+    }
+
+    pub fn set_rng(&mut self, rng: Option<&'a mut StdRng>) {
+        self.rng = rng;
+    }
+
+    /// The run's random number generator, seeded from `EngineSettings::seed`
+    /// and shared by all scenes across frames.
+    pub fn rng(&mut self) -> Option<&mut StdRng> {
+        self.rng.as_deref_mut()
+    }
+
+    /// The run's seed, for seeding generators such as `noise::Perlin`. Add
+    /// an offset per generator to keep them independent.
+    pub fn seed(&self) -> u64 {
+        self.engine.seed.unwrap_or(0)
+    }
+
     pub fn set_timing(&mut self, delta_time: f32, total_time: f32, frame: u64) {
         self.delta_time = delta_time;
         self.total_time = total_time;
@@ -51,6 +95,7 @@ pub struct SceneManager<'scene> {
     scenes: HashMap<String, Box<dyn Scene + 'scene>>,
     active_scene: Option<String>,
     queued_scene: Option<String>,
+    scene_time: f32,
 }
 
 impl<'scene> SceneManager<'scene> {
@@ -59,6 +104,7 @@ impl<'scene> SceneManager<'scene> {
             scenes: HashMap::new(),
             active_scene: None,
             queued_scene: None,
+            scene_time: 0.0,
         }
     }
 
@@ -96,15 +142,25 @@ impl<'scene> SceneManager<'scene> {
             .scenes
             .get_mut(name)
             .ok_or_else(|| anyhow!("Scene '{name}' not found"))?;
+        self.scene_time = 0.0;
+        ctx.scene_time = 0.0;
         scene.on_start(ctx);
         self.active_scene = Some(name.to_string());
         Ok(())
     }
 
+    /// Switches to the queued scene, if any, then advances the active
+    /// scene's clock by `ctx.delta_time` and updates it.
     pub fn update(&mut self, ctx: &mut Context<'_>) -> Result<()> {
-        if let Some(next_scene) = self.queued_scene.take() {
-            self.activate(&next_scene, ctx)?;
+        let started = self.queued_scene.take();
+        if let Some(next_scene) = &started {
+            self.activate(next_scene, ctx)?;
         }
+        // A scene that has just started sees time 0 on its first update.
+        if started.is_none() {
+            self.scene_time += ctx.delta_time;
+        }
+        ctx.scene_time = self.scene_time;
 
         if let Some(active_name) = self.active_scene.clone() {
             if let Some(scene) = self.scenes.get_mut(&active_name) {
@@ -116,6 +172,7 @@ impl<'scene> SceneManager<'scene> {
     }
 
     pub fn draw(&mut self, ctx: &mut Context<'_>) {
+        ctx.scene_time = self.scene_time;
         if let Some(active_name) = self.active_scene.clone() {
             if let Some(scene) = self.scenes.get_mut(&active_name) {
                 scene.on_draw(ctx);
@@ -135,3 +192,9 @@ impl<'scene> SceneManager<'scene> {
         self.active_scene.as_deref()
     }
 }
+
+impl<'scene> Default for SceneManager<'scene> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
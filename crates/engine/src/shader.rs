@@ -0,0 +1,175 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::color::Rgba;
+use crate::draw::PixelMode;
+use crate::image::{ImageCell, BRAILLE_DOTS, DEFAULT_RAMP};
+
+/// Ordered-dither thresholds for the 2x4 dots of a braille cell, `[y][x]`,
+/// so intermediate intensities light a stable share of the dots.
+const BRAILLE_THRESHOLDS: [[f32; 2]; 4] = [
+    [0.5 / 8.0, 4.5 / 8.0],
+    [6.5 / 8.0, 2.5 / 8.0],
+    [1.5 / 8.0, 5.5 / 8.0],
+    [7.5 / 8.0, 3.5 / 8.0],
+];
+
+/// What a `Canvas::shade` closure returns for one logical pixel. A bare
+/// `f32` converts into a plain intensity.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Shade {
+    /// Brightness, 0–1. Picks the glyph in ASCII, the dot density in
+    /// braille and scales the colour in half blocks.
+    pub intensity: f32,
+    /// Colour of the pixel; `None` uses the canvas' foreground colour.
+    pub color: Option<Rgba>,
+    /// Glyph to draw in ASCII mode instead of one from the density ramp.
+    pub glyph: Option<char>,
+}
+
+impl Shade {
+    pub fn new(intensity: f32) -> Self {
+        Self {
+            intensity,
+            ..Self::default()
+        }
+    }
+
+    /// A fully bright pixel of `color`.
+    pub fn color(color: Rgba) -> Self {
+        Self::new(1.0).with_color(color)
+    }
+
+    pub fn with_color(self, color: Rgba) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    pub fn with_glyph(self, glyph: char) -> Self {
+        Self {
+            glyph: Some(glyph),
+            ..self
+        }
+    }
+}
+
+impl From<f32> for Shade {
+    fn from(intensity: f32) -> Self {
+        Self::new(intensity)
+    }
+}
+
+/// Cells `columns` x `rows` starting at cell `(left, top)`, each computed
+/// from `shader` evaluated at its logical pixels in `mode`.
+pub(crate) fn shade_cells<F, S>(
+    (left, top): (usize, usize),
+    (columns, rows): (usize, usize),
+    mode: PixelMode,
+    time: f32,
+    default_color: Rgba,
+    shader: &F,
+) -> Vec<ImageCell>
+where
+    F: Fn(f32, f32, f32) -> S + Sync,
+    S: Into<Shade>,
+{
+    let (sx, sy) = mode.cell_resolution();
+    let (width, height) = (columns * sx, rows * sy);
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+    let mut shades = vec![Shade::default(); width * height];
+    let (origin_x, origin_y) = (left * sx, top * sy);
+    let shade_row = |(y, line): (usize, &mut [Shade])| {
+        let py = (origin_y + y) as f32;
+        for (x, shade) in line.iter_mut().enumerate() {
+            *shade = shader((origin_x + x) as f32, py, time).into();
+        }
+    };
+    #[cfg(feature = "parallel")]
+    shades.par_chunks_mut(width).enumerate().for_each(shade_row);
+    #[cfg(not(feature = "parallel"))]
+    shades.chunks_mut(width).enumerate().for_each(shade_row);
+
+    let at = |x: usize, y: usize| shades[y * width + x];
+    let mut cells = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        for column in 0..columns {
+            let cell = match mode {
+                PixelMode::Ascii => ascii_cell(at(column, row)),
+                PixelMode::HalfBlock => {
+                    let lit = |shade: Shade| {
+                        let color = shade.color.unwrap_or(default_color);
+                        let factor = shade.intensity.clamp(0.0, 1.0);
+                        let scale = |c: u8| (c as f32 * factor).round() as u8;
+                        Rgba::rgb(scale(color.r), scale(color.g), scale(color.b)).to_color()
+                    };
+                    ImageCell {
+                        symbol: '▀',
+                        fg: Some(lit(at(column, row * 2))),
+                        bg: Some(lit(at(column, row * 2 + 1))),
+                    }
+                }
+                PixelMode::Braille => braille_cell(|dx, dy| at(column * 2 + dx, row * 4 + dy)),
+            };
+            cells.push(cell);
+        }
+    }
+    cells
+}
+
+fn ascii_cell(shade: Shade) -> ImageCell {
+    let symbol = shade.glyph.unwrap_or_else(|| {
+        let last = DEFAULT_RAMP.chars().count() - 1;
+        let level = (shade.intensity.clamp(0.0, 1.0) * last as f32).round() as usize;
+        DEFAULT_RAMP.chars().nth(level).unwrap_or(' ')
+    });
+    ImageCell {
+        symbol,
+        fg: shade.color.map(Rgba::to_color),
+        bg: None,
+    }
+}
+
+/// Lights the dots whose intensity passes the ordered-dither threshold,
+/// coloured with the mean colour of the lit dots.
+fn braille_cell(shade_at: impl Fn(usize, usize) -> Shade) -> ImageCell {
+    let mut bits = 0;
+    let mut sum = [0u32; 3];
+    let mut colored = 0;
+    for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+        for (dx, bit) in dots.iter().enumerate() {
+            let shade = shade_at(dx, dy);
+            if shade.intensity <= BRAILLE_THRESHOLDS[dy][dx] {
+                continue;
+            }
+            bits |= bit;
+            if let Some(color) = shade.color {
+                sum[0] += color.r as u32;
+                sum[1] += color.g as u32;
+                sum[2] += color.b as u32;
+                colored += 1;
+            }
+        }
+    }
+    let fg = (colored > 0).then(|| {
+        Rgba::rgb(
+            (sum[0] / colored) as u8,
+            (sum[1] / colored) as u8,
+            (sum[2] / colored) as u8,
+        )
+        .to_color()
+    });
+    let symbol = if bits == 0 {
+        ' '
+    } else {
+        char::from_u32(0x2800 + bits).unwrap_or(' ')
+    };
+    ImageCell {
+        symbol,
+        fg,
+        bg: None,
+    }
+}
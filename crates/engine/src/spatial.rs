@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use crate::path::Point;
+
+/// Buckets item indices by position on a uniform grid, so neighbours within
+/// a radius can be found without comparing every pair.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialHash {
+    cell_size: f32,
+    buckets: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialHash {
+    /// A hash with square buckets of `cell_size`; queries are cheapest when
+    /// it matches the usual search radius.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            buckets: HashMap::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Empties the buckets, keeping their allocations for the next frame.
+    pub fn clear(&mut self) {
+        self.buckets.values_mut().for_each(Vec::clear);
+    }
+
+    pub fn insert(&mut self, index: usize, position: Point) {
+        self.buckets
+            .entry(self.key(position))
+            .or_default()
+            .push(index);
+    }
+
+    /// Indices in the buckets overlapping the square around `position`
+    /// reaching `radius` in each direction. Callers still filter by exact
+    /// distance.
+    pub fn query(&self, position: Point, radius: f32) -> impl Iterator<Item = usize> + '_ {
+        let (min_x, min_y) = self.key(Point::new(position.x - radius, position.y - radius));
+        let (max_x, max_y) = self.key(Point::new(position.x + radius, position.y + radius));
+        (min_y..=max_y)
+            .flat_map(move |y| (min_x..=max_x).map(move |x| (x, y)))
+            .filter_map(|key| self.buckets.get(&key))
+            .flatten()
+            .copied()
+    }
+
+    /// Like `query`, for items on a torus `width` x `height` with its
+    /// origin at zero: buckets past an edge are looked up on the opposite
+    /// side. Each index is returned once, in ascending order.
+    pub fn query_wrapped(
+        &self,
+        position: Point,
+        radius: f32,
+        width: f32,
+        height: f32,
+    ) -> Vec<usize> {
+        let images = |p: f32, size: f32| {
+            let mut offsets = vec![0.0];
+            if size > 0.0 && p - radius < 0.0 {
+                offsets.push(size);
+            }
+            if size > 0.0 && p + radius >= size {
+                offsets.push(-size);
+            }
+            offsets
+        };
+        let mut found = Vec::new();
+        for dy in images(position.y, height) {
+            for &dx in &images(position.x, width) {
+                found.extend(self.query(Point::new(position.x + dx, position.y + dy), radius));
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    fn key(&self, position: Point) -> (i32, i32) {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.y / self.cell_size).floor() as i32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_only_nearby_buckets() {
+        let mut hash = SpatialHash::new(2.0);
+        hash.insert(0, Point::new(1.0, 1.0));
+        hash.insert(1, Point::new(3.5, 1.0));
+        hash.insert(2, Point::new(9.0, 9.0));
+        let mut found: Vec<usize> = hash.query(Point::new(2.0, 1.0), 1.0).collect();
+        found.sort_unstable();
+        assert_eq!(found, vec![0, 1]);
+
+        hash.clear();
+        assert_eq!(hash.query(Point::new(2.0, 1.0), 1.0).count(), 0);
+    }
+
+    #[test]
+    fn wrapped_queries_reach_across_edges() {
+        let mut hash = SpatialHash::new(2.0);
+        hash.insert(0, Point::new(0.5, 5.0));
+        hash.insert(1, Point::new(9.5, 5.0));
+        hash.insert(2, Point::new(9.5, 9.5));
+        hash.insert(3, Point::new(5.0, 5.0));
+        let (width, height) = (10.0, 10.0);
+        assert_eq!(
+            hash.query_wrapped(Point::new(0.5, 5.0), 1.5, width, height),
+            vec![0, 1]
+        );
+        // Corners wrap both ways at once.
+        assert_eq!(
+            hash.query_wrapped(Point::new(0.2, 0.2), 1.5, width, height),
+            vec![2]
+        );
+        // A radius covering the whole torus still finds each item once.
+        assert_eq!(
+            hash.query_wrapped(Point::new(5.0, 5.0), 8.0, width, height),
+            vec![0, 1, 2, 3]
+        );
+    }
+}
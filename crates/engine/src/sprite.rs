@@ -0,0 +1,423 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use crossterm::style::Color;
+
+use crate::color::parse_color;
+use crate::text::{char_width, text_width};
+
+const FRAME_DELIMITER: &str = "---";
+const COLOR_MAP_DELIMITER: &str = "~~~";
+const DEFAULT_FRAME_MS: u64 = 100;
+
+/// Mirroring applied when drawing a sprite.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Flip {
+    #[default]
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl Flip {
+    pub(crate) fn horizontal(self) -> bool {
+        matches!(self, Flip::Horizontal | Flip::Both)
+    }
+
+    fn vertical(self) -> bool {
+        matches!(self, Flip::Vertical | Flip::Both)
+    }
+}
+
+/// A visible sprite cell. Cells without an explicit colour use the canvas foreground.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteCell {
+    pub symbol: char,
+    pub color: Option<Color>,
+}
+
+/// One frame of sprite art; `None` cells are transparent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteFrame {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<Option<SpriteCell>>,
+}
+
+impl SpriteFrame {
+    pub fn cell(&self, x: usize, y: usize) -> Option<SpriteCell> {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x]
+        } else {
+            None
+        }
+    }
+}
+
+/// Multi-frame ASCII art loaded from a `.sprite` text file.
+///
+/// The file starts with optional `key: value` header lines, followed by
+/// frames that each begin with a `---` line. A frame's art may be followed by
+/// a `~~~` line and a colour map of the same shape whose characters are
+/// palette keys:
+///
+/// ```text
+/// # Lines starting with '#' in the header are comments.
+/// transparent: .
+/// frame_ms: 150
+/// color r: red
+/// color y: #ffcc00
+/// ---
+/// .O.
+/// /|\
+/// ~~~
+/// .y.
+/// rrr
+/// ---
+/// \O/
+/// .|.
+/// ```
+///
+/// `transparent` defaults to a space. Colour map cells that are blank or not
+/// in the palette keep the canvas foreground colour. Wide glyphs such as CJK
+/// take two columns, in the art and in its colour map; combining marks are
+/// dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    frames: Vec<SpriteFrame>,
+    frame_duration: f32,
+}
+
+impl Sprite {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sprite at {}", path.display()))?;
+        Self::parse(&raw).with_context(|| format!("Failed to parse sprite at {}", path.display()))
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut transparent = ' ';
+        let mut frame_ms = DEFAULT_FRAME_MS;
+        let mut palette: HashMap<char, Color> = HashMap::new();
+
+        let mut lines = source.lines().enumerate();
+        for (number, line) in lines.by_ref() {
+            let line = line.trim_end();
+            if line == FRAME_DELIMITER {
+                break;
+            }
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| anyhow!("Line {}: expected 'key: value'", number + 1))?;
+            let value = value.trim();
+            match key.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["transparent"] => {
+                    transparent = single_char(value).ok_or_else(|| {
+                        anyhow!("Line {}: transparent key must be one character", number + 1)
+                    })?;
+                }
+                ["frame_ms"] => {
+                    frame_ms = value
+                        .parse()
+                        .with_context(|| format!("Line {}: invalid frame_ms", number + 1))?;
+                }
+                ["color", key] => {
+                    let key = single_char(key).ok_or_else(|| {
+                        anyhow!("Line {}: palette key must be one character", number + 1)
+                    })?;
+                    let color = parse_color(value)
+                        .ok_or_else(|| anyhow!("Line {}: unknown colour '{value}'", number + 1))?;
+                    palette.insert(key, color);
+                }
+                _ => bail!("Line {}: unknown header key '{}'", number + 1, key.trim()),
+            }
+        }
+
+        let mut frames = Vec::new();
+        let mut art: Vec<&str> = Vec::new();
+        let mut colors: Option<Vec<&str>> = None;
+        for (_, line) in lines {
+            let line = line.trim_end_matches('\r');
+            match line.trim_end() {
+                FRAME_DELIMITER => {
+                    if !art.is_empty() {
+                        frames.push(build_frame(&art, colors.as_deref(), transparent, &palette));
+                    }
+                    art.clear();
+                    colors = None;
+                }
+                COLOR_MAP_DELIMITER => colors = Some(Vec::new()),
+                _ => match colors.as_mut() {
+                    Some(map) => map.push(line),
+                    None => art.push(line),
+                },
+            }
+        }
+        if !art.is_empty() {
+            frames.push(build_frame(&art, colors.as_deref(), transparent, &palette));
+        }
+        if frames.is_empty() {
+            bail!("Sprite has no frames");
+        }
+
+        Ok(Self {
+            frames,
+            frame_duration: frame_ms.max(1) as f32 / 1_000.0,
+        })
+    }
+
+    pub fn frames(&self) -> &[SpriteFrame] {
+        &self.frames
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Seconds each frame stays on screen.
+    pub fn frame_duration(&self) -> f32 {
+        self.frame_duration
+    }
+
+    pub fn set_frame_duration(&mut self, seconds: f32) {
+        self.frame_duration = seconds.max(f32::EPSILON);
+    }
+
+    /// Index of the frame to show `seconds` into a looping animation, e.g. `ctx.total_time`.
+    pub fn frame_at(&self, seconds: f32) -> usize {
+        let step = (seconds.max(0.0) / self.frame_duration) as usize;
+        step % self.frames.len()
+    }
+
+    /// Largest frame size, useful for centring and collision boxes.
+    pub fn size(&self) -> (usize, usize) {
+        let width = self.frames.iter().map(|f| f.width).max().unwrap_or(0);
+        let height = self.frames.iter().map(|f| f.height).max().unwrap_or(0);
+        (width, height)
+    }
+}
+
+/// The glyph that looks like `symbol` seen in a mirror.
+pub(crate) fn mirror_symbol(symbol: char, flip: Flip) -> char {
+    let symbol = if flip.horizontal() {
+        match symbol {
+            '/' => '\\',
+            '\\' => '/',
+            '(' => ')',
+            ')' => '(',
+            '[' => ']',
+            ']' => '[',
+            '{' => '}',
+            '}' => '{',
+            '<' => '>',
+            '>' => '<',
+            other => other,
+        }
+    } else {
+        symbol
+    };
+    if flip.vertical() {
+        match symbol {
+            '/' => '\\',
+            '\\' => '/',
+            '^' => 'v',
+            'v' => '^',
+            '▀' => '▄',
+            '▄' => '▀',
+            other => other,
+        }
+    } else {
+        symbol
+    }
+}
+
+/// Frame-local coordinates of the cell drawn at `(x, y)` under `flip`.
+pub(crate) fn flipped_source(
+    frame: &SpriteFrame,
+    x: usize,
+    y: usize,
+    flip: Flip,
+) -> (usize, usize) {
+    let sx = if flip.horizontal() {
+        frame.width - 1 - x
+    } else {
+        x
+    };
+    let sy = if flip.vertical() {
+        frame.height - 1 - y
+    } else {
+        y
+    };
+    (sx, sy)
+}
+
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+fn build_frame(
+    art: &[&str],
+    colors: Option<&[&str]>,
+    transparent: char,
+    palette: &HashMap<char, Color>,
+) -> SpriteFrame {
+    let width = art.iter().map(|line| text_width(line)).max().unwrap_or(0);
+    let height = art.len();
+    let mut cells = vec![None; width * height];
+    for (y, line) in art.iter().enumerate() {
+        let color_row: Vec<char> = colors
+            .and_then(|map| map.get(y))
+            .map(|row| row.chars().collect())
+            .unwrap_or_default();
+        let mut x = 0;
+        for symbol in line.chars() {
+            let glyph_width = char_width(symbol);
+            if glyph_width == 0 {
+                continue;
+            }
+            if symbol != transparent {
+                let color = color_row.get(x).and_then(|key| palette.get(key)).copied();
+                cells[y * width + x] = Some(SpriteCell { symbol, color });
+            }
+            x += glyph_width;
+        }
+    }
+    SpriteFrame {
+        width,
+        height,
+        cells,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAVE: &str = "\
+# A waving figure.
+transparent: .
+frame_ms: 150
+color r: red
+color y: #ffcc00
+---
+.O.
+/|\\
+~~~
+.y.
+r r
+---
+\\O/
+.|.
+";
+
+    #[test]
+    fn parses_header_frames_and_colours() {
+        let sprite = Sprite::parse(WAVE).unwrap();
+        assert_eq!(sprite.frame_count(), 2);
+        assert_eq!(sprite.frame_duration(), 0.15);
+        assert_eq!(sprite.size(), (3, 2));
+
+        let first = &sprite.frames()[0];
+        assert_eq!(first.cell(0, 0), None);
+        let yellow = Color::Rgb {
+            r: 255,
+            g: 204,
+            b: 0,
+        };
+        assert_eq!(
+            first.cell(1, 0),
+            Some(SpriteCell {
+                symbol: 'O',
+                color: Some(yellow)
+            })
+        );
+        assert_eq!(first.cell(0, 1).unwrap().color, parse_color("red"));
+        // A blank colour map cell keeps the canvas foreground.
+        assert_eq!(first.cell(1, 1).unwrap().color, None);
+        assert_eq!(first.cell(3, 0), None);
+
+        let second = &sprite.frames()[1];
+        assert_eq!(second.cell(0, 0).unwrap().symbol, '\\');
+        assert_eq!(second.cell(0, 1), None);
+    }
+
+    #[test]
+    fn defaults_to_space_transparency() {
+        let sprite = Sprite::parse("---\n# #\n").unwrap();
+        let frame = &sprite.frames()[0];
+        assert_eq!(frame.cell(0, 0).unwrap().symbol, '#');
+        assert_eq!(frame.cell(1, 0), None);
+        assert_eq!(sprite.frame_duration(), DEFAULT_FRAME_MS as f32 / 1_000.0);
+    }
+
+    #[test]
+    fn pads_ragged_frames() {
+        let sprite = Sprite::parse("---\nab\nabcd\n").unwrap();
+        let frame = &sprite.frames()[0];
+        assert_eq!((frame.width, frame.height), (4, 2));
+        assert_eq!(frame.cell(3, 0), None);
+        assert_eq!(frame.cell(3, 1).unwrap().symbol, 'd');
+    }
+
+    #[test]
+    fn lays_out_wide_glyphs_by_display_width() {
+        let sprite = Sprite::parse("color r: red\n---\n日x\ne\u{301}.\n~~~\nrrr\n").unwrap();
+        let frame = &sprite.frames()[0];
+        assert_eq!((frame.width, frame.height), (3, 2));
+        assert_eq!(frame.cell(0, 0).unwrap().symbol, '日');
+        assert_eq!(frame.cell(1, 0), None);
+        assert_eq!(frame.cell(2, 0).unwrap().symbol, 'x');
+        assert_eq!(frame.cell(2, 0).unwrap().color, parse_color("red"));
+        // Combining marks take no column of their own.
+        assert_eq!(frame.cell(1, 1).unwrap().symbol, '.');
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        for source in [
+            "",
+            "---\n",
+            "size 3\n---\nx\n",
+            "transparent: ab\n---\nx\n",
+            "frame_ms: fast\n---\nx\n",
+            "color rr: red\n---\nx\n",
+            "color r: nope\n---\nx\n",
+            "speed: 3\n---\nx\n",
+        ] {
+            assert!(Sprite::parse(source).is_err(), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn loops_through_frames() {
+        let sprite = Sprite::parse(WAVE).unwrap();
+        assert_eq!(sprite.frame_at(0.0), 0);
+        assert_eq!(sprite.frame_at(0.16), 1);
+        assert_eq!(sprite.frame_at(0.31), 0);
+        assert_eq!(sprite.frame_at(-1.0), 0);
+    }
+
+    #[test]
+    fn mirrors_symbols_and_coordinates() {
+        assert_eq!(mirror_symbol('/', Flip::Horizontal), '\\');
+        assert_eq!(mirror_symbol('(', Flip::Horizontal), ')');
+        assert_eq!(mirror_symbol('^', Flip::Vertical), 'v');
+        assert_eq!(mirror_symbol('/', Flip::Both), '/');
+        assert_eq!(mirror_symbol('x', Flip::Both), 'x');
+
+        let sprite = Sprite::parse(WAVE).unwrap();
+        let frame = &sprite.frames()[0];
+        assert_eq!(flipped_source(frame, 0, 0, Flip::None), (0, 0));
+        assert_eq!(flipped_source(frame, 0, 0, Flip::Horizontal), (2, 0));
+        assert_eq!(flipped_source(frame, 0, 0, Flip::Both), (2, 1));
+    }
+}
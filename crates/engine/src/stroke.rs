@@ -0,0 +1,365 @@
+use std::f32::consts::PI;
+
+use serde::Deserialize;
+
+use crate::path::{arc_segment_count, Point};
+
+/// How the open ends of a stroke are finished.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineCap {
+    /// The stroke stops exactly at the end point.
+    #[default]
+    Butt,
+    /// A half-disc centred on the end point.
+    Round,
+    /// The stroke extends half its width past the end point.
+    Square,
+}
+
+/// Outline settings used by every stroking primitive on `Canvas`.
+///
+/// Widths and dash lengths are measured in logical pixels before the canvas
+/// transform is applied, so scaling the canvas also thickens its strokes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f32,
+    /// Alternating on/off lengths. An odd-length pattern is repeated to make it even.
+    pub dash: Vec<f32>,
+    /// Distance into the dash pattern at which strokes start. Animating this
+    /// produces the classic "marching ants" effect.
+    pub dash_offset: f32,
+    pub cap: LineCap,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+            cap: LineCap::Butt,
+        }
+    }
+}
+
+impl StrokeStyle {
+    /// True for the default one-pixel solid stroke, which can use Bresenham directly.
+    pub fn is_hairline(&self) -> bool {
+        self.width <= 1.0 && self.dash_pattern().is_none()
+    }
+
+    /// The dash pattern normalised to an even number of entries, or `None` for solid strokes.
+    pub(crate) fn dash_pattern(&self) -> Option<Vec<f32>> {
+        if self.dash.is_empty() || self.dash.iter().any(|d| !d.is_finite() || *d < 0.0) {
+            return None;
+        }
+        if self.dash.iter().sum::<f32>() <= 0.0 {
+            return None;
+        }
+        let mut pattern = self.dash.clone();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_slice(&self.dash);
+        }
+        Some(pattern)
+    }
+}
+
+/// Splits a polyline into the "on" pieces of a dash pattern starting `offset` into it.
+pub(crate) fn dash_polyline(
+    points: &[Point],
+    closed: bool,
+    pattern: &[f32],
+    offset: f32,
+) -> Vec<Vec<Point>> {
+    let mut dashes = Vec::new();
+    if points.len() < 2 {
+        return dashes;
+    }
+
+    let total: f32 = pattern.iter().sum();
+    let mut index = 0;
+    let mut remaining = offset.rem_euclid(total);
+    while remaining > 0.0 && remaining >= pattern[index] {
+        remaining -= pattern[index];
+        index = (index + 1) % pattern.len();
+    }
+    remaining = pattern[index] - remaining;
+    let mut on = index % 2 == 0;
+
+    let mut current = if on { vec![points[0]] } else { Vec::new() };
+    let closing = closed.then(|| (points[points.len() - 1], points[0]));
+    let segments = points.windows(2).map(|w| (w[0], w[1])).chain(closing);
+
+    for (a, b) in segments {
+        let length = a.distance(b);
+        let mut travelled = 0.0;
+        while length - travelled > remaining {
+            travelled += remaining;
+            let split = a.lerp(b, travelled / length);
+            if on {
+                current.push(split);
+                dashes.push(std::mem::take(&mut current));
+            } else {
+                current = vec![split];
+            }
+            on = !on;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= length - travelled;
+        if on {
+            current.push(b);
+        }
+    }
+    if on && current.len() >= 2 {
+        dashes.push(current);
+    }
+
+    dashes
+}
+
+/// Builds the polygons covering a thick stroke along `points`. Interior
+/// vertices get round joins; the open ends are finished with `cap`.
+///
+/// Every polygon is wound the same way so the union can be filled in a single
+/// non-zero pass without cells being plotted twice.
+pub(crate) fn stroke_outline(
+    points: &[Point],
+    closed: bool,
+    half_width: f32,
+    cap: LineCap,
+    tolerance: f32,
+) -> Vec<Vec<Point>> {
+    let mut rings = Vec::new();
+    if points.is_empty() {
+        return rings;
+    }
+
+    let last = points.len() - 1;
+    let closing = closed.then(|| (points[last], points[0]));
+    let segments: Vec<(Point, Point)> = points
+        .windows(2)
+        .map(|w| (w[0], w[1]))
+        .chain(closing)
+        .collect();
+
+    for (i, &(mut a, mut b)) in segments.iter().enumerate() {
+        let length = a.distance(b);
+        if length <= f32::EPSILON {
+            continue;
+        }
+        let (ux, uy) = ((b.x - a.x) / length, (b.y - a.y) / length);
+        if !closed && cap == LineCap::Square {
+            if i == 0 {
+                a = Point::new(a.x - ux * half_width, a.y - uy * half_width);
+            }
+            if i == segments.len() - 1 {
+                b = Point::new(b.x + ux * half_width, b.y + uy * half_width);
+            }
+        }
+        let (nx, ny) = (-uy * half_width, ux * half_width);
+        rings.push(oriented(vec![
+            Point::new(a.x + nx, a.y + ny),
+            Point::new(b.x + nx, b.y + ny),
+            Point::new(b.x - nx, b.y - ny),
+            Point::new(a.x - nx, a.y - ny),
+        ]));
+    }
+
+    let join_range = if closed { 0..points.len() } else { 1..last };
+    for i in join_range {
+        rings.push(disc(points[i], half_width, tolerance));
+    }
+    if !closed && cap == LineCap::Round {
+        rings.push(disc(points[0], half_width, tolerance));
+        if last > 0 {
+            rings.push(disc(points[last], half_width, tolerance));
+        }
+    }
+
+    rings
+}
+
+fn disc(center: Point, radius: f32, tolerance: f32) -> Vec<Point> {
+    let steps = arc_segment_count(radius, 2.0 * PI, tolerance).max(8);
+    let ring = (0..steps)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f32 / steps as f32;
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect();
+    oriented(ring)
+}
+
+/// Reverses `ring` if needed so its signed area is positive.
+fn oriented(mut ring: Vec<Point>) -> Vec<Point> {
+    let area: f32 = ring
+        .iter()
+        .zip(ring.iter().cycle().skip(1))
+        .map(|(p, q)| p.x * q.y - q.x * p.y)
+        .sum();
+    if area < 0.0 {
+        ring.reverse();
+    }
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(points: &[(f32, f32)]) -> Vec<Point> {
+        points.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    /// Dashes as rounded coordinates, for exact comparison.
+    fn dashes(
+        points: &[(f32, f32)],
+        closed: bool,
+        pattern: &[f32],
+        offset: f32,
+    ) -> Vec<Vec<(i32, i32)>> {
+        dash_polyline(&line(points), closed, pattern, offset)
+            .iter()
+            .map(|dash| {
+                dash.iter()
+                    .map(|p| ((p.x * 10.0).round() as i32, (p.y * 10.0).round() as i32))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Horizontal extent of every ring together.
+    fn x_bounds(rings: &[Vec<Point>]) -> (f32, f32) {
+        let xs = rings.iter().flatten().map(|p| p.x);
+        (
+            xs.clone().fold(f32::INFINITY, f32::min),
+            xs.fold(f32::NEG_INFINITY, f32::max),
+        )
+    }
+
+    fn signed_area(ring: &[Point]) -> f32 {
+        ring.iter()
+            .zip(ring.iter().cycle().skip(1))
+            .map(|(p, q)| p.x * q.y - q.x * p.y)
+            .sum()
+    }
+
+    #[test]
+    fn dash_patterns_are_normalised() {
+        let style = |dash: Vec<f32>| StrokeStyle {
+            dash,
+            ..StrokeStyle::default()
+        };
+        assert_eq!(style(vec![3.0]).dash_pattern(), Some(vec![3.0, 3.0]));
+        assert_eq!(
+            style(vec![2.0, 1.0, 3.0]).dash_pattern(),
+            Some(vec![2.0, 1.0, 3.0, 2.0, 1.0, 3.0])
+        );
+        assert_eq!(style(vec![]).dash_pattern(), None);
+        assert_eq!(style(vec![0.0, 0.0]).dash_pattern(), None);
+        assert_eq!(style(vec![2.0, -1.0]).dash_pattern(), None);
+        assert!(StrokeStyle::default().is_hairline());
+        assert!(!style(vec![1.0]).is_hairline());
+    }
+
+    #[test]
+    fn dashes_follow_the_pattern() {
+        let straight = [(0.0, 0.0), (10.0, 0.0)];
+        assert_eq!(
+            dashes(&straight, false, &[2.0, 2.0], 0.0),
+            vec![
+                vec![(0, 0), (20, 0)],
+                vec![(40, 0), (60, 0)],
+                vec![(80, 0), (100, 0)]
+            ]
+        );
+        // An odd pattern is doubled up, so its entries alternate as gaps.
+        let odd = StrokeStyle {
+            dash: vec![3.0],
+            ..StrokeStyle::default()
+        };
+        assert_eq!(
+            dashes(&straight, false, &odd.dash_pattern().unwrap(), 0.0),
+            vec![vec![(0, 0), (30, 0)], vec![(60, 0), (90, 0)]]
+        );
+    }
+
+    #[test]
+    fn dash_offsets_wrap_around_the_pattern() {
+        let straight = [(0.0, 0.0), (10.0, 0.0)];
+        let shifted = vec![
+            vec![(0, 0), (10, 0)],
+            vec![(30, 0), (50, 0)],
+            vec![(70, 0), (90, 0)],
+        ];
+        assert_eq!(dashes(&straight, false, &[2.0, 2.0], 1.0), shifted);
+        assert_eq!(dashes(&straight, false, &[2.0, 2.0], 5.0), shifted);
+        assert_eq!(dashes(&straight, false, &[2.0, 2.0], -3.0), shifted);
+        assert_eq!(
+            dashes(&straight, false, &[2.0, 2.0], 4.0),
+            dashes(&straight, false, &[2.0, 2.0], 0.0)
+        );
+    }
+
+    #[test]
+    fn closed_paths_dash_around_corners_and_back_to_the_start() {
+        let square = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+        assert_eq!(
+            dashes(&square, true, &[6.0, 2.0], 0.0),
+            vec![
+                vec![(0, 0), (40, 0), (40, 20)],
+                vec![(40, 40), (0, 40), (0, 20)]
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_length_segments_are_harmless() {
+        let points = [(0.0, 0.0), (0.0, 0.0), (4.0, 0.0)];
+        let dashed = dash_polyline(&line(&points), false, &[2.0, 2.0], 0.0);
+        assert_eq!(dashed.len(), 1);
+        assert!(dashed
+            .iter()
+            .flatten()
+            .all(|p| p.x.is_finite() && p.y.is_finite()));
+
+        let rings = stroke_outline(&line(&points), false, 1.0, LineCap::Butt, 0.1);
+        assert!(rings
+            .iter()
+            .flatten()
+            .all(|p| p.x.is_finite() && p.y.is_finite()));
+        assert_eq!(x_bounds(&rings), (-1.0, 4.0));
+    }
+
+    #[test]
+    fn caps_finish_open_ends() {
+        let points = line(&[(0.0, 0.0), (4.0, 0.0)]);
+        let outline = |cap| stroke_outline(&points, false, 1.0, cap, 0.1);
+
+        let butt = outline(LineCap::Butt);
+        assert_eq!(butt.len(), 1);
+        assert_eq!(x_bounds(&butt), (0.0, 4.0));
+
+        let square = outline(LineCap::Square);
+        assert_eq!(square.len(), 1);
+        assert_eq!(x_bounds(&square), (-1.0, 5.0));
+
+        let round = outline(LineCap::Round);
+        assert_eq!(round.len(), 3);
+        assert_eq!(x_bounds(&round), (-1.0, 5.0));
+    }
+
+    #[test]
+    fn closed_outlines_join_every_vertex_without_caps() {
+        let triangle = line(&[(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)]);
+        let rings = stroke_outline(&triangle, true, 0.5, LineCap::Square, 0.1);
+        // Three segments and three round joins.
+        assert_eq!(rings.len(), 6);
+        assert!(rings.iter().all(|ring| signed_area(ring) > 0.0));
+    }
+}
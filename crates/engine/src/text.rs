@@ -0,0 +1,163 @@
+use serde::Deserialize;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub const ELLIPSIS: char = '…';
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Number of terminal cells `c` occupies: 0 for combining marks and other
+/// zero-width characters, 2 for wide CJK and emoji glyphs, 1 otherwise.
+/// Control characters report 0 and are skipped by the text renderer.
+pub fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+pub fn text_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Shortens `text` to at most `max_width` cells, replacing the cut-off tail with `…`.
+pub fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
+    if text_width(text) <= max_width {
+        return text.to_string();
+    }
+    with_ellipsis(text, max_width)
+}
+
+/// Appends `…` to `text`, dropping trailing characters until the result fits in `max_width`.
+fn with_ellipsis(text: &str, max_width: usize) -> String {
+    if max_width == 0 {
+        return String::new();
+    }
+    let budget = max_width - 1;
+    let mut used = 0;
+    let mut result = String::new();
+    for c in text.chars() {
+        let w = char_width(c);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        result.push(c);
+    }
+    result.push(ELLIPSIS);
+    result
+}
+
+/// Breaks `text` into lines no wider than `width` cells.
+///
+/// Lines break at whitespace where possible; words wider than the whole line
+/// are split between characters, and a glyph wider than the line becomes `…`.
+/// Explicit newlines start a new line. When
+/// `max_lines` is given and the text does not fit, the last kept line ends in `…`.
+pub fn wrap_text(text: &str, width: usize, max_lines: Option<usize>) -> Vec<String> {
+    let mut lines = Vec::new();
+    if width == 0 {
+        return lines;
+    }
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split_whitespace() {
+            let word_width = text_width(word);
+            if line_width > 0 && line_width + 1 + word_width <= width {
+                line.push(' ');
+                line.push_str(word);
+                line_width += 1 + word_width;
+                continue;
+            }
+            if line_width > 0 {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            if word_width <= width {
+                line.push_str(word);
+                line_width = word_width;
+                continue;
+            }
+            for c in word.chars() {
+                // A glyph wider than the whole line can never fit.
+                let (c, w) = match char_width(c) {
+                    w if w > width => (ELLIPSIS, 1),
+                    w => (c, w),
+                };
+                if line_width > 0 && line_width + w > width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                line.push(c);
+                line_width += w;
+            }
+        }
+        lines.push(line);
+    }
+
+    if let Some(max_lines) = max_lines {
+        if lines.len() > max_lines {
+            lines.truncate(max_lines);
+            if let Some(last) = lines.last_mut() {
+                *last = with_ellipsis(last, width.min(text_width(last) + 1));
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths_count_wide_and_zero_width_glyphs() {
+        assert_eq!(text_width("abc"), 3);
+        assert_eq!(text_width("日本"), 4);
+        assert_eq!(char_width('\u{301}'), 0);
+    }
+
+    #[test]
+    fn truncates_to_the_width_with_an_ellipsis() {
+        assert_eq!(truncate_with_ellipsis("hello", 5), "hello");
+        assert_eq!(truncate_with_ellipsis("hello world", 6), "hello…");
+        assert_eq!(truncate_with_ellipsis("日本語", 4), "日…");
+        assert_eq!(truncate_with_ellipsis("hello", 0), "");
+    }
+
+    #[test]
+    fn wraps_at_whitespace() {
+        assert_eq!(
+            wrap_text("the quick brown fox", 10, None),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(wrap_text("a\n\nb", 10, None), vec!["a", "", "b"]);
+    }
+
+    #[test]
+    fn splits_words_longer_than_the_line() {
+        assert_eq!(wrap_text("abcdefgh", 3, None), vec!["abc", "def", "gh"]);
+        assert_eq!(wrap_text("日本語", 4, None), vec!["日本", "語"]);
+    }
+
+    #[test]
+    fn replaces_glyphs_wider_than_the_line() {
+        assert_eq!(wrap_text("日本", 1, None), vec!["…", "…"]);
+        assert_eq!(wrap_text("a日", 1, None), vec!["a", "…"]);
+    }
+
+    #[test]
+    fn marks_dropped_lines_with_an_ellipsis() {
+        assert_eq!(
+            wrap_text("one two three four", 9, Some(1)),
+            vec!["one two…"]
+        );
+        assert_eq!(wrap_text("one two", 3, Some(1)), vec!["on…"]);
+    }
+}
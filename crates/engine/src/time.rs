@@ -32,3 +32,64 @@ impl Clock {
         elapsed.as_secs_f32()
     }
 }
+
+/// Turns variable frame times into a whole number of fixed simulation
+/// steps, so physics behaves the same at any frame rate.
+///
+/// ```text
+/// for _ in 0..self.stepper.advance(ctx.delta_time) {
+///     particles.update(self.stepper.step(), rng);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedStep {
+    step: f32,
+    accumulator: f32,
+    max_steps: u32,
+}
+
+impl FixedStep {
+    /// Steps `rate` times per second, running at most 8 per frame so a
+    /// stalled frame cannot trigger a long catch-up.
+    pub fn new(rate: f32) -> Self {
+        Self {
+            step: 1.0 / rate.max(f32::EPSILON),
+            accumulator: 0.0,
+            max_steps: 8,
+        }
+    }
+
+    pub fn with_max_steps(self, max_steps: u32) -> Self {
+        Self { max_steps, ..self }
+    }
+
+    /// Changes the number of steps per second, keeping the time already
+    /// accumulated towards the next step.
+    pub fn set_rate(&mut self, rate: f32) {
+        self.step = 1.0 / rate.max(f32::EPSILON);
+    }
+
+    /// Seconds per step.
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// Adds a frame's elapsed time and returns how many steps to run now.
+    /// Time beyond the step limit is dropped.
+    pub fn advance(&mut self, delta_time: f32) -> u32 {
+        self.accumulator += delta_time.max(0.0);
+        let steps = (self.accumulator / self.step).floor() as u32;
+        if steps > self.max_steps {
+            self.accumulator = 0.0;
+            return self.max_steps;
+        }
+        self.accumulator -= steps as f32 * self.step;
+        steps
+    }
+
+    /// How far (0–1) the leftover time is into the next step, for
+    /// interpolating between the last two simulated states.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+}
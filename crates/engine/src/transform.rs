@@ -0,0 +1,207 @@
+use crate::path::Point;
+
+/// A 2D affine transform stored as the matrix
+///
+/// ```text
+/// | a c e |
+/// | b d f |
+/// | 0 0 1 |
+/// ```
+///
+/// Points are column vectors, so `apply` computes `(a*x + c*y + e, b*x + d*y + f)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self {
+            e: x,
+            f: y,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Rotation by `radians`. With the y axis pointing down the screen,
+    /// positive angles turn clockwise.
+    pub fn rotation(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    pub fn scaling(sx: f32, sy: f32) -> Self {
+        Self {
+            a: sx,
+            d: sy,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Returns `self * other`: the result applies `other` first, then `self`.
+    pub fn multiply(&self, other: &Transform) -> Transform {
+        Transform {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    /// The transform undoing `self`, e.g. to map screen positions back into
+    /// local coordinates. `None` when it collapses the plane (a zero scale).
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        Some(Transform {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+
+    pub fn apply(&self, point: Point) -> Point {
+        Point::new(
+            self.a * point.x + self.c * point.y + self.e,
+            self.b * point.x + self.d * point.y + self.f,
+        )
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// True when the transform only moves points, so integer rasterizers can be reused as-is.
+    pub fn is_translation(&self) -> bool {
+        self.a == 1.0 && self.b == 0.0 && self.c == 0.0 && self.d == 1.0
+    }
+
+    /// The largest factor by which the transform stretches any direction.
+    pub fn max_scale(&self) -> f32 {
+        let sx = self.a.hypot(self.b);
+        let sy = self.c.hypot(self.d);
+        sx.max(sy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_near(actual: Point, expected: (f32, f32)) {
+        assert!(
+            (actual.x - expected.0).abs() < 1e-4 && (actual.y - expected.1).abs() < 1e-4,
+            "expected {expected:?}, got ({}, {})",
+            actual.x,
+            actual.y
+        );
+    }
+
+    /// `transform` applied around `pivot` instead of the origin.
+    fn about(pivot: (f32, f32), transform: Transform) -> Transform {
+        Transform::translation(pivot.0, pivot.1)
+            .multiply(&transform)
+            .multiply(&Transform::translation(-pivot.0, -pivot.1))
+    }
+
+    #[test]
+    fn multiply_applies_the_right_hand_transform_first() {
+        let move_then_double =
+            Transform::scaling(2.0, 2.0).multiply(&Transform::translation(1.0, 0.0));
+        let double_then_move =
+            Transform::translation(1.0, 0.0).multiply(&Transform::scaling(2.0, 2.0));
+        assert_near(move_then_double.apply(Point::new(1.0, 1.0)), (4.0, 2.0));
+        assert_near(double_then_move.apply(Point::new(1.0, 1.0)), (3.0, 2.0));
+        assert_eq!(
+            Transform::IDENTITY.multiply(&move_then_double),
+            move_then_double
+        );
+    }
+
+    #[test]
+    fn rotation_turns_clockwise_on_screen() {
+        let quarter = Transform::rotation(FRAC_PI_2);
+        assert_near(quarter.apply(Point::new(1.0, 0.0)), (0.0, 1.0));
+        assert_near(quarter.apply(Point::new(0.0, 1.0)), (-1.0, 0.0));
+        assert!((quarter.max_scale() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rotation_and_scaling_about_a_point_keep_it_fixed() {
+        let pivot = (3.0, 2.0);
+        let spin = about(pivot, Transform::rotation(FRAC_PI_2));
+        assert_near(spin.apply(Point::new(3.0, 2.0)), pivot);
+        assert_near(spin.apply(Point::new(4.0, 2.0)), (3.0, 3.0));
+
+        let grow = about(pivot, Transform::scaling(2.0, 3.0));
+        assert_near(grow.apply(Point::new(3.0, 2.0)), pivot);
+        assert_near(grow.apply(Point::new(4.0, 3.0)), (5.0, 5.0));
+        assert_eq!(grow.max_scale(), 3.0);
+        assert!(!grow.is_translation());
+    }
+
+    #[test]
+    fn inverse_undoes_the_transform() {
+        let transform = Transform::translation(5.0, -2.0)
+            .multiply(&Transform::rotation(0.7))
+            .multiply(&Transform::scaling(2.0, 0.5));
+        let inverse = transform.inverse().unwrap();
+        for p in [
+            Point::new(0.0, 0.0),
+            Point::new(3.0, -4.0),
+            Point::new(-1.5, 8.0),
+        ] {
+            let back = inverse.apply(transform.apply(p));
+            assert_near(back, (p.x, p.y));
+        }
+        assert_eq!(
+            Transform::translation(2.0, 3.0).inverse(),
+            Some(Transform::translation(-2.0, -3.0))
+        );
+        assert_eq!(Transform::IDENTITY.inverse(), Some(Transform::IDENTITY));
+        assert_eq!(Transform::scaling(0.0, 1.0).inverse(), None);
+    }
+
+    #[test]
+    fn identity_and_translation_checks() {
+        assert!(Transform::default().is_identity());
+        assert!(Transform::translation(1.0, 2.0).is_translation());
+        assert!(!Transform::translation(1.0, 2.0).is_identity());
+        assert!(!Transform::rotation(0.1).is_translation());
+    }
+}
@@ -0,0 +1,203 @@
+use crossterm::style::Color;
+
+use crate::draw::Canvas;
+use crate::path::{Path, Point};
+
+/// Where the turtle is and how it draws. Saved and restored by
+/// `Turtle::push`/`Turtle::pop`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurtleState {
+    pub position: Point,
+    /// Degrees counter-clockwise from east, so 90 points up the screen.
+    pub heading: f32,
+    pub pen_down: bool,
+    /// Line colour; `None` keeps the canvas' foreground colour.
+    pub color: Option<Color>,
+    /// Line glyph; `None` keeps the canvas' symbol.
+    pub symbol: Option<char>,
+    /// Line width in cells; `None` keeps the canvas' stroke width.
+    pub stroke_width: Option<f32>,
+}
+
+/// Logo-style turtle graphics on a `Canvas`.
+///
+/// Positions are floats in the canvas' local coordinates, so the transform,
+/// stroke style and clip rectangle all apply. Because cells are about twice
+/// as tall as they are wide, vertical movement is scaled by `aspect`
+/// (0.5 by default) so a square walk looks square.
+pub struct Turtle<'c, 'a> {
+    canvas: &'c mut Canvas<'a>,
+    state: TurtleState,
+    stack: Vec<TurtleState>,
+    aspect: f32,
+}
+
+impl<'c, 'a> Turtle<'c, 'a> {
+    /// A turtle at the centre of `canvas`, facing up with its pen down.
+    pub fn new(canvas: &'c mut Canvas<'a>) -> Self {
+        let center = Point::new(canvas.width as f32 / 2.0, canvas.height as f32 / 2.0);
+        Self {
+            canvas,
+            state: TurtleState {
+                position: center,
+                heading: 90.0,
+                pen_down: true,
+                color: None,
+                symbol: None,
+                stroke_width: None,
+            },
+            stack: Vec::new(),
+            aspect: 0.5,
+        }
+    }
+
+    /// The canvas being drawn on, e.g. to change settings the turtle leaves alone.
+    pub fn canvas(&mut self) -> &mut Canvas<'a> {
+        self.canvas
+    }
+
+    pub fn state(&self) -> &TurtleState {
+        &self.state
+    }
+
+    pub fn set_state(&mut self, state: TurtleState) {
+        self.state = state;
+    }
+
+    pub fn position(&self) -> Point {
+        self.state.position
+    }
+
+    pub fn heading(&self) -> f32 {
+        self.state.heading
+    }
+
+    pub fn set_heading(&mut self, degrees: f32) {
+        self.state.heading = degrees.rem_euclid(360.0);
+    }
+
+    /// Scale applied to vertical movement; 1 treats cells as square.
+    pub fn set_aspect(&mut self, aspect: f32) {
+        self.aspect = aspect;
+    }
+
+    pub fn forward(&mut self, distance: f32) {
+        let radians = self.state.heading.to_radians();
+        let from = self.state.position;
+        let to = Point::new(
+            from.x + radians.cos() * distance,
+            from.y - radians.sin() * distance * self.aspect,
+        );
+        self.go_to(to.x, to.y);
+    }
+
+    pub fn back(&mut self, distance: f32) {
+        self.forward(-distance);
+    }
+
+    /// Turns counter-clockwise by `degrees`.
+    pub fn left(&mut self, degrees: f32) {
+        self.set_heading(self.state.heading + degrees);
+    }
+
+    /// Turns clockwise by `degrees`.
+    pub fn right(&mut self, degrees: f32) {
+        self.set_heading(self.state.heading - degrees);
+    }
+
+    /// Moves to `(x, y)`, drawing a line if the pen is down.
+    pub fn go_to(&mut self, x: f32, y: f32) {
+        let from = self.state.position;
+        self.state.position = Point::new(x, y);
+        if self.state.pen_down && from != self.state.position {
+            self.draw_segment(from, self.state.position);
+        }
+    }
+
+    /// Moves to `(x, y)` without drawing.
+    pub fn jump_to(&mut self, x: f32, y: f32) {
+        self.state.position = Point::new(x, y);
+    }
+
+    pub fn pen_up(&mut self) {
+        self.state.pen_down = false;
+    }
+
+    pub fn pen_down(&mut self) {
+        self.state.pen_down = true;
+    }
+
+    pub fn is_pen_down(&self) -> bool {
+        self.state.pen_down
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.state.color = Some(color);
+    }
+
+    pub fn set_symbol(&mut self, symbol: char) {
+        self.state.symbol = Some(symbol);
+    }
+
+    pub fn set_stroke_width(&mut self, width: f32) {
+        self.state.stroke_width = Some(width);
+    }
+
+    /// Saves the current state, e.g. before a branch.
+    pub fn push(&mut self) {
+        self.stack.push(self.state);
+    }
+
+    /// Returns to the last pushed state; does nothing if none is saved.
+    pub fn pop(&mut self) {
+        if let Some(state) = self.stack.pop() {
+            self.state = state;
+        }
+    }
+
+    /// Strokes one line in the turtle's style, leaving the canvas' own
+    /// colour, symbol and stroke style as they were.
+    fn draw_segment(&mut self, from: Point, to: Point) {
+        let state = self.state;
+        let path = Path::new().move_to(from.x, from.y).line_to(to.x, to.y);
+        self.canvas.with_saved_pen(|canvas| {
+            if let Some(color) = state.color {
+                canvas.set_foreground_color(color);
+            }
+            if let Some(symbol) = state.symbol {
+                canvas.set_symbol(symbol);
+            }
+            if let Some(width) = state.stroke_width {
+                canvas.set_stroke_width(width);
+            }
+            canvas.stroke_path(&path);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Cell;
+
+    #[test]
+    fn popping_restores_the_canvas_style() {
+        let mut buffer = vec![Cell::default(); 20 * 10];
+        let mut canvas = Canvas::new(20, 10, &mut buffer);
+        canvas.set_symbol('#');
+        canvas.set_foreground_color(Color::Blue);
+        {
+            let mut turtle = Turtle::new(&mut canvas);
+            turtle.push();
+            turtle.set_color(Color::Red);
+            turtle.set_symbol('*');
+            turtle.forward(4.0);
+            turtle.pop();
+            turtle.right(90.0);
+            turtle.forward(5.0);
+        }
+        let cell = |x: usize, y: usize| buffer[y * 20 + x];
+        assert_eq!((cell(10, 3).symbol, cell(10, 3).fg), ('*', Color::Red));
+        assert_eq!((cell(14, 5).symbol, cell(14, 5).fg), ('#', Color::Blue));
+    }
+}